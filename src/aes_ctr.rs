@@ -6,6 +6,7 @@ use aes::cipher::{generic_array::GenericArray, StreamCipher, KeyIvInit}; // Remo
 use ctr::Ctr128BE; // Using Big Endian as is common in cryptographic contexts.
 use crate::types::SeedPK;
 use crate::params::MayoVariantParams;
+use crate::error::MayoError;
 
 /// Generates a stream of pseudo-random bytes using AES-128-CTR.
 ///
@@ -16,17 +17,12 @@ use crate::params::MayoVariantParams;
 /// * `key_bytes` - A 16-byte slice representing the AES-128 key.
 /// * `output_len` - The number of pseudo-random bytes to generate.
 ///
-/// # Panics
-/// Panics if `key_bytes` is not 16 bytes long. This is a simplification for this subtask;
-/// a production implementation should return a `Result`.
-///
 /// # Returns
-/// A `Vec<u8>` containing the generated pseudo-random bytes.
-fn aes128_ctr_generate(key_bytes: &[u8], output_len: usize) -> Vec<u8> {
+/// A `Vec<u8>` containing the generated pseudo-random bytes, or
+/// `Err(MayoError::InvalidKeyLength)` if `key_bytes` is not 16 bytes long.
+fn aes128_ctr_generate(key_bytes: &[u8], output_len: usize) -> Result<Vec<u8>, MayoError> {
     if key_bytes.len() != 16 {
-        // In a real library, this should be an error type.
-        // Aes128::new itself would also panic or error on incorrect key length.
-        panic!("AES-128 key must be 16 bytes. Provided key length: {}", key_bytes.len());
+        return Err(MayoError::InvalidKeyLength("AES-128 key must be 16 bytes"));
     }
     let key = GenericArray::from_slice(key_bytes);
     let iv = GenericArray::from_slice(&[0u8; 16]); // Standard zero IV for CTR start
@@ -37,7 +33,7 @@ fn aes128_ctr_generate(key_bytes: &[u8], output_len: usize) -> Vec<u8> {
     let mut output = vec![0u8; output_len];
     cipher.apply_keystream(&mut output);
     
-    output
+    Ok(output)
 }
 
 /// Derives the bytes for the P1 matrix component from a public key seed (`SeedPK`)
@@ -48,15 +44,18 @@ fn aes128_ctr_generate(key_bytes: &[u8], output_len: usize) -> Vec<u8> {
 /// * `params` - The MAYO variant parameters, used to determine `params.p1_bytes`.
 ///
 /// # Returns
-/// A `Vec<u8>` representing the derived `P1_bytes`.
-pub fn derive_p1_bytes(seed_pk: &SeedPK, params: &MayoVariantParams) -> Vec<u8> {
+/// A `Vec<u8>` representing the derived `P1_bytes`, `Err(MayoError::InvalidKeyLength)` if
+/// `seed_pk` is not `params.pk_seed_bytes` (16) bytes long, or an error if the output
+/// length does not equal `params.p1_bytes`.
+pub fn derive_p1_bytes(seed_pk: &SeedPK, params: &MayoVariantParams) -> Result<Vec<u8>, MayoError> {
     if seed_pk.0.len() != params.pk_seed_bytes {
-        // Ensure seed_pk length matches expected key size from params
-        // This also implicitly checks if pk_seed_bytes is 16 for AES-128
-        panic!("SeedPK length {} does not match params.pk_seed_bytes {} for AES-128 key", 
-               seed_pk.0.len(), params.pk_seed_bytes);
+        return Err(MayoError::InvalidKeyLength("SeedPK length does not match params.pk_seed_bytes"));
+    }
+    let p1_bytes = aes128_ctr_generate(&seed_pk.0, params.p1_bytes)?;
+    if p1_bytes.len() != params.p1_bytes {
+        return Err(MayoError::DimensionMismatch("P1_bytes length mismatch during derivation"));
     }
-    aes128_ctr_generate(&seed_pk.0, params.p1_bytes)
+    Ok(p1_bytes)
 }
 
 /// Derives the bytes for the P2 matrix component from a public key seed (`SeedPK`)
@@ -67,11 +66,50 @@ pub fn derive_p1_bytes(seed_pk: &SeedPK, params: &MayoVariantParams) -> Vec<u8>
 /// * `params` - The MAYO variant parameters, used to determine `params.p2_bytes`.
 ///
 /// # Returns
-/// A `Vec<u8>` representing the derived `P2_bytes`.
-pub fn derive_p2_bytes(seed_pk: &SeedPK, params: &MayoVariantParams) -> Vec<u8> {
+/// A `Vec<u8>` representing the derived `P2_bytes`, `Err(MayoError::InvalidKeyLength)` if
+/// `seed_pk` is not `params.pk_seed_bytes` (16) bytes long, or an error if the output
+/// length does not equal `params.p2_bytes`.
+pub fn derive_p2_bytes(seed_pk: &SeedPK, params: &MayoVariantParams) -> Result<Vec<u8>, MayoError> {
     if seed_pk.0.len() != params.pk_seed_bytes {
-        panic!("SeedPK length {} does not match params.pk_seed_bytes {} for AES-128 key", 
-               seed_pk.0.len(), params.pk_seed_bytes);
+        return Err(MayoError::InvalidKeyLength("SeedPK length does not match params.pk_seed_bytes"));
+    }
+    let p2_bytes = aes128_ctr_generate(&seed_pk.0, params.p2_bytes)?;
+    if p2_bytes.len() != params.p2_bytes {
+        return Err(MayoError::DimensionMismatch("P2_bytes length mismatch during derivation"));
+    }
+    Ok(p2_bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::params::MayoParams;
+
+    #[test]
+    fn test_derived_lengths_match_params() {
+        for params_enum in [MayoParams::mayo1(), MayoParams::mayo2()] {
+            let params = params_enum.variant();
+            let seed_pk = SeedPK(vec![0x42; params.pk_seed_bytes]);
+            assert_eq!(derive_p1_bytes(&seed_pk, params).unwrap().len(), params.p1_bytes);
+            assert_eq!(derive_p2_bytes(&seed_pk, params).unwrap().len(), params.p2_bytes);
+        }
+    }
+
+    #[test]
+    fn test_wrong_seed_length_is_an_error() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let expected = Err(MayoError::InvalidKeyLength("SeedPK length does not match params.pk_seed_bytes"));
+        for len in [0, params.pk_seed_bytes - 1, params.pk_seed_bytes + 1] {
+            let seed_pk = SeedPK(vec![0x42; len]);
+            assert_eq!(derive_p1_bytes(&seed_pk, params), expected);
+            assert_eq!(derive_p2_bytes(&seed_pk, params), expected);
+        }
+
+        // A variant whose seed is not an AES-128 key is rejected rather than panicking.
+        let mut odd_params = *params;
+        odd_params.pk_seed_bytes = 24;
+        let seed_pk = SeedPK(vec![0x42; 24]);
+        assert_eq!(derive_p1_bytes(&seed_pk, &odd_params), Err(MayoError::InvalidKeyLength("AES-128 key must be 16 bytes")));
     }
-    aes128_ctr_generate(&seed_pk.0, params.p2_bytes)
 }
@@ -2,11 +2,13 @@
 use wasm_bindgen::prelude::*;
 use wasm_bindgen::JsValue;
 
-use crate::types::{CompactSecretKey, CompactPublicKey, Message, Signature, ExpandedSecretKey, ExpandedPublicKey};
+use crate::types::{CompactSecretKey, CompactPublicKey, Message, Signature, SignatureOrder, ExpandedSecretKey, ExpandedPublicKey};
 use crate::params::MayoParams; // MayoVariantParams is accessed via MayoParams.variant()
 use crate::keygen::{compact_key_gen, expand_sk, expand_pk};
 use crate::sign::sign_message;
 use crate::verify::verify_signature;
+use crate::hash::message_digest;
+use crate::error::MayoError;
 
 #[wasm_bindgen(getter_with_clone)]
 pub struct KeyPairWrapper {
@@ -18,25 +20,40 @@ pub struct KeyPairWrapper {
 /// This wraps `MAYO.CompactKeyGen`.
 #[wasm_bindgen]
 pub fn keypair(mayo_variant_name: String) -> Result<KeyPairWrapper, JsValue> {
-    let params_enum = MayoParams::get_params_by_name(&mayo_variant_name).map_err(|e| JsValue::from_str(&e))?;
-    let (sk, pk) = compact_key_gen(&params_enum).map_err(|e| JsValue::from_str(e))?;
+    let params_enum = MayoParams::get_params_by_name(&mayo_variant_name).map_err(to_js_error)?;
+    let (sk, pk) = compact_key_gen(&params_enum).map_err(to_js_error)?;
     Ok(KeyPairWrapper { sk, pk })
 }
 
+#[wasm_bindgen(getter_with_clone)]
+pub struct ExpandedKeyPairWrapper {
+    pub sk: CompactSecretKey,
+    /// Expanded public key bytes: P1_bytes || P2_bytes || P3_bytes.
+    pub epk: Vec<u8>,
+}
+
+/// Generates a key pair and returns the expanded public key alongside the compact secret key,
+/// so callers verifying repeatedly do not need a separate expansion step.
+/// This wraps `keygen::keypair_expanded`.
+#[wasm_bindgen]
+pub fn keypair_expanded(mayo_variant_name: String) -> Result<ExpandedKeyPairWrapper, JsValue> {
+    let params_enum = MayoParams::get_params_by_name(&mayo_variant_name).map_err(to_js_error)?;
+    let (sk, epk) = crate::keygen::keypair_expanded(&params_enum).map_err(to_js_error)?;
+    Ok(ExpandedKeyPairWrapper { sk, epk: epk.0 })
+}
+
 /// Signs a message using a compact secret key.
 /// This involves expanding the secret key and then calling `MAYO.Sign`.
 /// The returned signature does not include the message.
 #[wasm_bindgen]
 pub fn sign(csk: &CompactSecretKey, message_bytes: &[u8], mayo_variant_name: String) -> Result<Signature, JsValue> {
-    let params_enum = MayoParams::get_params_by_name(&mayo_variant_name).map_err(|e| JsValue::from_str(&e))?;
+    let params_enum = MayoParams::get_params_by_name(&mayo_variant_name).map_err(to_js_error)?;
     // Note: The problem description mentions ExpandedSecretKey is not used by sign.
     // However, the provided function signature for sign_message in sign.rs *does* take ExpandedSecretKey.
     // Algorithm 8 (MAYO.Sign) takes esk as input.
     // Algorithm 3 (NIST API Sign) takes sk (csk) as input, implying internal expansion.
     // So, expanding sk to esk here is correct.
-    let esk: ExpandedSecretKey = expand_sk(csk, &params_enum).map_err(|e_str| JsValue::from_str(e_str))?; // Assuming expand_sk returns &'static str
-    let message_to_sign = Message(message_bytes.to_vec());
-    sign_message(&esk, &message_to_sign, &params_enum).map_err(|e_string| JsValue::from_str(&e_string)) // sign_message now returns String
+    sign_checked(csk, &Message(message_bytes.to_vec()), &params_enum).map_err(to_js_error)
 }
 
 /// Verifies a signature on a "signed message" and recovers the original message if valid.
@@ -44,37 +61,119 @@ pub fn sign(csk: &CompactSecretKey, message_bytes: &[u8], mayo_variant_name: Str
 /// Assumes `signed_message` is `signature_bytes || original_message_bytes`.
 #[wasm_bindgen]
 pub fn open(cpk: &CompactPublicKey, signed_message: &[u8], mayo_variant_name: String) -> Result<Option<Message>, JsValue> {
-    let params_enum = MayoParams::get_params_by_name(&mayo_variant_name).map_err(|e| JsValue::from_str(&e))?;
-    let params = params_enum.variant();
-    
-    // Determine signature length: s_bytes_len (n elements) + salt_bytes
-    let s_bytes_len = MayoParams::bytes_for_gf16_elements(params.n);
-    let expected_sig_len = s_bytes_len + params.salt_bytes;
+    open_with_order(cpk, signed_message, mayo_variant_name, SignatureOrder::SignatureFirst)
+}
 
-    if signed_message.len() < expected_sig_len {
-        return Err(JsValue::from_str("Signed message is too short to contain a signature"));
-    }
+/// Like `open`, for signed messages laid out as described by `order`.
+#[wasm_bindgen]
+pub fn open_with_order(cpk: &CompactPublicKey, signed_message: &[u8], mayo_variant_name: String, order: SignatureOrder) -> Result<Option<Message>, JsValue> {
+    let params_enum = MayoParams::get_params_by_name(&mayo_variant_name).map_err(to_js_error)?;
+    open_checked(cpk, signed_message, order, &params_enum).map_err(to_js_error)
+}
+
+/// Hands a `MayoError` to JavaScript as its `Display` text.
+fn to_js_error(error: MayoError) -> JsValue {
+    JsValue::from_str(&error.to_string())
+}
 
-    let sig_bytes = &signed_message[0..expected_sig_len];
-    let message_bytes = &signed_message[expected_sig_len..];
+/// Runs `operation`, turning a panic into `Err(MayoError::InternalPanic)` instead of unwinding
+/// into the caller. This is a safety net for malformed untrusted input, not a substitute for
+/// returning errors. It has no effect when the crate is built with `panic = "abort"`.
+fn catch_internal_panic<T>(operation: impl FnOnce() -> Result<T, MayoError>) -> Result<T, MayoError> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(operation))
+        .unwrap_or_else(|_| Err(MayoError::InternalPanic))
+}
 
-    let signature = Signature(sig_bytes.to_vec());
-    let original_message = Message(message_bytes.to_vec());
+/// Native counterpart of `sign`: expands `csk` and signs `message`, converting any internal
+/// panic into an error.
+pub fn sign_checked(csk: &CompactSecretKey, message: &Message, params_enum: &MayoParams) -> Result<Signature, MayoError> {
+    catch_internal_panic(|| {
+        // Note: The problem description mentions ExpandedSecretKey is not used by sign.
+        // However, the provided function signature for sign_message in sign.rs *does* take ExpandedSecretKey.
+        // Algorithm 8 (MAYO.Sign) takes esk as input.
+        // Algorithm 3 (NIST API Sign) takes sk (csk) as input, implying internal expansion.
+        // So, expanding sk to esk here is correct.
+        // The esk holds O and L in the clear; it is wiped when dropped at the end of this closure.
+        let esk: ExpandedSecretKey = expand_sk(csk, params_enum)?;
+        sign_message(&esk, message, params_enum)
+    })
+}
 
-    // Note: The problem description mentions ExpandedPublicKey is not used by verify.
-    // However, the provided function signature for verify_signature in verify.rs *does* take ExpandedPublicKey.
-    // Algorithm 9 (MAYO.Verify) takes epk as input.
-    // Algorithm 4 (NIST API Verify/Open) takes pk (cpk) as input, implying internal expansion.
-    // So, expanding pk to epk here is correct.
-    let epk: ExpandedPublicKey = expand_pk(cpk, &params_enum).map_err(|e_str| JsValue::from_str(e_str))?; // Assuming expand_pk returns &'static str
-    
-    match verify_signature(&epk, &original_message, &signature, &params_enum) {
-        Ok(true) => Ok(Some(original_message)), // Valid signature, return message
-        Ok(false) => Ok(None),                  // Invalid signature
-        Err(e_string) => Err(JsValue::from_str(&e_string)),      // verify_signature now returns String
-    }
+/// Signs `message` with `csk` and immediately verifies the signature against the public key
+/// derived from `csk`, e.g. to confirm a freshly provisioned key works before storing it.
+/// Returns `Ok(false)` if the signature does not verify; errors from signing are returned as is.
+pub fn sign_and_verify_selftest(csk: &CompactSecretKey, message: &Message, params_enum: &MayoParams) -> Result<bool, MayoError> {
+    let signature = sign_checked(csk, message, params_enum)?;
+    catch_internal_panic(|| {
+        let cpk = csk.public_key(params_enum)?;
+        let epk = expand_pk(&cpk, params_enum)?;
+        verify_signature(&epk, message, &signature, params_enum)
+    })
 }
 
+/// Native counterpart of `open_with_order`, converting any internal panic into an error.
+/// Returns `Ok(Some(message))` for a valid signature and `Ok(None)` for an invalid one.
+pub fn open_checked(cpk: &CompactPublicKey, signed_message: &[u8], order: SignatureOrder, params_enum: &MayoParams) -> Result<Option<Message>, MayoError> {
+    catch_internal_panic(|| {
+        let (signature, original_message) = split_signed_message(signed_message, order, params_enum)?;
+
+        // Note: The problem description mentions ExpandedPublicKey is not used by verify.
+        // However, the provided function signature for verify_signature in verify.rs *does* take ExpandedPublicKey.
+        // Algorithm 9 (MAYO.Verify) takes epk as input.
+        // Algorithm 4 (NIST API Verify/Open) takes pk (cpk) as input, implying internal expansion.
+        // So, expanding pk to epk here is correct.
+        let epk: ExpandedPublicKey = expand_pk(cpk, params_enum)?;
+        let valid = verify_signature(&epk, &original_message, &signature, params_enum)?;
+        Ok(valid.then_some(original_message))
+    })
+}
+
+/// `verify_signature`, converting any internal panic into an error.
+pub fn verify_checked(epk: &ExpandedPublicKey, message: &Message, signature: &Signature, params_enum: &MayoParams) -> Result<bool, MayoError> {
+    catch_internal_panic(|| verify_signature(epk, message, signature, params_enum))
+}
+
+/// Lists the variant names accepted by every `mayo_variant_name` argument, as a JS array of strings.
+#[wasm_bindgen]
+pub fn list_variants() -> JsValue {
+    let names: Vec<String> = MayoParams::supported_variant_names().into_iter().map(String::from).collect();
+    JsValue::from(names)
+}
+
+/// Computes the SHAKE256 message digest that `sign` and `open` derive the target from.
+/// Its length is the variant's `digest_bytes`. Native callers can use `hash::message_digest`.
+#[wasm_bindgen]
+pub fn digest_message(message_bytes: &[u8], mayo_variant_name: String) -> Result<Vec<u8>, JsValue> {
+    let params_enum = MayoParams::get_params_by_name(&mayo_variant_name).map_err(to_js_error)?;
+    Ok(message_digest(&Message(message_bytes.to_vec()), &params_enum).0)
+}
+
+/// Determines the variant of a signature serialized with `Signature::to_tagged_bytes`,
+/// checking that its length matches that variant.
+pub fn detect_variant_from_tagged_signature(bytes: &[u8]) -> Result<MayoParams, MayoError> {
+    Signature::from_tagged_bytes(bytes).map(|(_, params_enum)| params_enum)
+}
+
+/// Splits a signed message blob into its signature and message, according to `order`.
+/// The signature length is fixed by the variant: s_bytes (k * n elements) + salt_bytes.
+pub fn split_signed_message(signed_message: &[u8], order: SignatureOrder, params_enum: &MayoParams) -> Result<(Signature, Message), MayoError> {
+    let expected_sig_len = params_enum.variant().sig_bytes();
+    if signed_message.len() < expected_sig_len {
+        return Err(MayoError::InvalidSignatureLength("Signed message is too short to contain a signature"));
+    }
+
+    let (sig_bytes, message_bytes) = match order {
+        SignatureOrder::SignatureFirst => {
+            let (sig, msg) = signed_message.split_at(expected_sig_len);
+            (sig, msg)
+        }
+        SignatureOrder::MessageFirst => {
+            let (msg, sig) = signed_message.split_at(signed_message.len() - expected_sig_len);
+            (sig, msg)
+        }
+    };
+    Ok((Signature(sig_bytes.to_vec()), Message(message_bytes.to_vec())))
+}
 
 #[cfg(test)]
 mod tests {
@@ -92,7 +191,7 @@ mod tests {
         let csk1 = wrapper1.sk;
         let cpk1 = wrapper1.pk;
         let params_mayo1 = MayoParams::mayo1(); // For assertion values
-        let params_mayo1_variant = params_mayo1.variant();
+        
         assert_eq!(csk1.0.len(), params_mayo1.sk_seed_bytes());
         // Use hardcoded P3 size for Mayo1 due to HACK in codec::encode_p3_matrices
         assert_eq!(cpk1.0.len(), params_mayo1.pk_seed_bytes() + 1152);
@@ -106,10 +205,114 @@ mod tests {
         let csk2 = wrapper2.sk;
         let cpk2 = wrapper2.pk;
         let params_mayo2 = MayoParams::mayo2(); // For assertion values
-        let params_mayo2_variant = params_mayo2.variant();
+        
         assert_eq!(csk2.0.len(), params_mayo2.sk_seed_bytes());
-        // Use hardcoded P3 size for Mayo2 due to HACK in codec::encode_p3_matrices
-        assert_eq!(cpk2.0.len(), params_mayo2.pk_seed_bytes() + 5504);
+        // P3 for MAYO2: 64 upper-triangular 18x18 matrices, 64 * 171 / 2 bytes
+        assert_eq!(cpk2.0.len(), params_mayo2.pk_seed_bytes() + 5472);
+    }
+
+    #[test]
+    fn test_keypair_expanded_api() {
+        let params_enum = MayoParams::mayo1();
+        let wrapper = keypair_expanded("mayo1".to_string()).expect("keypair_expanded failed");
+        let epk = ExpandedPublicKey(wrapper.epk);
+        assert_eq!(epk.0.len(), params_enum.variant().epk_bytes());
+
+        // Same epk as expanding the public key derived from the secret key
+        let cpk = wrapper.sk.public_key(&params_enum).unwrap();
+        assert_eq!(expand_pk(&cpk, &params_enum).unwrap(), epk);
+
+        let message = Message(b"signed under the expanded key pair".to_vec());
+        let signature = sign_checked(&wrapper.sk, &message, &params_enum).expect("signing failed");
+        assert_eq!(verify_signature(&epk, &message, &signature, &params_enum), Ok(true));
+    }
+
+    #[test]
+    fn test_digest_message_api() {
+        let message_bytes = b"message to digest";
+        for name in ["mayo1", "mayo2"] {
+            let params_enum = MayoParams::get_params_by_name(name).unwrap();
+            let digest = digest_message(message_bytes, name.to_string()).unwrap_or_else(|_| panic!("digest_message failed for {}", name));
+            assert_eq!(digest.len(), params_enum.digest_bytes());
+            // Must match the digest sign_message and verify_signature compute internally
+            assert_eq!(digest, message_digest(&Message(message_bytes.to_vec()), &params_enum).0);
+            assert_eq!(digest, crate::hash::shake256_digest(message_bytes, &params_enum).0);
+        }
+    }
+
+    #[test]
+    fn test_open_message_first_order() {
+        let mayo1_name = "mayo1".to_string();
+        let params_enum = MayoParams::mayo1();
+        let wrapper = keypair(mayo1_name.clone()).expect("keypair generation failed");
+        let sig_bytes: Vec<u8> = (0..params_enum.variant().sig_bytes()).map(|i| i as u8).collect();
+        let message_bytes = b"message placed before its signature".to_vec();
+
+        let mut sig_first = sig_bytes.clone();
+        sig_first.extend_from_slice(&message_bytes);
+        let mut msg_first = message_bytes.clone();
+        msg_first.extend_from_slice(&sig_bytes);
+
+        let expected = (Signature(sig_bytes.clone()), Message(message_bytes.clone()));
+        assert_eq!(split_signed_message(&sig_first, SignatureOrder::SignatureFirst, &params_enum), Ok(expected.clone()));
+        assert_eq!(split_signed_message(&msg_first, SignatureOrder::MessageFirst, &params_enum), Ok(expected));
+        assert_eq!(split_signed_message(&msg_first[..sig_bytes.len() - 1], SignatureOrder::MessageFirst, &params_enum),
+                   Err(MayoError::InvalidSignatureLength("Signed message is too short to contain a signature")));
+
+        // Both layouts reach the same verification verdict
+        let verdict_sig_first = open(&wrapper.pk, &sig_first, mayo1_name.clone()).expect("open failed");
+        let verdict_msg_first = open_with_order(&wrapper.pk, &msg_first, mayo1_name, SignatureOrder::MessageFirst).expect("open_with_order failed");
+        assert_eq!(verdict_sig_first, verdict_msg_first);
+    }
+
+    #[test]
+    fn test_checked_entry_points_return_errors() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let message = Message(b"malformed key material".to_vec());
+        let signature = Signature(vec![0u8; params.sig_bytes()]);
+
+        // Truncated and oversized epk bytes are reported, not panicked on.
+        for epk_len in [0, params.p1_bytes, params.epk_bytes() + 1] {
+            let epk = ExpandedPublicKey(vec![0xFF; epk_len]);
+            assert_eq!(verify_checked(&epk, &message, &signature, &params_enum), Err(MayoError::InvalidKeyLength("Expanded public key has incorrect length")));
+        }
+
+        let bad_cpk = CompactPublicKey(vec![0xFF; 3]);
+        let mut signed_message = signature.0.clone();
+        signed_message.extend_from_slice(&message.0);
+        assert!(open_checked(&bad_cpk, &signed_message, SignatureOrder::SignatureFirst, &params_enum).is_err());
+        assert!(sign_checked(&CompactSecretKey(vec![0u8; 1]), &message, &params_enum).is_err());
+
+        // A panic inside the wrapped operation becomes an error.
+        let caught: Result<(), MayoError> = catch_internal_panic(|| panic!("simulated decoder panic"));
+        assert_eq!(caught, Err(MayoError::InternalPanic));
+    }
+
+    #[test]
+    fn test_sign_checked_still_signs_with_esk_wipe() {
+        let params_enum = MayoParams::mayo1();
+        let message = Message(b"wiped after signing".to_vec());
+        let (csk, cpk) = compact_key_gen(&params_enum).unwrap();
+        let signature = sign_checked(&csk, &message, &params_enum).unwrap();
+        let epk = expand_pk(&cpk, &params_enum).unwrap();
+        assert_eq!(verify_signature(&epk, &message, &signature, &params_enum), Ok(true));
+
+        let mut esk = expand_sk(&csk, &params_enum).unwrap();
+        let len = esk.0.len();
+        esk.wipe();
+        assert_eq!(esk.0, vec![0u8; len]);
+    }
+
+    #[test]
+    fn test_sign_and_verify_selftest() {
+        let params_enum = MayoParams::mayo1();
+        let message = Message(b"provisioning self-test".to_vec());
+        let (csk, _) = compact_key_gen(&params_enum).unwrap();
+        assert_eq!(sign_and_verify_selftest(&csk, &message, &params_enum), Ok(true), "a freshly generated key failed its self-test");
+
+        let corrupted = CompactSecretKey(csk.0[1..].to_vec());
+        assert!(sign_and_verify_selftest(&corrupted, &message, &params_enum).is_err());
     }
 
     #[cfg(target_arch = "wasm32")]
@@ -121,20 +324,7 @@ mod tests {
         let message_bytes = b"test message for sign api"; // Use bytes directly
 
         let sign_result = sign(&csk, message_bytes, mayo1_name.clone());
-        // sign_message now returns Result<Signature, String>.
-        // If it fails, it should be the detailed error string.
-        match sign_result {
-            Err(e) => {
-                let error_string = e.as_string().expect("Error should be a string from JsValue");
-                assert!(error_string.starts_with("MAYO.Sign failed after maximum retries") || error_string.contains("Solver error"),
-                        "Expected detailed sign failure, got: {}", error_string);
-            }
-            Ok(_) => {
-                // This case might occur if, by sheer chance, a solution is found.
-                // It's less likely with MAX_SIGN_RETRIES, but possible.
-                // println!("Sign API test unexpectedly succeeded."); 
-            }
-        }
+        assert!(sign_result.is_ok(), "sign failed: {:?}", sign_result.err().and_then(|e| e.as_string()));
     }
 
     #[cfg(target_arch = "wasm32")]
@@ -146,8 +336,7 @@ mod tests {
         
         let params_enum_for_test = MayoParams::get_params_by_name(&mayo1_name).unwrap();
         let params_variant = params_enum_for_test.variant();
-        let s_bytes_len = MayoParams::bytes_for_gf16_elements(params_variant.n);
-        let expected_sig_len = s_bytes_len + params_variant.salt_bytes;
+        let expected_sig_len = params_variant.sig_bytes();
         
         let dummy_sig_bytes = vec![0u8; expected_sig_len];
         let original_message_text = b"test message for open api";
@@ -156,7 +345,7 @@ mod tests {
         signed_message_bytes.extend_from_slice(original_message_text);
         
         let open_result = open(&cpk, &signed_message_bytes, mayo1_name.clone());
-        // verify_signature now returns Result<bool, String>
+        // verify_signature now returns Result<bool, MayoError>
         // If it fails, it should be the detailed error string.
         match open_result {
             Err(e) => {
@@ -181,8 +370,7 @@ mod tests {
         let cpk = wrapper.pk;
         let params_enum_for_test = MayoParams::get_params_by_name(&mayo1_name).unwrap(); 
         let params_variant = params_enum_for_test.variant();
-        let s_bytes_len = MayoParams::bytes_for_gf16_elements(params_variant.n);
-        let expected_sig_len = s_bytes_len + params_variant.salt_bytes;
+        let expected_sig_len = params_variant.sig_bytes();
         let short_signed_message = vec![0u8; expected_sig_len - 1]; // One byte too short
         
         let open_result = open(&cpk, &short_signed_message, mayo1_name.clone());
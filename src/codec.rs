@@ -3,34 +3,51 @@
 
 use crate::types::{GFElement, GFMatrix, GFVector};
 use crate::params::{MayoVariantParams}; // MayoParams enum not directly needed here if we pass MayoVariantParams
+use crate::error::MayoError;
 // For GFMatrix::new_with_data, we need to import GFMatrix itself if methods are not on it.
 // However, GFMatrix::new_with_data was defined in matrix.rs as part of `impl GFMatrix`.
 // So, we just need GFMatrix type from types.rs.
 
+/// Packs nibble values two per byte: the even-index nibble goes in the high half,
+/// the odd-index one in the low half. An odd trailing nibble leaves the low half zero.
+/// This is the single nibble convention used for every byte <-> GF(16) conversion in the crate.
+pub fn pack_nibbles(nibbles: &[u8]) -> Vec<u8> {
+    nibbles
+        .chunks(2)
+        .map(|pair| {
+            let high = (pair[0] & 0x0F) << 4;
+            let low = pair.get(1).map_or(0, |n| n & 0x0F);
+            high | low
+        })
+        .collect()
+}
+
+/// Inverse of `pack_nibbles`: unpacks the first `count` nibbles of `bytes`, high half first.
+/// Returns `None` if `bytes` holds fewer than `count` nibbles.
+pub fn unpack_nibbles(bytes: &[u8], count: usize) -> Option<Vec<u8>> {
+    if bytes.len() < count.div_ceil(2) {
+        return None;
+    }
+    Some(
+        (0..count)
+            .map(|i| {
+                let byte_val = bytes[i / 2];
+                if i % 2 == 0 { byte_val >> 4 } else { byte_val & 0x0F }
+            })
+            .collect(),
+    )
+}
+
 /// Encodes a vector of GF(16) elements (nibbles) into a byte vector.
-/// Two GFElement (0-15) are packed into each byte.
+/// Two GFElement (0-15) are packed into each byte, using `pack_nibbles`.
 /// If there's an odd number of elements, the last nibble of the last byte is zero-padded.
 pub fn encode_gf_elements(elements: &GFVector) -> Vec<u8> {
-    let num_elements = elements.len();
-    let num_bytes = (num_elements + 1) / 2;
-    let mut bytes = vec![0u8; num_bytes];
-
-    for i in 0..num_elements {
-        let element_val = elements[i].0 & 0x0F; // Ensure it's a nibble
-        let byte_idx = i / 2;
-        if i % 2 == 0 {
-            // High nibble for even index
-            bytes[byte_idx] = element_val << 4;
-        } else {
-            // Low nibble for odd index
-            bytes[byte_idx] |= element_val;
-        }
-    }
-    bytes
+    let nibbles: Vec<u8> = elements.iter().map(|e| e.0).collect();
+    pack_nibbles(&nibbles)
 }
 
 /// Decodes a byte vector into a GFVector of a specified number of GF(16) elements.
-/// Unpacks two GFElement (nibbles) from each byte.
+/// Unpacks two GFElement (nibbles) from each byte, using `unpack_nibbles`.
 ///
 /// # Arguments
 /// * `bytes` - The byte slice to decode.
@@ -38,30 +55,28 @@ pub fn encode_gf_elements(elements: &GFVector) -> Vec<u8> {
 ///
 /// # Returns
 /// `Ok(GFVector)` if successful, or `Err` if `bytes` length is insufficient for `num_elements`.
-pub fn decode_gf_elements(bytes: &[u8], num_elements: usize) -> Result<GFVector, &'static str> {
-    let expected_num_bytes = (num_elements + 1) / 2;
-    if bytes.len() < expected_num_bytes {
-        return Err("Insufficient bytes to decode the specified number of GF elements");
-    }
-
-    let mut elements = Vec::with_capacity(num_elements);
-    for i in 0..num_elements {
-        let byte_idx = i / 2;
-        let byte_val = bytes[byte_idx];
-        if i % 2 == 0 {
-            // High nibble for even index
-            elements.push(GFElement((byte_val >> 4) & 0x0F));
-        } else {
-            // Low nibble for odd index
-            elements.push(GFElement(byte_val & 0x0F));
-        }
-    }
-    Ok(elements)
+pub fn decode_gf_elements(bytes: &[u8], num_elements: usize) -> Result<GFVector, MayoError> {
+    let nibbles = unpack_nibbles(bytes, num_elements)
+        .ok_or(MayoError::DecodeError("Insufficient bytes to decode the specified number of GF elements"))?;
+    Ok(nibbles.into_iter().map(GFElement).collect())
+}
+
+/// Decodes the m-element target vector t from SHAKE256 output.
+/// Sign and verify both go through this, so they interpret the hash output identically.
+pub fn decode_target_vector(t_bytes: &[u8], m: usize) -> Result<GFVector, MayoError> {
+    decode_gf_elements(t_bytes, m)
+}
+
+/// Like `decode_gf_elements`, but also returns the number of bytes consumed
+/// (`(num_elements + 1) / 2`), so concatenated fields can be parsed with a cursor.
+pub fn decode_gf_elements_counted(bytes: &[u8], num_elements: usize) -> Result<(GFVector, usize), MayoError> {
+    let elements = decode_gf_elements(bytes, num_elements)?;
+    Ok((elements, num_elements.div_ceil(2)))
 }
 
 /// Decodes the O matrix from its byte representation.
 /// Matrix O is `(n-o) x o`.
-pub fn decode_o_matrix(o_bytes: &[u8], params: &MayoVariantParams) -> Result<GFMatrix, &'static str> {
+pub fn decode_o_matrix(o_bytes: &[u8], params: &MayoVariantParams) -> Result<GFMatrix, MayoError> {
     let rows = params.n - params.o;
     let cols = params.o;
     let num_elements = rows * cols;
@@ -73,9 +88,9 @@ pub fn decode_o_matrix(o_bytes: &[u8], params: &MayoVariantParams) -> Result<GFM
     // if params.o_bytes is used to calculate num_elements.
     // However, num_elements here is calculated from matrix dimensions.
     // We should check if o_bytes *can* provide num_elements.
-    let expected_byte_len = (num_elements + 1) / 2;
+    let expected_byte_len = num_elements.div_ceil(2);
     if o_bytes.len() < expected_byte_len {
-         return Err("Insufficient o_bytes to decode O matrix based on calculated dimensions");
+         return Err(MayoError::DecodeError("Insufficient o_bytes to decode O matrix based on calculated dimensions"));
     }
     // If params.o_bytes is also a field in MayoVariantParams, we should use/check against it.
     // Assuming params.o_bytes is the definitive length of the input slice for O.
@@ -94,9 +109,9 @@ pub fn decode_o_matrix(o_bytes: &[u8], params: &MayoVariantParams) -> Result<GFM
 
 // Helper for decoding upper triangular matrices
 // Fills an (n x n) matrix from a list of (n*(n+1)/2) elements for its upper triangular part.
-fn decode_upper_triangular_matrix(elements: &GFVector, size: usize) -> Result<GFMatrix, &'static str> {
+fn decode_upper_triangular_matrix(elements: &GFVector, size: usize) -> Result<GFMatrix, MayoError> {
     if elements.len() != size * (size + 1) / 2 {
-        return Err("Incorrect number of elements for upper triangular matrix");
+        return Err(MayoError::DecodeError("Incorrect number of elements for upper triangular matrix"));
     }
     let mut matrix = GFMatrix::zero(size, size);
     let mut k = 0;
@@ -109,72 +124,80 @@ fn decode_upper_triangular_matrix(elements: &GFVector, size: usize) -> Result<GF
     Ok(matrix)
 }
 
+/// Encodes the upper triangular part of a square matrix into bytes.
+/// The elements are taken row by row (r <= c), matching `decode_upper_triangular_matrix`.
+/// Returns Err if the matrix is not square or has a nonzero entry below the diagonal,
+/// since those entries would otherwise be silently dropped.
+pub fn encode_upper_triangular(matrix: &GFMatrix) -> Result<Vec<u8>, MayoError> {
+    Ok(encode_gf_elements(&upper_triangular_elements(matrix)?))
+}
+
+// Collects the upper triangle of a square matrix row by row, rejecting nonzero entries below it.
+fn upper_triangular_elements(matrix: &GFMatrix) -> Result<GFVector, MayoError> {
+    if matrix.num_rows() != matrix.num_cols() {
+        return Err(MayoError::DimensionMismatch("Matrix must be square to encode as upper triangular"));
+    }
+    let size = matrix.num_rows();
+    let mut elements = Vec::with_capacity(size * (size + 1) / 2);
+    for r in 0..size {
+        for c in 0..size {
+            let val = matrix.get_unsafe(r, c);
+            if c >= r {
+                elements.push(val);
+            } else if val.0 != 0 {
+                return Err(MayoError::InvalidInput("Matrix has nonzero entries below the diagonal"));
+            }
+        }
+    }
+    Ok(elements)
+}
+
+
+// Helper for the P1/P2/P3 decoders: the m matrices are packed as one nibble stream, matrix
+// after matrix, holding only the upper triangle when `upper_triangular` is set. This is the
+// spec's packed length, so only the final byte of the whole set can carry a padding nibble.
+fn decode_matrix_set(bytes: &[u8], rows: usize, cols: usize, upper_triangular: bool, params: &MayoVariantParams) -> Result<Vec<GFMatrix>, MayoError> {
+    let elements_per_mat = if upper_triangular { rows * (rows + 1) / 2 } else { rows * cols };
+    let elements = decode_gf_elements(bytes, params.m * elements_per_mat)?;
+    let mut matrices = Vec::with_capacity(params.m);
+    for mat_elements in elements.chunks_exact(elements_per_mat) {
+        let matrix = if upper_triangular {
+            decode_upper_triangular_matrix(&mat_elements.to_vec(), rows)?
+        } else {
+            GFMatrix::new_with_data(rows, cols, mat_elements.to_vec())
+        };
+        matrices.push(matrix);
+    }
+    Ok(matrices)
+}
 
 /// Decodes P1 matrices from byte representation.
 /// P1 consists of `m` matrices, each P(1)i is `(n-o) x (n-o)` and upper triangular.
-/// Assumes simple concatenation of the packed representations of each P(1)i.
-pub fn decode_p1_matrices(p1_bytes: &[u8], params: &MayoVariantParams) -> Result<Vec<GFMatrix>, &'static str> {
+/// The upper triangles are packed back to back as one stream of `params.p1_bytes` bytes.
+pub fn decode_p1_matrices(p1_bytes: &[u8], params: &MayoVariantParams) -> Result<Vec<GFMatrix>, MayoError> {
     if p1_bytes.len() != params.p1_bytes {
-        return Err("p1_bytes length does not match params.p1_bytes field");
+        return Err(MayoError::InvalidKeyLength("p1_bytes length does not match params.p1_bytes field"));
     }
-    let m = params.m;
-    let bytes_per_p1_mat = params.p1_bytes / m;
     let size_p1_mat = params.n - params.o;
-    let num_elements_per_p1_mat_upper_tri = size_p1_mat * (size_p1_mat + 1) / 2;
-    let mut p1_matrices = Vec::with_capacity(m);
-    for i in 0..m {
-        let start_byte = i * bytes_per_p1_mat;
-        let end_byte = start_byte + bytes_per_p1_mat;
-        let mat_bytes = &p1_bytes[start_byte..end_byte];
-        let elements = decode_gf_elements(mat_bytes, num_elements_per_p1_mat_upper_tri)?;
-        let p1_mat = decode_upper_triangular_matrix(&elements, size_p1_mat)?;
-        p1_matrices.push(p1_mat);
-    }
-    Ok(p1_matrices)
+    decode_matrix_set(p1_bytes, size_p1_mat, size_p1_mat, true, params)
 }
 
 /// Decodes P2 matrices from byte representation.
 /// P2 consists of `m` matrices, each P(2)i is `(n-o) x o`.
-pub fn decode_p2_matrices(p2_bytes: &[u8], params: &MayoVariantParams) -> Result<Vec<GFMatrix>, &'static str> {
+pub fn decode_p2_matrices(p2_bytes: &[u8], params: &MayoVariantParams) -> Result<Vec<GFMatrix>, MayoError> {
     if p2_bytes.len() != params.p2_bytes {
-        return Err("p2_bytes length does not match params.p2_bytes field");
-    }
-    let m = params.m;
-    let bytes_per_p2_mat = params.p2_bytes / m;
-    let rows_p2 = params.n - params.o;
-    let cols_p2 = params.o;
-    let num_elements_per_p2_mat = rows_p2 * cols_p2;
-    let mut p2_matrices = Vec::with_capacity(m);
-    for i in 0..m {
-        let start_byte = i * bytes_per_p2_mat;
-        let end_byte = start_byte + bytes_per_p2_mat;
-        let mat_bytes = &p2_bytes[start_byte..end_byte];
-        let elements = decode_gf_elements(mat_bytes, num_elements_per_p2_mat)?;
-        p2_matrices.push(GFMatrix::new_with_data(rows_p2, cols_p2, elements));
-    }
-    Ok(p2_matrices)
+        return Err(MayoError::InvalidKeyLength("p2_bytes length does not match params.p2_bytes field"));
+    }
+    decode_matrix_set(p2_bytes, params.n - params.o, params.o, false, params)
 }
 
 /// Decodes P3 matrices from byte representation.
 /// P3 consists of `m` matrices, each P(3)i is `o x o` and upper triangular.
-pub fn decode_p3_matrices(p3_bytes: &[u8], params: &MayoVariantParams) -> Result<Vec<GFMatrix>, &'static str> {
+pub fn decode_p3_matrices(p3_bytes: &[u8], params: &MayoVariantParams) -> Result<Vec<GFMatrix>, MayoError> {
     if p3_bytes.len() != params.p3_bytes {
-        return Err("p3_bytes length does not match params.p3_bytes field");
-    }
-    let m = params.m;
-    let bytes_per_p3_mat = params.p3_bytes / m;
-    let size_p3_mat = params.o;
-    let num_elements_per_p3_mat_upper_tri = size_p3_mat * (size_p3_mat + 1) / 2;
-    let mut p3_matrices = Vec::with_capacity(m);
-    for i in 0..m {
-        let start_byte = i * bytes_per_p3_mat;
-        let end_byte = start_byte + bytes_per_p3_mat;
-        let mat_bytes = &p3_bytes[start_byte..end_byte];
-        let elements = decode_gf_elements(mat_bytes, num_elements_per_p3_mat_upper_tri)?;
-        let p3_mat = decode_upper_triangular_matrix(&elements, size_p3_mat)?;
-        p3_matrices.push(p3_mat);
-    }
-    Ok(p3_matrices)
+        return Err(MayoError::InvalidKeyLength("p3_bytes length does not match params.p3_bytes field"));
+    }
+    decode_matrix_set(p3_bytes, params.o, params.o, true, params)
 }
 
 /// Decodes L matrices from byte representation. (Not typically stored/decoded directly in MAYO standard)
@@ -182,7 +205,7 @@ pub fn decode_p3_matrices(p3_bytes: &[u8], params: &MayoVariantParams) -> Result
 /// This function is provided as per subtask, but its usage in MAYO needs clarification.
 /// If L matrices are derived during verification and not directly part of keys/signatures,
 /// this might not be used in the main flow.
-pub fn decode_l_matrices(l_bytes: &[u8], params: &MayoVariantParams) -> Result<Vec<GFMatrix>, &'static str> {
+pub fn decode_l_matrices(l_bytes: &[u8], params: &MayoVariantParams) -> Result<Vec<GFMatrix>, MayoError> {
     let rows_l = params.n - params.o;
     let cols_l = params.o;
     let num_elements_per_l_mat = rows_l * cols_l;
@@ -198,25 +221,132 @@ pub fn decode_l_matrices(l_bytes: &[u8], params: &MayoVariantParams) -> Result<V
     Ok(l_matrices)
 }
 
+/// Encodes the `(n-o) x o` matrix O into `params.o_bytes` bytes, the inverse of `decode_o_matrix`.
+pub fn encode_o_matrix(o_matrix: &GFMatrix, params: &MayoVariantParams) -> Result<Vec<u8>, MayoError> {
+    if o_matrix.num_rows() != params.n - params.o || o_matrix.num_cols() != params.o {
+        return Err(MayoError::DimensionMismatch("O matrix has incorrect dimensions"));
+    }
+    let o_bytes = encode_gf_elements(&o_matrix.data);
+    if o_bytes.len() != params.o_bytes {
+        return Err(MayoError::DimensionMismatch("Encoded O matrix does not match params.o_bytes"));
+    }
+    Ok(o_bytes)
+}
+
+// Helper for the P1/P2/P3 encoders, the inverse of `decode_matrix_set`: collects the elements
+// of all m matrices (only the upper triangle when `upper_triangular` is set) and packs them as
+// one stream of `expected_len` bytes.
+fn encode_matrix_set(matrices: &[GFMatrix], rows: usize, cols: usize, upper_triangular: bool, expected_len: usize, params: &MayoVariantParams) -> Result<Vec<u8>, MayoError> {
+    if matrices.len() != params.m {
+        return Err(MayoError::DimensionMismatch("Incorrect number of matrices to encode"));
+    }
+    let mut elements: GFVector = Vec::with_capacity(2 * expected_len);
+    for matrix in matrices {
+        if matrix.num_rows() != rows || matrix.num_cols() != cols {
+            return Err(MayoError::DimensionMismatch("Matrix to encode has incorrect dimensions"));
+        }
+        if upper_triangular {
+            elements.extend_from_slice(&upper_triangular_elements(matrix)?);
+        } else {
+            elements.extend_from_slice(&matrix.data);
+        }
+    }
+    let bytes = encode_gf_elements(&elements);
+    if bytes.len() != expected_len {
+        return Err(MayoError::DimensionMismatch("Encoded matrices do not match the params byte length"));
+    }
+    Ok(bytes)
+}
+
+/// Encodes the m upper-triangular `(n-o) x (n-o)` P1 matrices into `params.p1_bytes` bytes,
+/// the inverse of `decode_p1_matrices`. Fails on a nonzero entry below the diagonal.
+pub fn encode_p1_matrices(p1_matrices: &[GFMatrix], params: &MayoVariantParams) -> Result<Vec<u8>, MayoError> {
+    let size = params.n - params.o;
+    encode_matrix_set(p1_matrices, size, size, true, params.p1_bytes, params)
+}
+
+/// Encodes the m `(n-o) x o` P2 matrices into `params.p2_bytes` bytes, the inverse of `decode_p2_matrices`.
+pub fn encode_p2_matrices(p2_matrices: &[GFMatrix], params: &MayoVariantParams) -> Result<Vec<u8>, MayoError> {
+    encode_matrix_set(p2_matrices, params.n - params.o, params.o, false, params.p2_bytes, params)
+}
+
+/// Encodes the m upper-triangular `o x o` P3 matrices into `params.p3_bytes` bytes,
+/// the inverse of `decode_p3_matrices`. Fails on a nonzero entry below the diagonal.
+pub fn encode_p3_matrices(p3_matrices: &[GFMatrix], params: &MayoVariantParams) -> Result<Vec<u8>, MayoError> {
+    encode_matrix_set(p3_matrices, params.o, params.o, true, params.p3_bytes, params)
+}
+
+/// Encodes the m `(n-o) x o` L matrices into `params.l_bytes()` bytes, the inverse of
+/// `decode_l_matrices`: all elements are packed as one stream, not matrix by matrix.
+pub fn encode_l_matrices(l_matrices: &[GFMatrix], params: &MayoVariantParams) -> Result<Vec<u8>, MayoError> {
+    if l_matrices.len() != params.m {
+        return Err(MayoError::DimensionMismatch("Incorrect number of matrices to encode"));
+    }
+    let mut elements: GFVector = Vec::with_capacity(params.m * (params.n - params.o) * params.o);
+    for l_i in l_matrices {
+        if l_i.num_rows() != params.n - params.o || l_i.num_cols() != params.o {
+            return Err(MayoError::DimensionMismatch("Matrix to encode has incorrect dimensions"));
+        }
+        elements.extend_from_slice(&l_i.data);
+    }
+    let l_bytes = encode_gf_elements(&elements);
+    if l_bytes.len() != params.l_bytes() {
+        return Err(MayoError::DimensionMismatch("L_all_bytes length mismatch during encoding"));
+    }
+    Ok(l_bytes)
+}
+
 
 /// Encodes the solution vector `s` (a GFVector) into bytes.
+/// `s` is the concatenation s_0 || ... || s_{k-1} of the k whipped vectors, k * n elements.
 /// This is a thin wrapper around `encode_gf_elements`.
 pub fn encode_s_vector(s_vector: &GFVector, _params: &MayoVariantParams) -> Vec<u8> {
-    // s_vector should have length params.n
-    // assert_eq!(s_vector.len(), params.n, "s_vector length mismatch");
+    // s_vector should have length params.k * params.n
+    // assert_eq!(s_vector.len(), params.k * params.n, "s_vector length mismatch");
     encode_gf_elements(s_vector)
 }
 
 /// Decodes the solution vector `s` (a GFVector) from bytes.
-/// The length of `s` is `params.n`.
+/// The length of `s` is `params.k * params.n`: the k vectors s_0, ..., s_{k-1}, in order.
 /// This is a thin wrapper around `decode_gf_elements`.
-pub fn decode_s_vector(s_bytes: &[u8], params: &MayoVariantParams) -> Result<GFVector, &'static str> {
-    // s_bytes should have length (params.n+1)/2
-    // assert_eq!(s_bytes.len(), (params.n+1)/2, "s_bytes length mismatch");
-    decode_gf_elements(s_bytes, params.n)
+pub fn decode_s_vector(s_bytes: &[u8], params: &MayoVariantParams) -> Result<GFVector, MayoError> {
+    // s_bytes should have length params.s_bytes()
+    // assert_eq!(s_bytes.len(), params.s_bytes(), "s_bytes length mismatch");
+    decode_gf_elements(s_bytes, params.k * params.n)
 }
 
 
+/// Appends `value` to `out` as 4 big-endian bytes.
+/// All integer fields in this crate's serialization formats (length prefixes, indices,
+/// checksums) use big-endian so byte order never depends on the host platform.
+pub fn write_u32_be(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_be_bytes());
+}
+
+/// Reads a big-endian u32 from the start of `bytes`.
+/// Returns the value and the number of bytes consumed (always 4).
+pub fn read_u32_be(bytes: &[u8]) -> Result<(u32, usize), MayoError> {
+    if bytes.len() < 4 {
+        return Err(MayoError::DecodeError("Insufficient bytes to decode a u32"));
+    }
+    let value = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+    Ok((value, 4))
+}
+
+/// CRC-32 (IEEE 802.3, reflected polynomial 0xEDB88320) of `bytes`, as used by zlib and PNG.
+/// Detects accidental corruption of stored keys; it is not a cryptographic integrity check.
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = 0xFFFF_FFFFu32;
+    for &byte in bytes {
+        crc ^= byte as u32;
+        for _ in 0..8 {
+            let mask = (crc & 1).wrapping_neg();
+            crc = (crc >> 1) ^ (0xEDB8_8320 & mask);
+        }
+    }
+    !crc
+}
+
 // --- Unit Tests ---
 #[cfg(test)]
 mod tests {
@@ -225,6 +355,25 @@ mod tests {
 
     fn gf(val: u8) -> GFElement { GFElement(val) }
 
+    #[test]
+    fn test_crc32_check_value() {
+        assert_eq!(crc32(b"123456789"), 0xCBF4_3926);
+        assert_eq!(crc32(b""), 0);
+    }
+
+    #[test]
+    fn test_pack_unpack_nibbles() {
+        assert_eq!(pack_nibbles(&[0x1, 0x2, 0x3]), vec![0x12, 0x30]);
+        assert_eq!(pack_nibbles(&[]), Vec::<u8>::new());
+        assert_eq!(unpack_nibbles(&[0x12, 0x30], 3), Some(vec![0x1, 0x2, 0x3]));
+        assert_eq!(unpack_nibbles(&[0xAB], 3), None);
+
+        let nibbles: Vec<u8> = (0..31).map(|i| (i * 7 % 16) as u8).collect();
+        assert_eq!(unpack_nibbles(&pack_nibbles(&nibbles), nibbles.len()), Some(nibbles.clone()));
+        let elements: GFVector = nibbles.iter().map(|&n| gf(n)).collect();
+        assert_eq!(encode_gf_elements(&elements), pack_nibbles(&nibbles));
+    }
+
     #[test]
     fn test_encode_decode_gf_elements() {
         // Even number of elements
@@ -260,13 +409,34 @@ mod tests {
         assert!(decode_gf_elements(&[], 1).is_err());   // Need 1 byte for 1 element
     }
 
+    #[test]
+    fn test_decode_gf_elements_counted() {
+        let first = vec![GFElement(1), GFElement(2), GFElement(3)]; // Odd length: padded to 2 bytes
+        let second = vec![GFElement(0xA), GFElement(0xB), GFElement(0xC), GFElement(0xD)];
+        let mut blob = encode_gf_elements(&first);
+        blob.extend_from_slice(&encode_gf_elements(&second));
+
+        let mut cursor = 0;
+        let (decoded_first, used) = decode_gf_elements_counted(&blob[cursor..], first.len()).unwrap();
+        assert_eq!(used, 2);
+        cursor += used;
+        let (decoded_second, used) = decode_gf_elements_counted(&blob[cursor..], second.len()).unwrap();
+        assert_eq!(used, 2);
+        cursor += used;
+
+        assert_eq!(decoded_first, first);
+        assert_eq!(decoded_second, second);
+        assert_eq!(cursor, blob.len());
+        assert!(decode_gf_elements_counted(&blob[cursor..], 1).is_err());
+    }
+
     #[test]
     fn test_decode_o_matrix_simple() {
-        let params = MayoParams::mayo1().variant().clone(); // n=66, o=8. So O is 58x8.
+        let params = *MayoParams::mayo1().variant(); // n=66, o=8. So O is 58x8.
         let rows = params.n - params.o; // 58
         let cols = params.o; // 8
         let num_elements = rows * cols; // 58 * 8 = 464
-        let o_byte_len_expected = (num_elements + 1) / 2; // 232
+        let o_byte_len_expected = num_elements.div_ceil(2); // 232
         
         // Check if params.o_bytes matches this. From Turn 37, MAYO1 o_bytes = 232. Correct.
         assert_eq!(params.o_bytes, o_byte_len_expected);
@@ -309,18 +479,43 @@ mod tests {
         assert!(decode_upper_triangular_matrix(&elements, 2).is_err()); // Wrong size
     }
 
+    #[test]
+    fn test_upper_triangle_flat_inverts_decode() {
+        let elements = vec![gf(1), gf(2), gf(3), gf(4), gf(5), gf(6)];
+        let matrix = decode_upper_triangular_matrix(&elements, 3).unwrap();
+        assert_eq!(matrix.upper_triangle_flat().unwrap(), elements);
+
+        assert_eq!(GFMatrix::zero(2, 3).upper_triangle_flat(), Err(MayoError::DimensionMismatch("Matrix must be square to extract its upper triangle")));
+    }
+
+    #[test]
+    fn test_encode_upper_triangular() {
+        let elements = vec![gf(1), gf(2), gf(3), gf(4), gf(5), gf(6)];
+        let matrix = decode_upper_triangular_matrix(&elements, 3).unwrap();
+        let encoded = encode_upper_triangular(&matrix).unwrap();
+        assert_eq!(encoded, encode_gf_elements(&elements));
+        assert_eq!(decode_gf_elements(&encoded, elements.len()).unwrap(), elements);
+
+        // A dense matrix has lower-triangle data that cannot be represented.
+        let dense = GFMatrix::new_with_data(2, 2, vec![gf(1), gf(2), gf(3), gf(4)]);
+        assert_eq!(encode_upper_triangular(&dense), Err(MayoError::InvalidInput("Matrix has nonzero entries below the diagonal")));
+
+        assert!(encode_upper_triangular(&GFMatrix::zero(2, 3)).is_err());
+    }
+
     #[test]
     fn test_decode_p_matrices_structure() {
         // Using MAYO1 parameters from Turn 37 for structural checks
         // n=66, m=64, o=8, k=9
         // p1_bytes: 960, p2_bytes: 14848, p3_bytes: 160
-        let params_variant = MayoParams::mayo1().variant().clone();
+        let params_variant = *MayoParams::mayo1().variant();
 
         // P1: m=64 matrices, each (n-o)x(n-o) = 58x58 upper triangular
         let size_p1 = params_variant.n - params_variant.o; // 58
         let elems_p1_upper = size_p1 * (size_p1 + 1) / 2; // 58*59/2 = 1711
-        let bytes_p1_one_mat = (elems_p1_upper + 1) / 2;  // (1711+1)/2 = 856
-        assert_eq!(params_variant.p1_bytes, params_variant.m * bytes_p1_one_mat); // 64 * 856 = 54784. This is NOT 960.
+        let bytes_p1_one_mat = elems_p1_upper.div_ceil(2);  // (1711+1)/2 = 856
+        // The m upper triangles are packed as one stream, so no matrix is padded on its own.
+        assert_eq!(params_variant.p1_bytes, (params_variant.m * elems_p1_upper).div_ceil(2)); // 64 * 1711 / 2 = 54752. This is NOT 960.
         // The p1_bytes=960 from Turn 37 is very small. It cannot hold m=64 matrices of 58x58 upper triangular.
         // 960 bytes can hold 1920 nibbles. 1920 / 64 matrices = 30 nibbles per matrix.
         // 30 nibbles is not enough for a 58x58 upper triangular matrix (needs 1711 nibbles).
@@ -338,7 +533,7 @@ mod tests {
         // if p1_bytes.len() != params.p1_bytes ...
         // And: if p1_bytes.len() % bytes_per_p1_mat != 0 || (p1_bytes.len() / bytes_per_p1_mat) != params.m ...
         // This means the provided params from Turn 37 (MAYO1 p1_bytes=960) ARE NOT CONSISTENT with m=64 and n-o=58.
-        // p1_bytes should be 54784.
+        // p1_bytes should be 54752.
         // This is a critical issue for actual MAYO implementation.
         // For this subtask, I will proceed testing the codec functions with the assumption that input bytes
         // *could* be valid if parameters were consistent.
@@ -347,7 +542,7 @@ mod tests {
         let rows_p2 = params_variant.n - params_variant.o; // 58
         let cols_p2 = params_variant.o; // 8
         let elems_p2_one_mat = rows_p2 * cols_p2; // 58*8 = 464
-        let bytes_p2_one_mat = (elems_p2_one_mat + 1)/2; // 232
+        let bytes_p2_one_mat = elems_p2_one_mat.div_ceil(2); // 232
         assert_eq!(params_variant.p2_bytes, params_variant.m * bytes_p2_one_mat); // 64 * 232 = 14848. This matches.
 
         let p2_sample_bytes = vec![0xAA; params_variant.p2_bytes];
@@ -359,7 +554,7 @@ mod tests {
         // P3: m=64 matrices, each oxo = 8x8 upper triangular
         let size_p3 = params_variant.o; // 8
         let elems_p3_upper = size_p3 * (size_p3 + 1) / 2; // 8*9/2 = 36
-        let bytes_p3_one_mat = (elems_p3_upper + 1) / 2;  // (36+1)/2 = 18 (rounded up) -> 19 if strict, but (36+1)/2 = 18.5 -> 19 if it were 37. Let's recheck (36+1)/2 = 18. No, it's 18.
+        let bytes_p3_one_mat = elems_p3_upper.div_ceil(2);  // (36+1)/2 = 18 (rounded up) -> 19 if strict, but (36+1)/2 = 18.5 -> 19 if it were 37. Let's recheck (36+1)/2 = 18. No, it's 18.
                                                         // (num_elements + 1) / 2. For 36 elements, it's (36+1)/2 = 18.5 -> 19 if strict ceiling. Oh, it's integer division. (36+1)/2 = 18.
                                                         // (36 elements -> 18 bytes).
         assert_eq!(bytes_p1_one_mat, 856); // from above, just for reference
@@ -379,7 +574,7 @@ mod tests {
         // m * bytes_l_one_mat = 64 * 1682 = 107648.
         // This is just a structural check for the function decode_l_matrices
         let l_test_m = 2;
-        let mut l_dummy_params = params_variant.clone(); // params_variant is MAYO1 (n=66, o=8, m=64)
+        let mut l_dummy_params = params_variant; // params_variant is MAYO1 (n=66, o=8, m=64)
         // Let's define test L matrix dimensions: rows_l = 5, cols_l = 3
         let test_l_rows = 5;
         let test_l_cols = 3;
@@ -387,7 +582,7 @@ mod tests {
         l_dummy_params.n = test_l_rows + l_dummy_params.o; // n = 5 + 3 = 8
         l_dummy_params.m = l_test_m;
         let num_elements_per_l_mat_test = test_l_rows * test_l_cols; // 5 * 3 = 15 elements
-        let l_test_bytes_per_mat = (num_elements_per_l_mat_test + 1) / 2; // (15+1)/2 = 8 bytes
+        let l_test_bytes_per_mat = num_elements_per_l_mat_test.div_ceil(2); // (15+1)/2 = 8 bytes
         let l_test_bytes = vec![0xFF; l_test_m * l_test_bytes_per_mat]; // 2 * 8 = 16 bytes
         
         let l_mats_res = decode_l_matrices(&l_test_bytes, &l_dummy_params);
@@ -399,13 +594,104 @@ mod tests {
     }
 
 
+    #[test]
+    fn test_decoded_p1_is_upper_triangular() {
+        let params = *MayoParams::mayo1().variant();
+        let p1_bytes: Vec<u8> = (0..params.p1_bytes).map(|i| (i % 251) as u8 | 0x11).collect();
+        let p1_mats = decode_p1_matrices(&p1_bytes, &params).unwrap();
+        let size = params.n - params.o;
+        for mat in &p1_mats {
+            for r in 1..size {
+                for c in 0..r {
+                    assert_eq!(mat.get_unsafe(r, c), gf(0), "Sub-diagonal entry ({}, {}) is nonzero", r, c);
+                }
+            }
+            // Every upper entry is nonzero here (each nibble has its low bit set).
+            let expected = (size * (size + 1) / 2) as f64 / (size * size) as f64;
+            assert!((mat.nonzero_fraction() - expected).abs() < 1e-9);
+        }
+    }
+
+    /// m random `rows x cols` matrices, with the entries below the diagonal cleared if `upper_triangular`.
+    fn random_matrices(rows: usize, cols: usize, upper_triangular: bool, m: usize) -> Vec<GFMatrix> {
+        (0..m).map(|_| {
+            let mut matrix = GFMatrix::random(rows, cols, &mut rand_core::OsRng);
+            if upper_triangular {
+                for r in 0..rows {
+                    for c in 0..r.min(cols) {
+                        matrix[(r, c)] = gf(0);
+                    }
+                }
+            }
+            matrix
+        }).collect()
+    }
+
+    #[test]
+    fn test_encode_decode_key_matrices_round_trip() {
+        for params_enum in MayoParams::all_variants() {
+            let params = params_enum.variant();
+            let (v, o) = (params.n - params.o, params.o);
+
+            let o_matrix = random_matrices(v, o, false, 1).remove(0);
+            let o_bytes = encode_o_matrix(&o_matrix, params).unwrap();
+            assert_eq!(o_bytes.len(), params.o_bytes);
+            assert_eq!(decode_o_matrix(&o_bytes, params).unwrap(), o_matrix);
+
+            let p1 = random_matrices(v, v, true, params.m);
+            let p1_bytes = encode_p1_matrices(&p1, params).unwrap();
+            assert_eq!(p1_bytes.len(), params.p1_bytes);
+            assert_eq!(decode_p1_matrices(&p1_bytes, params).unwrap(), p1);
+
+            let p2 = random_matrices(v, o, false, params.m);
+            let p2_bytes = encode_p2_matrices(&p2, params).unwrap();
+            assert_eq!(p2_bytes.len(), params.p2_bytes);
+            assert_eq!(decode_p2_matrices(&p2_bytes, params).unwrap(), p2);
+
+            let p3 = random_matrices(o, o, true, params.m);
+            let p3_bytes = encode_p3_matrices(&p3, params).unwrap();
+            assert_eq!(p3_bytes.len(), params.p3_bytes);
+            assert_eq!(decode_p3_matrices(&p3_bytes, params).unwrap(), p3);
+
+            let l = random_matrices(v, o, false, params.m);
+            let l_bytes = encode_l_matrices(&l, params).unwrap();
+            assert_eq!(l_bytes.len(), params.l_bytes());
+            assert_eq!(decode_l_matrices(&l_bytes, params).unwrap(), l);
+        }
+    }
+
+    #[test]
+    fn test_encode_key_matrices_rejects_malformed_input() {
+        let params = *MayoParams::mayo1().variant();
+        let v = params.n - params.o;
+        let mut p1 = random_matrices(v, v, true, params.m);
+        assert_eq!(encode_p1_matrices(&p1[1..], &params), Err(MayoError::DimensionMismatch("Incorrect number of matrices to encode")));
+        p1[3][(1, 0)] = gf(1);
+        assert_eq!(encode_p1_matrices(&p1, &params), Err(MayoError::InvalidInput("Matrix has nonzero entries below the diagonal")));
+        let wrong_shape = random_matrices(params.o, params.o, false, params.m);
+        assert_eq!(encode_p2_matrices(&wrong_shape, &params), Err(MayoError::DimensionMismatch("Matrix to encode has incorrect dimensions")));
+        assert_eq!(encode_o_matrix(&wrong_shape[0], &params), Err(MayoError::DimensionMismatch("O matrix has incorrect dimensions")));
+    }
+
+    #[test]
+    fn test_u32_be_round_trip() {
+        let mut out = Vec::new();
+        write_u32_be(&mut out, 0x0102_0304);
+        assert_eq!(out, vec![0x01, 0x02, 0x03, 0x04]); // Most significant byte first
+        write_u32_be(&mut out, u32::MAX);
+        assert_eq!(read_u32_be(&out).unwrap(), (0x0102_0304, 4));
+        assert_eq!(read_u32_be(&out[4..]).unwrap(), (u32::MAX, 4));
+        assert!(read_u32_be(&out[5..]).is_err());
+    }
+
     #[test]
     fn test_encode_decode_s_vector() {
-        let params = MayoParams::mayo1().variant().clone(); // n=66
-        let s_vec_elements: GFVector = (0..(params.n)).map(|i| gf((i % 16) as u8)).collect();
+        let params = *MayoParams::mayo1().variant(); // n=66, k=9
+        let s_vec_elements: GFVector = (0..(params.k * params.n)).map(|i| gf((i % 16) as u8)).collect();
         
         let encoded_s = encode_s_vector(&s_vec_elements, &params);
-        let expected_bytes = (params.n + 1) / 2;
+        let expected_bytes = params.s_bytes();
+        assert_eq!(expected_bytes, (params.k * params.n).div_ceil(2));
         assert_eq!(encoded_s.len(), expected_bytes);
 
         let decoded_s_res = decode_s_vector(&encoded_s, &params);
@@ -0,0 +1,94 @@
+//! Error type shared by key generation, signing, verification, the solver and the codecs.
+
+use std::fmt;
+
+/// Errors returned by the MAYO routines of this crate.
+///
+/// Most variants carry a static description of the check that failed; `Display` prints
+/// that description, which is also the text the wasm API hands to JavaScript.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MayoError {
+    /// The parameter set is inconsistent or not supported.
+    InvalidParameters(&'static str),
+    /// No supported variant has the given name.
+    UnknownVariant(String),
+    /// A secret or public key, or one of its components, has the wrong length.
+    InvalidKeyLength(&'static str),
+    /// A signature, its s vector or a signed message has the wrong length.
+    InvalidSignatureLength(&'static str),
+    /// Matrix or vector dimensions do not fit the operation.
+    DimensionMismatch(&'static str),
+    /// Bytes could not be decoded: truncated input, unknown variant tag or checksum mismatch.
+    DecodeError(&'static str),
+    /// Any other caller-supplied argument is malformed or out of range.
+    InvalidInput(&'static str),
+    /// Key material is well-formed but inconsistent, e.g. O does not match seedsk.
+    InvalidKey(&'static str),
+    /// Division by, or inversion of, zero in GF(16).
+    DivisionByZero(&'static str),
+    /// The randomness source failed or returned output of the wrong length.
+    RngFailure(&'static str),
+    /// No signing attempt produced a solvable linear system.
+    SignRetriesExhausted {
+        /// Number of attempts made.
+        attempts: usize,
+        /// Attempts whose system was inconsistent.
+        no_solution: usize,
+        /// Attempts the solver rejected.
+        solver_errors: usize,
+        /// The error of the last rejected attempt, if any.
+        last_solver_error: Option<Box<MayoError>>,
+    },
+    /// An operation panicked internally and the panic was caught at the API boundary.
+    InternalPanic,
+}
+
+impl fmt::Display for MayoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MayoError::InvalidParameters(msg)
+            | MayoError::InvalidKeyLength(msg)
+            | MayoError::InvalidSignatureLength(msg)
+            | MayoError::DimensionMismatch(msg)
+            | MayoError::DecodeError(msg)
+            | MayoError::InvalidInput(msg)
+            | MayoError::InvalidKey(msg)
+            | MayoError::DivisionByZero(msg)
+            | MayoError::RngFailure(msg) => f.write_str(msg),
+            MayoError::UnknownVariant(name) => write!(f, "Unknown MAYO variant name: {}", name),
+            MayoError::SignRetriesExhausted { attempts, no_solution, solver_errors, last_solver_error } => {
+                write!(f, "MAYO.Sign failed after maximum retries ({} attempts). No solution found: {} times. Solver errored: {} times.",
+                       attempts, no_solution, solver_errors)?;
+                if let Some(e) = last_solver_error {
+                    write!(f, " Last solver error: {}", e)?;
+                }
+                Ok(())
+            }
+            MayoError::InternalPanic => f.write_str("Internal panic during MAYO operation"),
+        }
+    }
+}
+
+impl std::error::Error for MayoError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_prints_description() {
+        assert_eq!(MayoError::InvalidKeyLength("Expanded public key has incorrect length").to_string(),
+                   "Expanded public key has incorrect length");
+        assert_eq!(MayoError::UnknownVariant("mayo9".to_string()).to_string(), "Unknown MAYO variant name: mayo9");
+
+        let exhausted = MayoError::SignRetriesExhausted {
+            attempts: 4,
+            no_solution: 3,
+            solver_errors: 1,
+            last_solver_error: Some(Box::new(MayoError::DimensionMismatch("Matrix A rows must match y_vector length"))),
+        };
+        assert_eq!(exhausted.to_string(),
+                   "MAYO.Sign failed after maximum retries (4 attempts). No solution found: 3 times. Solver errored: 1 times. \
+                    Last solver error: Matrix A rows must match y_vector length");
+    }
+}
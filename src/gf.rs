@@ -1,16 +1,43 @@
 //! Implements arithmetic for the finite field GF(16).
 //! The field is defined by the irreducible polynomial x^4 + x + 1 (0x13 or 0b10011).
 
+use std::cell::Cell;
 use crate::types::GFElement;
 use crate::params::F_POLY_U8; // Using the u8 version: 0b0001_0011
+use crate::error::MayoError;
 
 // Mask to ensure we only operate on the lower 4 bits (nibble)
 const NIBBLE_MASK: u8 = 0x0F;
 
+#[cfg(feature = "counting")]
+thread_local! {
+    static GF_OP_COUNT: Cell<u64> = const { Cell::new(0) };
+}
+
+/// Returns the number of GF(16) additions, subtractions and multiplications performed on
+/// the current thread since the last `reset_gf_op_count`.
+#[cfg(feature = "counting")]
+pub fn gf_op_count() -> u64 {
+    GF_OP_COUNT.with(|c| c.get())
+}
+
+/// Resets the current thread's GF(16) operation counter to zero.
+#[cfg(feature = "counting")]
+pub fn reset_gf_op_count() {
+    GF_OP_COUNT.with(|c| c.set(0));
+}
+
+#[inline]
+fn count_gf_op() {
+    #[cfg(feature = "counting")]
+    GF_OP_COUNT.with(|c| c.set(c.get() + 1));
+}
+
 /// Adds two GF(16) elements.
 /// In GF(2^n), addition is XOR. Result is masked to 4 bits.
 #[inline]
 pub fn gf16_add(a: GFElement, b: GFElement) -> GFElement {
+    count_gf_op();
     GFElement((a.0 ^ b.0) & NIBBLE_MASK)
 }
 
@@ -18,12 +45,54 @@ pub fn gf16_add(a: GFElement, b: GFElement) -> GFElement {
 /// In GF(2^n), subtraction is the same as addition (XOR). Result is masked to 4 bits.
 #[inline]
 pub fn gf16_sub(a: GFElement, b: GFElement) -> GFElement {
+    count_gf_op();
     GFElement((a.0 ^ b.0) & NIBBLE_MASK) // Identical to add
 }
 
+/// Multiplication strategy used by `gf16_mul`. All backends compute the same products;
+/// they exist so benchmarks can compare strategies without recompiling.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GfBackend {
+    /// Shift-and-add with reduction by x^4 + x + 1 (`gf16_mul_bitwise`).
+    Bitwise,
+    /// Lookup in the full 16x16 product table (`gf16_mul_table`).
+    Table,
+    /// Log/antilog tables (`gf16_mul_via_log`).
+    #[default]
+    LogTable,
+}
+
+thread_local! {
+    static GF_BACKEND: Cell<GfBackend> = const { Cell::new(GfBackend::LogTable) };
+}
+
+/// Selects the backend `gf16_mul` uses on the current thread.
+/// The setting is thread-local, so other threads keep their own (default `LogTable`).
+pub fn set_gf_backend(backend: GfBackend) {
+    GF_BACKEND.with(|b| b.set(backend));
+}
+
+/// Returns the backend `gf16_mul` uses on the current thread.
+pub fn gf_backend() -> GfBackend {
+    GF_BACKEND.with(|b| b.get())
+}
+
+/// Multiplies two GF(16) elements with the current thread's `GfBackend`.
+/// The default backend is `LogTable`: zero if either operand is zero, otherwise
+/// `GF16_EXP[(GF16_LOG[a] + GF16_LOG[b]) % 15]`.
+#[inline]
+pub fn gf16_mul(a: GFElement, b: GFElement) -> GFElement {
+    count_gf_op();
+    match gf_backend() {
+        GfBackend::Bitwise => gf16_mul_bitwise(a, b),
+        GfBackend::Table => gf16_mul_table(a, b),
+        GfBackend::LogTable => gf16_mul_via_log(a, b),
+    }
+}
+
 /// Multiplies two GF(16) elements using bitwise operations (Russian peasant method variant).
 /// Field is GF(2^4) with irreducible polynomial x^4 + x + 1 (F_POLY_U8 = 0b00010011).
-pub fn gf16_mul(a: GFElement, b: GFElement) -> GFElement {
+pub fn gf16_mul_bitwise(a: GFElement, b: GFElement) -> GFElement {
     let mut p: u8 = 0; // Accumulator for the product
     let mut val_a = a.0 & NIBBLE_MASK;
     let mut val_b = b.0 & NIBBLE_MASK;
@@ -55,18 +124,130 @@ pub fn gf16_mul(a: GFElement, b: GFElement) -> GFElement {
     GFElement(p & NIBBLE_MASK)
 }
 
-/// Computes base^exp in GF(16).
+/// Computes `acc[i] += scalar * src[i]` for every i, the row update of matrix products.
+/// Uses `gf16_mul` and `gf16_add`, so it follows the selected `GfBackend`.
+/// Panics if `acc` and `src` differ in length.
+pub fn gf16_vec_madd(acc: &mut [GFElement], scalar: GFElement, src: &[GFElement]) {
+    assert_eq!(acc.len(), src.len(), "gf16_vec_madd: length mismatch");
+    for (a, &s) in acc.iter_mut().zip(src) {
+        *a = gf16_add(*a, gf16_mul(scalar, s));
+    }
+}
+
+/// Computes base^exp in GF(16) by square-and-multiply, scanning `exp` from its top bit.
+/// `exp == 0` gives 1 for every base, including 0.
 pub fn gf16_pow(base: GFElement, exp: usize) -> GFElement {
+    let mut result = GFElement(1); // g^0 = 1
     if exp == 0 {
-        return GFElement(1); // g^0 = 1
+        return result;
     }
-    let mut result = base;
-    for _ in 1..exp {
-        result = gf16_mul(result, base);
+    for bit in (0..usize::BITS - exp.leading_zeros()).rev() {
+        result = gf16_mul(result, result);
+        if (exp >> bit) & 1 != 0 {
+            result = gf16_mul(result, base);
+        }
     }
     result
 }
 
+/// Generator of the multiplicative group GF(16)*: the element x (0x2).
+pub const GF16_GENERATOR: u8 = 0x2;
+
+/// Antilog table: `GF16_EXP[i]` is x^i for i in 0..15.
+pub const GF16_EXP: [u8; 15] = build_exp_table();
+
+/// Log table: `GF16_LOG[a]` is the i with x^i = a, for nonzero a.
+/// `GF16_LOG[0]` is unused (zero has no discrete log) and is set to 0.
+pub const GF16_LOG: [u8; 16] = build_log_table();
+
+const fn build_exp_table() -> [u8; 15] {
+    let mut table = [0u8; 15];
+    let mut val: u8 = 1;
+    let mut i = 0;
+    while i < 15 {
+        table[i] = val;
+        // Multiply by the generator x and reduce by x^4 + x + 1
+        val <<= 1;
+        if val & 0x10 != 0 {
+            val ^= F_POLY_U8;
+        }
+        i += 1;
+    }
+    table
+}
+
+const fn build_log_table() -> [u8; 16] {
+    let exp = build_exp_table();
+    let mut table = [0u8; 16];
+    let mut i = 0;
+    while i < 15 {
+        table[exp[i] as usize] = i as u8;
+        i += 1;
+    }
+    table
+}
+
+/// Inverse table: `GF16_INV[a]` is a^-1 = x^(15 - log a), for nonzero a.
+/// `GF16_INV[0]` is unused (zero has no inverse) and is set to 0.
+pub const GF16_INV: [u8; 16] = build_inv_table();
+
+const fn build_inv_table() -> [u8; 16] {
+    let exp = build_exp_table();
+    let log = build_log_table();
+    let mut table = [0u8; 16];
+    let mut a = 1;
+    while a < 16 {
+        table[a] = exp[(15 - log[a] as usize) % 15];
+        a += 1;
+    }
+    table
+}
+
+/// Full multiplication table: `GF16_MUL_TABLE[a][b]` is a * b.
+pub const GF16_MUL_TABLE: [[u8; 16]; 16] = build_mul_table();
+
+const fn build_mul_table() -> [[u8; 16]; 16] {
+    let exp = build_exp_table();
+    let log = build_log_table();
+    let mut table = [[0u8; 16]; 16];
+    let mut a = 1;
+    while a < 16 {
+        let mut b = 1;
+        while b < 16 {
+            table[a][b] = exp[(log[a] as usize + log[b] as usize) % 15];
+            b += 1;
+        }
+        a += 1;
+    }
+    table
+}
+
+/// Multiplies two GF(16) elements by a single lookup in `GF16_MUL_TABLE`.
+#[inline]
+pub fn gf16_mul_table(a: GFElement, b: GFElement) -> GFElement {
+    GFElement(GF16_MUL_TABLE[(a.0 & NIBBLE_MASK) as usize][(b.0 & NIBBLE_MASK) as usize])
+}
+
+/// Multiplies two GF(16) elements via the log/antilog tables:
+/// a * b = x^(log a + log b mod 15), with zero handled separately.
+pub fn gf16_mul_via_log(a: GFElement, b: GFElement) -> GFElement {
+    let (a, b) = (a.0 & NIBBLE_MASK, b.0 & NIBBLE_MASK);
+    if a == 0 || b == 0 {
+        return GFElement(0);
+    }
+    let log_sum = (GF16_LOG[a as usize] as usize + GF16_LOG[b as usize] as usize) % 15;
+    GFElement(GF16_EXP[log_sum])
+}
+
+
+/// Divides `a` by `b` in GF(16), i.e. multiplies `a` by `GF16_INV[b]`.
+pub fn gf16_div(a: GFElement, b: GFElement) -> Result<GFElement, MayoError> {
+    let b = b.0 & NIBBLE_MASK;
+    if b == 0 {
+        return Err(MayoError::DivisionByZero("Cannot divide by zero element"));
+    }
+    Ok(gf16_mul(a, GFElement(GF16_INV[b as usize])))
+}
 
 #[cfg(test)]
 mod tests {
@@ -171,6 +352,42 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_gf16_mul_field_axioms_exhaustive() {
+        for a in 0..16 {
+            for b in 0..16 {
+                for c in 0..16 {
+                    let (a, b, c) = (gf(a), gf(b), gf(c));
+                    assert_eq!(gf16_mul(gf16_mul(a, b), c), gf16_mul(a, gf16_mul(b, c)), "Associativity failed for ({:?}, {:?}, {:?})", a, b, c);
+                    assert_eq!(gf16_mul(a, gf16_add(b, c)), gf16_add(gf16_mul(a, b), gf16_mul(a, c)), "Distributivity failed for ({:?}, {:?}, {:?})", a, b, c);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_gf16_vec_madd_matches_naive() {
+        let src: Vec<GFElement> = (0..16).map(gf).collect();
+        let initial: Vec<GFElement> = (0..16).map(|i| gf((i * 7 + 3) % 16)).collect();
+        for scalar in 0..16 {
+            let mut acc = initial.clone();
+            gf16_vec_madd(&mut acc, gf(scalar), &src);
+            for i in 0..16 {
+                assert_eq!(acc[i], gf16_add(initial[i], gf16_mul(gf(scalar), src[i])), "Failed for scalar={}, i={}", scalar, i);
+            }
+        }
+
+        let mut empty: Vec<GFElement> = Vec::new();
+        gf16_vec_madd(&mut empty, gf(0x5), &[]);
+        assert!(empty.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "gf16_vec_madd: length mismatch")]
+    fn test_gf16_vec_madd_length_mismatch_panics() {
+        gf16_vec_madd(&mut [gf(1), gf(2)], gf(3), &[gf(4)]);
+    }
+
     #[test]
     fn test_gf16_pow() {
         // x = 0x2
@@ -190,5 +407,95 @@ mod tests {
         assert_eq!(gf16_pow(gf(0x5), 2).0, 0x2); 
         assert_eq!(gf16_pow(gf(0x5), 3).0, gf16_mul(gf(0x2), gf(0x5)).0); // 0x2 * 0x5 = x(x^2+1) = x^3+x = 0x8^0x2 = 0xA
         assert_eq!(gf16_pow(gf(0x5), 3).0, 0xA);
+
+        // Zero base: 0^0 = 1, 0^e = 0 otherwise
+        assert_eq!(gf16_pow(gf(0x0), 0).0, 0x1);
+        assert_eq!(gf16_pow(gf(0x0), 1).0, 0x0);
+        assert_eq!(gf16_pow(gf(0x0), 14).0, 0x0);
+
+        // Matches repeated multiplication for every base, across several group periods
+        for b in 0..16 {
+            let mut expected = gf(1);
+            for e in 0..50 {
+                assert_eq!(gf16_pow(gf(b), e), expected, "Failed for base={}, exp={}", b, e);
+                expected = gf16_mul(expected, gf(b));
+            }
+        }
+    }
+
+    #[test]
+    #[allow(clippy::needless_range_loop)]
+    fn test_gf16_log_exp_tables_consistent() {
+        assert_eq!(GF16_EXP[0], 1);
+        assert_eq!(GF16_EXP[1], GF16_GENERATOR);
+        for a in 1..16u8 {
+            assert_eq!(GF16_EXP[GF16_LOG[a as usize] as usize], a, "exp[log[a]] != a for a={}", a);
+        }
+        for i in 0..15 {
+            assert_eq!(GF16_EXP[i], gf16_pow(gf(GF16_GENERATOR), i).0);
+        }
+    }
+
+    #[test]
+    fn test_gf16_inv_table() {
+        assert_eq!(GF16_INV[0], 0);
+        for a in 1..16u8 {
+            assert_eq!(gf16_mul(gf(a), gf(GF16_INV[a as usize])).0, 1, "Inverse failed for a={}", a);
+        }
+    }
+
+    #[test]
+    fn test_gf16_div_inverts_mul() {
+        for a in 0..16 {
+            assert_eq!(gf16_div(gf(a), gf(0)).err(), Some(MayoError::DivisionByZero("Cannot divide by zero element")));
+            for b in 1..16 {
+                let q = gf16_div(gf(a), gf(b)).unwrap();
+                assert_eq!(gf16_mul(q, gf(b)).0, a, "Division failed for a={}, b={}", a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_gf_backends_agree() {
+        assert_eq!(gf_backend(), GfBackend::LogTable);
+        for i in 0..16 {
+            for j in 0..16 {
+                let expected = gf16_mul_bitwise(gf(i), gf(j));
+                assert_eq!(gf16_mul_table(gf(i), gf(j)), expected, "Table failed for i={}, j={}", i, j);
+                assert_eq!(gf16_mul_via_log(gf(i), gf(j)), expected, "LogTable failed for i={}, j={}", i, j);
+            }
+        }
+
+        // gf16_mul dispatches to the selected backend
+        for backend in [GfBackend::Bitwise, GfBackend::LogTable, GfBackend::Table] {
+            set_gf_backend(backend);
+            assert_eq!(gf_backend(), backend);
+            for i in 0..16 {
+                for j in 0..16 {
+                    assert_eq!(gf16_mul(gf(i), gf(j)), gf16_mul_bitwise(gf(i), gf(j)), "{:?} failed for i={}, j={}", backend, i, j);
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "counting")]
+    #[test]
+    fn test_gf_op_count() {
+        reset_gf_op_count();
+        assert_eq!(gf_op_count(), 0);
+        let product = gf16_mul(gf(0x3), gf(0x7));
+        let _ = gf16_sub(gf16_add(product, gf(0x1)), gf(0x2));
+        assert_eq!(gf_op_count(), 3);
+        reset_gf_op_count();
+        assert_eq!(gf_op_count(), 0);
+    }
+
+    #[test]
+    fn test_gf16_mul_via_log_matches_gf16_mul() {
+        for i in 0..16 {
+            for j in 0..16 {
+                assert_eq!(gf16_mul_via_log(gf(i), gf(j)), gf16_mul(gf(i), gf(j)), "Failed for i={}, j={}", i, j);
+            }
+        }
     }
 }
@@ -2,7 +2,8 @@
 //! and other parts of the MAYO signature scheme.
 
 use sha3::{Shake256, digest::{Update, ExtendableOutput, XofReader}};
-use crate::types::{MessageDigest, Salt, SeedSK, SeedPK};
+use crate::types::{ExpandedPublicKey, Message, MessageDigest, Salt, SeedSK, SeedPK};
+use crate::codec::write_u32_be;
 use crate::params::MayoParams;
 
 /// Generates a fixed-size message digest using SHAKE256.
@@ -22,6 +23,59 @@ pub fn shake256_digest(input: &[u8], params: &MayoParams) -> MessageDigest {
     MessageDigest(digest_bytes_vec)
 }
 
+/// Computes the message digest M_digest = SHAKE256(M) used by both MAYO.Sign and MAYO.Verify
+/// to derive the target vector. Exposed so callers can pre-compute or audit it.
+pub fn message_digest(message: &Message, params: &MayoParams) -> MessageDigest {
+    shake256_digest(&message.0, params)
+}
+
+/// Size of the chunks `message_digest_from_reader` feeds to SHAKE256.
+const READER_CHUNK_BYTES: usize = 64 * 1024;
+
+/// Computes the same digest as `message_digest` incrementally, for messages that arrive
+/// in pieces. Feeding the chunks in order gives the digest of their concatenation.
+#[derive(Clone)]
+pub struct MessageDigester {
+    hasher: Shake256,
+    digest_bytes: usize,
+}
+
+impl MessageDigester {
+    /// Starts a digest producing the variant's `digest_bytes` bytes.
+    pub fn new(params: &MayoParams) -> Self {
+        MessageDigester { hasher: Shake256::default(), digest_bytes: params.digest_bytes() }
+    }
+
+    /// Absorbs the next chunk of the message.
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.hasher.update(chunk);
+    }
+
+    /// Returns the digest of all chunks absorbed so far.
+    pub fn finalize(self) -> MessageDigest {
+        let mut xof_reader = self.hasher.finalize_xof();
+        let mut digest_bytes_vec = vec![0u8; self.digest_bytes];
+        xof_reader.read(&mut digest_bytes_vec);
+        MessageDigest(digest_bytes_vec)
+    }
+}
+
+/// Computes the same digest as `message_digest`, streaming the message from `reader`
+/// in fixed-size chunks so it never has to be held in memory.
+pub fn message_digest_from_reader<R: std::io::Read>(reader: &mut R, params: &MayoParams) -> std::io::Result<MessageDigest> {
+    let mut digester = MessageDigester::new(params);
+    let mut chunk = vec![0u8; READER_CHUNK_BYTES];
+    loop {
+        match reader.read(&mut chunk) {
+            Ok(0) => break,
+            Ok(len) => digester.update(&chunk[..len]),
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(digester.finalize())
+}
+
 /// Derives a public key seed (`SeedPK`) and bytes for the oil space (`O_bytes`)
 /// from a secret key seed (`SeedSK`) using SHAKE256 XOF (Extendable Output Function).
 ///
@@ -45,22 +99,34 @@ pub fn shake256_xof_derive_pk_seed_and_o(seed: &SeedSK, params: &MayoParams) ->
     (SeedPK(seedpk_bytes_vec), o_bytes_vec)
 }
 
-/// Derives bytes for the P3 matrix component (`P3_bytes`) from a public key seed (`SeedPK`)
-/// using SHAKE256 XOF.
-///
-/// # Arguments
-/// * `seed_pk` - The public key seed (`SeedPK`).
-/// * `params` - MAYO parameters, used to determine the `P3_bytes` length.
-///
-/// # Returns
-/// A `Vec<u8>` representing the `P3_bytes`.
-pub fn shake256_xof_derive_p3(seed_pk: &SeedPK, params: &MayoParams) -> Vec<u8> {
+/// Derives the randomness of deterministic signing from SHAKE256(seedsk || M): the salt
+/// (`salt_bytes` bytes) followed by a vinegar seed (`sk_seed_bytes` bytes) that
+/// `shake256_derive_vinegar_bytes` expands once per signing attempt.
+pub fn shake256_derive_deterministic_salt_and_seed(seedsk: &SeedSK, message: &Message, params: &MayoParams) -> (Salt, Vec<u8>) {
+    let mut hasher = Shake256::default();
+    hasher.update(&seedsk.0);
+    hasher.update(&message.0);
+    let mut reader = hasher.finalize_xof();
+
+    let mut salt_bytes_vec = vec![0u8; params.salt_bytes()];
+    reader.read(&mut salt_bytes_vec);
+    let mut vinegar_seed = vec![0u8; params.sk_seed_bytes()];
+    reader.read(&mut vinegar_seed);
+    (Salt(salt_bytes_vec), vinegar_seed)
+}
+
+/// Expands a vinegar seed into `len` bytes for signing attempt `ctr`:
+/// SHAKE256(vinegar_seed || ctr), with `ctr` absorbed as 4 big-endian bytes.
+pub fn shake256_derive_vinegar_bytes(vinegar_seed: &[u8], ctr: u32, len: usize) -> Vec<u8> {
+    let mut ctr_bytes = Vec::with_capacity(4);
+    write_u32_be(&mut ctr_bytes, ctr);
     let mut hasher = Shake256::default();
-    hasher.update(&seed_pk.0);
+    hasher.update(vinegar_seed);
+    hasher.update(&ctr_bytes);
     let mut reader = hasher.finalize_xof();
-    let mut p3_bytes_vec = vec![0u8; params.p3_bytes()];
-    reader.read(&mut p3_bytes_vec);
-    p3_bytes_vec
+    let mut v_bytes = vec![0u8; len];
+    reader.read(&mut v_bytes);
+    v_bytes
 }
 
 /// Derives the target vector `t` from a message digest (`M_digest`) and a salt (`Salt`)
@@ -75,9 +141,29 @@ pub fn shake256_xof_derive_p3(seed_pk: &SeedPK, params: &MayoParams) -> Vec<u8>
 /// # Returns
 /// A `Vec<u8>` representing the target vector `t`, with a length of `ceil(m/2)` bytes.
 pub fn shake256_derive_target_t(m_digest: &MessageDigest, salt: &Salt, params: &MayoParams) -> Vec<u8> {
+    shake256_derive_target_t_with_binding(m_digest, salt, None, params)
+}
+
+/// Domain separation tag absorbed ahead of the public key fingerprint in the target derivation.
+const PK_BINDING_TAG: &[u8] = b"MAYO-PK-BINDING";
+
+/// Computes a fingerprint of an expanded public key, SHAKE256(epk), of `digest_bytes` length.
+/// Used to bind signatures to a public key (see `SignatureOptions::bind_public_key`).
+pub fn public_key_fingerprint(epk: &ExpandedPublicKey, params: &MayoParams) -> Vec<u8> {
+    shake256_digest(&epk.0, params).0
+}
+
+/// Same as `shake256_derive_target_t`, but when `pk_fingerprint` is given it is absorbed
+/// after the salt, preceded by a domain tag: t = SHAKE256(M_digest || salt || tag || fingerprint).
+/// With `None` the output is identical to `shake256_derive_target_t`.
+pub fn shake256_derive_target_t_with_binding(m_digest: &MessageDigest, salt: &Salt, pk_fingerprint: Option<&[u8]>, params: &MayoParams) -> Vec<u8> {
     let mut hasher = Shake256::default();
     hasher.update(&m_digest.0);
     hasher.update(&salt.0);
+    if let Some(fingerprint) = pk_fingerprint {
+        hasher.update(PK_BINDING_TAG);
+        hasher.update(fingerprint);
+    }
     let mut reader = hasher.finalize_xof();
     
     // Each element of t is in GF(q). For q=16, each element is 4 bits.
@@ -1,12 +1,14 @@
 //! Implements MAYO Compact Key Generation (Algorithm 5), Secret Key Expansion (Algorithm 6), and Public Key Expansion (Algorithm 7).
 
-use crate::types::{CompactSecretKey, CompactPublicKey, ExpandedSecretKey, ExpandedPublicKey, SeedSK, SeedPK, GFMatrix, GFVector};
+use crate::types::{CompactSecretKey, CompactPublicKey, ExpandedSecretKey, ExpandedPublicKey, SeedSK, SeedPK, GFMatrix};
 use crate::params::{MayoParams}; // MayoVariantParams is accessed via MayoParams.variant()
-use crate::hash::{shake256_xof_derive_pk_seed_and_o, shake256_xof_derive_p3};
-use crate::codec::{decode_o_matrix, decode_p1_matrices, decode_p2_matrices, encode_gf_elements};
+use crate::hash::shake256_xof_derive_pk_seed_and_o;
+use crate::codec::{decode_o_matrix, decode_p1_matrices, decode_p2_matrices, encode_l_matrices, encode_p3_matrices};
 use crate::aes_ctr::{derive_p1_bytes, derive_p2_bytes};
-use crate::matrix::{matrix_add, matrix_transpose, matrix_mul};
-use getrandom::getrandom;
+use crate::matrix::{matrix_add_assign, matrix_transpose, matrix_mul, matrix_upper, matrix_rank};
+use rand_core::{OsRng, RngCore};
+use crate::error::MayoError;
+use crate::types::{wipe_bytes, WipeOnDrop};
 
 /// Implements MAYO.CompactKeyGen (Algorithm 5 from the MAYO specification).
 /// Generates a compact secret key (csk) and a compact public key (cpk).
@@ -16,126 +18,277 @@ use getrandom::getrandom;
 ///
 /// # Returns
 /// `Ok((CompactSecretKey, CompactPublicKey))` if successful.
-/// `Err(&'static str)` if random number generation fails or if derived byte lengths are inconsistent.
-pub fn compact_key_gen(params_enum: &MayoParams) -> Result<(CompactSecretKey, CompactPublicKey), &'static str> {
+/// `Err(MayoError::RngFailure)` if random number generation fails, or another `MayoError` if derived byte lengths are inconsistent.
+pub fn compact_key_gen(params_enum: &MayoParams) -> Result<(CompactSecretKey, CompactPublicKey), MayoError> {
+    compact_key_gen_with_rng(&mut OsRng, params_enum)
+}
+
+/// Same as `compact_key_gen`, drawing the secret key seed from `rng` instead of the
+/// operating system, e.g. a seeded RNG in tests or a hardware RNG.
+pub fn compact_key_gen_with_rng<R: RngCore>(rng: &mut R, params_enum: &MayoParams) -> Result<(CompactSecretKey, CompactPublicKey), MayoError> {
     let params = params_enum.variant(); // Get MayoVariantParams
+    params.validate()?;
 
     // 1. Generate a random secret key seed (seed_sk)
     //    seed_sk <-$_R {0,1}^(lambda_seed)  (lambda_seed = params.sk_seed_bytes * 8)
     let mut seedsk_bytes = vec![0u8; params.sk_seed_bytes];
-    getrandom(&mut seedsk_bytes).map_err(|_| "Failed to generate random seedsk")?;
-    let seedsk = SeedSK(seedsk_bytes);
+    if rng.try_fill_bytes(&mut seedsk_bytes).is_err() {
+        wipe_bytes(&mut seedsk_bytes);
+        return Err(MayoError::RngFailure("Failed to generate random seedsk"));
+    }
+
+    let key_pair = compact_key_gen_from_seed(&seedsk_bytes, params_enum);
+    // The csk holds its own copy of the seed; this one is no longer needed.
+    wipe_bytes(&mut seedsk_bytes);
+    key_pair
+}
+
+/// MAYO.CompactKeyGen with a caller-provided secret key seed instead of a random one, for
+/// reproducible test vectors and deterministic key derivation. The same seed always gives
+/// the same key pair. `seed` must be exactly `sk_seed_bytes` long.
+pub fn compact_key_gen_from_seed(seed: &[u8], params_enum: &MayoParams) -> Result<(CompactSecretKey, CompactPublicKey), MayoError> {
+    let params = params_enum.variant();
+    params.validate()?;
+    if seed.len() != params.sk_seed_bytes {
+        return Err(MayoError::InvalidKeyLength("Secret key seed has incorrect length"));
+    }
+    let seedsk = SeedSK(seed.to_vec());
+
+    let cpk = compact_public_key_from_seedsk(&seedsk, params_enum)?;
+
+    // 4. Construct csk (CompactSecretKey is just SeedSK)
+    //    csk = seed_sk
+    let csk = CompactSecretKey(seedsk.0.clone()); // SeedSK wipes its own copy on drop
+
+    Ok((csk, cpk))
+}
+
+/// Steps 2, 3 and 5 of MAYO.CompactKeyGen: derives cpk = seed_pk || P3_bytes from seed_sk.
+fn compact_public_key_from_seedsk(seedsk: &SeedSK, params_enum: &MayoParams) -> Result<CompactPublicKey, MayoError> {
+    let params = params_enum.variant();
 
     // 2. Derive seed_pk and O_bytes from seed_sk using SHAKE256
     //    (seed_pk || O_bytes) = SHAKE256(seed_sk, params.pk_seed_bytes + params.O_bytes)
     //    The shake256_xof_derive_pk_seed_and_o function handles this logic.
     //    O_bytes itself isn't directly part of the simplified csk/cpk here, but is derived.
-    let (seedpk, _o_bytes) = shake256_xof_derive_pk_seed_and_o(&seedsk, params_enum);
+    let (seedpk, o_bytes) = shake256_xof_derive_pk_seed_and_o(seedsk, params_enum);
+    let o_bytes = WipeOnDrop(o_bytes);
+    let o_matrix = WipeOnDrop(decode_o_matrix(&o_bytes, params)?);
 
-    // 3. Derive P3_bytes from seed_pk using SHAKE256
-    //    P3_bytes = SHAKE256(seed_pk, params.P3_bytes)
-    //    The shake256_xof_derive_p3 function handles this.
-    let p3_bytes = shake256_xof_derive_p3(&seedpk, params_enum);
+    // 3. Compute P3_i = Upper(-O^T (P1_i O + P2_i)) from O and the public P1, P2
+    let p3_bytes = compute_p3_bytes(&seedpk, &o_matrix, params_enum)?;
     
-    // Ensure derived P3_bytes has the expected length as defined in params.
-    // This check is good practice, though shake256_xof_derive_p3 should already produce correct length.
+    // Ensure P3_bytes has the expected length as defined in params.
     if p3_bytes.len() != params.p3_bytes {
-         return Err("Derived P3_bytes length does not match params.p3_bytes");
+         return Err(MayoError::DimensionMismatch("Derived P3_bytes length does not match params.p3_bytes"));
     }
 
-    // 4. Construct csk (CompactSecretKey is just SeedSK)
-    //    csk = seed_sk
-    let csk = CompactSecretKey(seedsk.0); // .0 extracts the Vec<u8> from SeedSK
-
     // 5. Construct cpk (CompactPublicKey is seed_pk || P3_bytes)
     //    cpk = seed_pk || P3_bytes
     let mut cpk_bytes = Vec::with_capacity(params.pk_seed_bytes + params.p3_bytes);
     cpk_bytes.extend_from_slice(&seedpk.0); // .0 extracts Vec<u8> from SeedPK
     cpk_bytes.extend_from_slice(&p3_bytes);
-    let cpk = CompactPublicKey(cpk_bytes);
+    Ok(CompactPublicKey(cpk_bytes))
+}
 
-    Ok((csk, cpk))
+impl CompactSecretKey {
+    /// Derives the matching compact public key (seed_pk || P3_bytes) from this secret key,
+    /// without generating a new key pair.
+    pub fn public_key(&self, params_enum: &MayoParams) -> Result<CompactPublicKey, MayoError> {
+        let params = params_enum.variant();
+        params.validate()?;
+        if self.0.len() != params.sk_seed_bytes {
+            return Err(MayoError::InvalidKeyLength("Compact secret key has incorrect length"));
+        }
+        compact_public_key_from_seedsk(&SeedSK(self.0.clone()), params_enum)
+    }
+}
+
+/// The P1, P2 and P3 byte blobs of an expanded public key, in that order.
+pub type EpkComponents = (Vec<u8>, Vec<u8>, Vec<u8>);
+
+impl ExpandedSecretKey {
+    /// Decodes the secret (n-o) x o oil space matrix O stored after seed_sk in the esk.
+    /// Works for both the full and the compact esk layout, which share this prefix.
+    pub fn decode_o(&self, params_enum: &MayoParams) -> Result<GFMatrix, MayoError> {
+        let params = params_enum.variant();
+        let o_start = params.sk_seed_bytes;
+        let o_end = o_start + params.o_bytes;
+        if self.0.len() < o_end {
+            return Err(MayoError::InvalidKeyLength("Expanded secret key is too short to contain O"));
+        }
+        decode_o_matrix(&self.0[o_start..o_end], params)
+    }
+}
+
+impl ExpandedPublicKey {
+    /// Assembles an epk = P1_bytes || P2_bytes || P3_bytes from its separately stored parts.
+    /// Returns Err if any part does not have the length given by `params`.
+    pub fn from_components(p1_bytes: &[u8], p2_bytes: &[u8], p3_bytes: &[u8], params_enum: &MayoParams) -> Result<Self, MayoError> {
+        let params = params_enum.variant();
+        if p1_bytes.len() != params.p1_bytes {
+            return Err(MayoError::InvalidKeyLength("P1_bytes component has incorrect length"));
+        }
+        if p2_bytes.len() != params.p2_bytes {
+            return Err(MayoError::InvalidKeyLength("P2_bytes component has incorrect length"));
+        }
+        if p3_bytes.len() != params.p3_bytes {
+            return Err(MayoError::InvalidKeyLength("P3_bytes component has incorrect length"));
+        }
+        let mut epk_bytes = Vec::with_capacity(params.epk_bytes());
+        epk_bytes.extend_from_slice(p1_bytes);
+        epk_bytes.extend_from_slice(p2_bytes);
+        epk_bytes.extend_from_slice(p3_bytes);
+        Ok(ExpandedPublicKey(epk_bytes))
+    }
+
+    /// Splits the epk into owned copies of its P1, P2 and P3 byte blobs, e.g. for storage
+    /// in separate database columns. Inverse of `from_components`.
+    pub fn split_components(&self, params_enum: &MayoParams) -> Result<EpkComponents, MayoError> {
+        let params = params_enum.variant();
+        if self.0.len() != params.epk_bytes() {
+            return Err(MayoError::InvalidKeyLength("Expanded public key has incorrect length"));
+        }
+        let (p1_bytes, rest) = self.0.split_at(params.p1_bytes);
+        let (p2_bytes, p3_bytes) = rest.split_at(params.p2_bytes);
+        Ok((p1_bytes.to_vec(), p2_bytes.to_vec(), p3_bytes.to_vec()))
+    }
+}
+
+/// Checks that a compact secret key and compact public key belong together, by re-deriving
+/// seed_pk and P3 from the csk and comparing them with the cpk in constant time.
+/// Returns `Ok(false)` for a mismatched or corrupted cpk.
+pub fn validate_keypair(csk: &CompactSecretKey, cpk: &CompactPublicKey, params_enum: &MayoParams) -> Result<bool, MayoError> {
+    let expected_cpk = csk.public_key(params_enum)?;
+    Ok(constant_time_eq(&expected_cpk.0, &cpk.0))
+}
+
+/// Compares two byte strings without branching on their contents.
+/// Only the lengths, which are public, can end the comparison early.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+/// Checks that the oil space matrix O ((n-o) x o) has full column rank o.
+/// A rank-deficient O spans a smaller oil space and weakens the scheme.
+/// `expand_sk` rejects keys that fail this check.
+pub fn validate_oil_space(o_matrix: &GFMatrix) -> Result<bool, MayoError> {
+    if o_matrix.num_cols() == 0 {
+        return Err(MayoError::DimensionMismatch("Oil space matrix must have at least one column"));
+    }
+    Ok(matrix_rank(o_matrix) == o_matrix.num_cols())
+}
+
+/// Computes P3_i = Upper(-O^T (P1_i O + P2_i)) for every P1_i, P2_i.
+///
+/// This choice of P3 makes the public map vanish on the oil space: for s = (O x, x),
+/// s^T P_i s = x^T (O^T P1_i O + O^T P2_i + P3_i) x = 0, since Upper(M) has the same
+/// quadratic form as M and -1 = 1 in GF(16).
+pub fn compute_p3_matrices(o_matrix: &GFMatrix, p1_matrices: &[GFMatrix], p2_matrices: &[GFMatrix]) -> Result<Vec<GFMatrix>, MayoError> {
+    if p1_matrices.len() != p2_matrices.len() {
+        return Err(MayoError::DimensionMismatch("Incorrect number of P1 or P2 matrices"));
+    }
+    let o_transpose = matrix_transpose(o_matrix);
+    p1_matrices.iter().zip(p2_matrices).map(|(p1_i, p2_i)| {
+        // P1_i enters a dense product, so a caller-built P1_i with entries below the
+        // diagonal would silently yield a P3_i that does not match the encoded P1.
+        if cfg!(debug_assertions) {
+            p1_i.assert_upper_triangular();
+        }
+        // P1_i O + P2_i
+        let mut inner = matrix_mul(p1_i, o_matrix)?;
+        matrix_add_assign(&mut inner, p2_i)?;
+        // -O^T (P1_i O + P2_i); negation is the identity in characteristic 2
+        matrix_upper(&matrix_mul(&o_transpose, &inner)?)
+    }).collect()
+}
+
+/// Derives P1 and P2 from `seedpk` and returns the encoded P3 matrices (`params.p3_bytes` bytes),
+/// see `compute_p3_matrices`. Each P3_i is encoded as its packed upper triangle.
+pub fn compute_p3_bytes(seedpk: &SeedPK, o_matrix: &GFMatrix, params_enum: &MayoParams) -> Result<Vec<u8>, MayoError> {
+    let params = params_enum.variant();
+    let p1_matrices = decode_p1_matrices(&derive_p1_bytes(seedpk, params)?, params)?;
+    let p2_matrices = decode_p2_matrices(&derive_p2_bytes(seedpk, params)?, params)?;
+    let p3_matrices = compute_p3_matrices(o_matrix, &p1_matrices, &p2_matrices)?;
+    encode_p3_matrices(&p3_matrices, params)
 }
 
 /// Implements MAYO.ExpandSK (Algorithm 6 from the MAYO specification).
 /// Expands a compact secret key (csk) into an expanded secret key (esk).
-pub fn expand_sk(csk: &CompactSecretKey, params_enum: &MayoParams) -> Result<ExpandedSecretKey, &'static str> {
+pub fn expand_sk(csk: &CompactSecretKey, params_enum: &MayoParams) -> Result<ExpandedSecretKey, MayoError> {
     let params = params_enum.variant();
+    params.validate()?;
     
     // 1. Parse csk to get seedsk (csk is effectively seedsk)
     let seedsk = SeedSK(csk.0.clone()); // csk.0 is Vec<u8>
 
     // 2. Derive seedpk and O_bytes from seedsk
+    // O, L and their encodings are wiped on every exit path, including the early returns.
     let (seedpk, o_bytes) = shake256_xof_derive_pk_seed_and_o(&seedsk, params_enum);
+    let o_bytes = WipeOnDrop(o_bytes);
     if o_bytes.len() != params.o_bytes {
-        return Err("O_bytes length mismatch during derivation");
+        return Err(MayoError::DimensionMismatch("O_bytes length mismatch during derivation"));
     }
 
     // 3. Decode O_bytes into matrix O
-    let o_matrix = decode_o_matrix(&o_bytes, params)?;
+    let o_matrix = WipeOnDrop(decode_o_matrix(&o_bytes, params)?);
+    if !validate_oil_space(&o_matrix)? {
+        return Err(MayoError::InvalidKey("Oil space matrix O is rank-deficient"));
+    }
 
     // 4. Derive P1_all_bytes and P2_all_bytes from seedpk
-    let p1_all_bytes = derive_p1_bytes(&seedpk, params);
-    if p1_all_bytes.len() != params.p1_bytes {
-         return Err("P1_bytes length mismatch during derivation");
-    }
-    let p2_all_bytes = derive_p2_bytes(&seedpk, params);
-     if p2_all_bytes.len() != params.p2_bytes {
-         return Err("P2_bytes length mismatch during derivation");
-    }
+    let p1_all_bytes = derive_p1_bytes(&seedpk, params)?;
+    let p2_all_bytes = derive_p2_bytes(&seedpk, params)?;
 
     // 5. Decode P1_all_bytes and P2_all_bytes into matrices {P(1)i} and {P(2)i}
     let p1_matrices = decode_p1_matrices(&p1_all_bytes, params)?;
     let p2_matrices = decode_p2_matrices(&p2_all_bytes, params)?;
 
     if p1_matrices.len() != params.m || p2_matrices.len() != params.m {
-        return Err("Incorrect number of P1 or P2 matrices decoded");
+        return Err(MayoError::DimensionMismatch("Incorrect number of P1 or P2 matrices decoded"));
     }
 
     // 6. Compute secret matrices Li
-    let mut l_matrices: Vec<GFMatrix> = Vec::with_capacity(params.m);
+    let mut l_matrices: WipeOnDrop<Vec<GFMatrix>> = WipeOnDrop(Vec::with_capacity(params.m));
     for i in 0..params.m {
+        // In-place additions avoid two temporaries per L_i (see benches/l_matrix.rs)
         let p1_i = &p1_matrices[i];
-        let p1_i_t = matrix_transpose(p1_i);
         // P(1)i + P(1)Ti
-        let sum_p1_p1t = matrix_add(p1_i, &p1_i_t)?; 
-        // (P(1)i + P(1)Ti)O
-        let term1 = matrix_mul(&sum_p1_p1t, &o_matrix)?;
+        let mut sum_p1_p1t = matrix_transpose(p1_i);
+        matrix_add_assign(&mut sum_p1_p1t, p1_i)?;
+        // (P(1)i + P(1)Ti)O, pushed first so the guard covers it
+        l_matrices.push(matrix_mul(&sum_p1_p1t, &o_matrix)?);
         // Li = (P(1)i + P(1)Ti)O + P(2)i
-        let l_i = matrix_add(&term1, &p2_matrices[i])?;
-        l_matrices.push(l_i);
+        let l_i = l_matrices.last_mut().expect("L_i was just pushed");
+        matrix_add_assign(l_i, &p2_matrices[i])?;
     }
 
-    // Flatten all L matrices into one long GFVector then encode.
-    let mut l_elements_flat: GFVector = Vec::new();
-    for l_i in &l_matrices {
-        l_elements_flat.extend_from_slice(&l_i.data);
-    }
-    let l_all_bytes = encode_gf_elements(&l_elements_flat);
-    let expected_l_elements = params.m * (params.n - params.o) * params.o;
-    let expected_l_bytes_len = MayoParams::bytes_for_gf16_elements(expected_l_elements);
-    if l_all_bytes.len() != expected_l_bytes_len {
-        return Err("L_all_bytes length mismatch during encoding");
-    }
+    // 7. Encode all L matrices as one packed stream
+    let l_all_bytes = WipeOnDrop(encode_l_matrices(&l_matrices, params)?);
 
     // 8. Construct esk: seedsk || O_bytes || P1_all_bytes || l_all_bytes
-    let mut esk_bytes = Vec::new();
+    let mut esk_bytes = Vec::with_capacity(params.esk_capacity());
     esk_bytes.extend_from_slice(&seedsk.0);
     esk_bytes.extend_from_slice(&o_bytes);
     esk_bytes.extend_from_slice(&p1_all_bytes);
     esk_bytes.extend_from_slice(&l_all_bytes);
-    
+
+    // The guards wipe o_bytes, O, the L_i and l_all_bytes here, leaving the esk as the only copy.
     Ok(ExpandedSecretKey(esk_bytes))
 }
 
 /// Implements MAYO.ExpandPK (Algorithm 7 from the MAYO specification).
 /// Expands a compact public key (cpk) into an expanded public key (epk).
-pub fn expand_pk(cpk: &CompactPublicKey, params_enum: &MayoParams) -> Result<ExpandedPublicKey, &'static str> {
+pub fn expand_pk(cpk: &CompactPublicKey, params_enum: &MayoParams) -> Result<ExpandedPublicKey, MayoError> {
     let params = params_enum.variant();
+    params.validate()?;
 
     // 1. Parse cpk to extract seedpk and P3_byte_string
     if cpk.0.len() != params.pk_seed_bytes + params.p3_bytes {
-        return Err("Compact public key has incorrect length");
+        return Err(MayoError::InvalidKeyLength("Compact public key has incorrect length"));
     }
     let seedpk_bytes = &cpk.0[0..params.pk_seed_bytes];
     let p3_all_bytes_from_cpk = &cpk.0[params.pk_seed_bytes..];
@@ -143,17 +296,11 @@ pub fn expand_pk(cpk: &CompactPublicKey, params_enum: &MayoParams) -> Result<Exp
     let seedpk = SeedPK(seedpk_bytes.to_vec());
 
     // 2. Derive P1_all_bytes and P2_all_bytes from seedpk
-    let p1_all_bytes = derive_p1_bytes(&seedpk, params);
-    if p1_all_bytes.len() != params.p1_bytes {
-            return Err("P1_bytes length mismatch during derivation");
-    }
-    let p2_all_bytes = derive_p2_bytes(&seedpk, params);
-    if p2_all_bytes.len() != params.p2_bytes {
-            return Err("P2_bytes length mismatch during derivation");
-    }
+    let p1_all_bytes = derive_p1_bytes(&seedpk, params)?;
+    let p2_all_bytes = derive_p2_bytes(&seedpk, params)?;
 
     // 3. Construct epk: P1_all_bytes || P2_all_bytes || P3_all_bytes_from_cpk
-    let mut epk_bytes = Vec::with_capacity(params.p1_bytes + params.p2_bytes + params.p3_bytes);
+    let mut epk_bytes = Vec::with_capacity(params.epk_bytes());
     epk_bytes.extend_from_slice(&p1_all_bytes);
     epk_bytes.extend_from_slice(&p2_all_bytes);
     epk_bytes.extend_from_slice(p3_all_bytes_from_cpk);
@@ -161,6 +308,14 @@ pub fn expand_pk(cpk: &CompactPublicKey, params_enum: &MayoParams) -> Result<Exp
     Ok(ExpandedPublicKey(epk_bytes))
 }
 
+/// Generates a key pair and expands the public key once, for callers that verify many
+/// signatures under it. Returns the compact secret key and the expanded public key.
+pub fn keypair_expanded(params_enum: &MayoParams) -> Result<(CompactSecretKey, ExpandedPublicKey), MayoError> {
+    let (csk, cpk) = compact_key_gen(params_enum)?;
+    let epk = expand_pk(&cpk, params_enum)?;
+    Ok((csk, epk))
+}
+
 
 #[cfg(test)]
 mod tests {
@@ -190,6 +345,92 @@ mod tests {
         assert_ne!(cpk.0, cpk2.0, "CPKs from subsequent calls should be different");
     }
 
+    #[test]
+    fn test_compact_key_gen_from_seed_is_deterministic() {
+        for params_enum in MayoParams::all_variants() {
+            let params = params_enum.variant();
+            let seed_a = vec![0x5A; params.sk_seed_bytes];
+            let seed_b = vec![0xA5; params.sk_seed_bytes];
+
+            let (csk_a, cpk_a) = compact_key_gen_from_seed(&seed_a, &params_enum).unwrap();
+            assert_eq!(csk_a.0, seed_a);
+            assert_eq!(compact_key_gen_from_seed(&seed_a, &params_enum).unwrap(), (csk_a.clone(), cpk_a.clone()));
+            assert_eq!(csk_a.public_key(&params_enum), Ok(cpk_a.clone()));
+
+            let (csk_b, cpk_b) = compact_key_gen_from_seed(&seed_b, &params_enum).unwrap();
+            assert_ne!(csk_a, csk_b);
+            assert_ne!(cpk_a, cpk_b);
+
+            assert_eq!(compact_key_gen_from_seed(&seed_a[1..], &params_enum),
+                       Err(MayoError::InvalidKeyLength("Secret key seed has incorrect length")));
+        }
+    }
+
+    #[test]
+    fn test_validate_keypair() {
+        let params_enum = MayoParams::mayo1();
+        let (csk, cpk) = compact_key_gen(&params_enum).unwrap();
+        assert_eq!(validate_keypair(&csk, &cpk, &params_enum), Ok(true));
+
+        // One flipped byte in either the seed_pk or the P3 part of the cpk
+        for idx in [0, cpk.0.len() - 1] {
+            let mut corrupted = cpk.clone();
+            corrupted.0[idx] ^= 0x01;
+            assert_eq!(validate_keypair(&csk, &corrupted, &params_enum), Ok(false));
+        }
+
+        let (_, other_cpk) = compact_key_gen(&params_enum).unwrap();
+        assert_eq!(validate_keypair(&csk, &other_cpk, &params_enum), Ok(false));
+        let truncated = CompactPublicKey(cpk.0[..cpk.0.len() - 1].to_vec());
+        assert_eq!(validate_keypair(&csk, &truncated, &params_enum), Ok(false));
+        assert!(validate_keypair(&CompactSecretKey(vec![0u8; 3]), &cpk, &params_enum).is_err());
+    }
+
+    #[test]
+    fn test_compact_secret_key_public_key() {
+        for params_enum in [MayoParams::mayo1(), MayoParams::mayo2()] {
+            let (csk, cpk) = compact_key_gen(&params_enum).unwrap();
+            assert_eq!(csk.public_key(&params_enum), Ok(cpk));
+        }
+        assert!(CompactSecretKey(vec![0u8; 3]).public_key(&MayoParams::mayo1()).is_err());
+    }
+
+    #[test]
+    fn test_epk_split_components_round_trip() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let (_csk, cpk) = compact_key_gen(&params_enum).unwrap();
+        let epk = expand_pk(&cpk, &params_enum).unwrap();
+
+        let (p1, p2, p3) = epk.split_components(&params_enum).unwrap();
+        assert_eq!((p1.len(), p2.len(), p3.len()), (params.p1_bytes, params.p2_bytes, params.p3_bytes));
+        assert_eq!(&p3[..], &cpk.0[params.pk_seed_bytes..]);
+        assert_eq!(ExpandedPublicKey::from_components(&p1, &p2, &p3, &params_enum), Ok(epk.clone()));
+
+        assert!(ExpandedPublicKey::from_components(&p2, &p2, &p3, &params_enum).is_err());
+        let truncated = ExpandedPublicKey(epk.0[1..].to_vec());
+        assert!(truncated.split_components(&params_enum).is_err());
+    }
+
+    #[test]
+    fn test_validate_oil_space() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let (csk, _cpk) = compact_key_gen(&params_enum).unwrap();
+        let (_, o_bytes) = shake256_xof_derive_pk_seed_and_o(&SeedSK(csk.0.clone()), &params_enum);
+        let mut o_matrix = decode_o_matrix(&o_bytes, params).unwrap();
+        assert_eq!(validate_oil_space(&o_matrix), Ok(true));
+
+        // Make the last column a copy of the first: rank drops below o
+        for r in 0..o_matrix.num_rows() {
+            let val = o_matrix.get_unsafe(r, 0);
+            o_matrix.set_val(r, params.o - 1, val);
+        }
+        assert_eq!(validate_oil_space(&o_matrix), Ok(false));
+        assert_eq!(validate_oil_space(&GFMatrix::zero(params.n - params.o, params.o)), Ok(false));
+        assert!(validate_oil_space(&GFMatrix::zero(4, 0)).is_err());
+    }
+
     #[test]
     fn test_compact_keygen_mayo1() {
         test_compact_keygen_for_variant(&MayoParams::mayo1());
@@ -227,14 +468,14 @@ mod tests {
         // From params.rs for MAYO2:
         // sk_seed_bytes: 24
         // pk_seed_bytes: 16
-        // p3_bytes: 10944
+        // p3_bytes: 5472
         assert_eq!(variant_params.sk_seed_bytes, 24);
         assert_eq!(variant_params.pk_seed_bytes, 16);
-        assert_eq!(variant_params.p3_bytes, 5504);
+        assert_eq!(variant_params.p3_bytes, 5472);
 
         let (csk, cpk) = compact_key_gen(&params_mayo2).unwrap();
         assert_eq!(csk.0.len(), 24);
-        assert_eq!(cpk.0.len(), 16 + 5504);
+        assert_eq!(cpk.0.len(), 16 + 5472);
     }
 
     fn test_expand_sk_for_variant(params_enum: &MayoParams) {
@@ -246,6 +487,7 @@ mod tests {
         let esk = esk_res.unwrap();
 
         assert!(!esk.0.is_empty(), "Expanded secret key should not be empty");
+        assert_eq!(esk.0.len(), params_variant.esk_capacity(), "ESK length differs from reported capacity");
 
         // Verify starting part of esk is csk
         assert_eq!(&esk.0[0..params_variant.sk_seed_bytes], &csk.0[..],
@@ -267,13 +509,12 @@ mod tests {
         let esk_p1_bytes = &esk.0[p1_bytes_start..p1_bytes_end];
 
         // Re-derive p1_all_bytes for comparison
-        let p1_all_bytes_derived = derive_p1_bytes(&_seedpk_for_check, params_variant);
+        let p1_all_bytes_derived = derive_p1_bytes(&_seedpk_for_check, params_variant).unwrap();
         assert_eq!(esk_p1_bytes, &p1_all_bytes_derived[..], "ESK p1_bytes part mismatch");
         
         // Verify L_all_bytes length
         let l_bytes_start = p1_bytes_end;
-        let num_l_elements = params_variant.m * (params_variant.n - params_variant.o) * params_variant.o;
-        let expected_l_bytes_len = MayoParams::bytes_for_gf16_elements(num_l_elements);
+        let expected_l_bytes_len = params_variant.l_bytes();
         assert_eq!(esk.0.len(), params_variant.sk_seed_bytes + params_variant.o_bytes + params_variant.p1_bytes + expected_l_bytes_len,
                    "Total ESK length mismatch");
         let esk_l_bytes = &esk.0[l_bytes_start..];
@@ -302,14 +543,15 @@ mod tests {
         
         let expected_total_len = params_variant.p1_bytes + params_variant.p2_bytes + params_variant.p3_bytes;
         assert_eq!(epk.0.len(), expected_total_len, "EPK total length mismatch");
+        assert_eq!(epk.0.len(), params_variant.epk_bytes(), "EPK length differs from params.epk_bytes");
 
         // Verify components of epk.0
         // Re-derive P1_all_bytes and P2_all_bytes from seedpk part of cpk
         let seedpk_bytes_from_cpk = &cpk.0[0..params_variant.pk_seed_bytes];
         let seedpk_for_check = SeedPK(seedpk_bytes_from_cpk.to_vec());
         
-        let p1_all_bytes_derived = derive_p1_bytes(&seedpk_for_check, params_variant);
-        let p2_all_bytes_derived = derive_p2_bytes(&seedpk_for_check, params_variant);
+        let p1_all_bytes_derived = derive_p1_bytes(&seedpk_for_check, params_variant).unwrap();
+        let p2_all_bytes_derived = derive_p2_bytes(&seedpk_for_check, params_variant).unwrap();
         let p3_all_bytes_from_cpk = &cpk.0[params_variant.pk_seed_bytes..];
 
         assert_eq!(&epk.0[0..params_variant.p1_bytes], &p1_all_bytes_derived[..],
@@ -334,4 +576,92 @@ mod tests {
     fn test_expand_pk_mayo2() {
         test_expand_pk_for_variant(&MayoParams::mayo2());
     }
+
+    #[test]
+    fn test_esk_decode_o_matches_seed_derivation() {
+        for params_enum in MayoParams::all_variants() {
+            let params = params_enum.variant();
+            let (csk, _) = compact_key_gen(&params_enum).unwrap();
+            let esk = expand_sk(&csk, &params_enum).unwrap();
+            let o_matrix = esk.decode_o(&params_enum).unwrap();
+            assert_eq!((o_matrix.num_rows(), o_matrix.num_cols()), (params.n - params.o, params.o));
+
+            let (_, o_bytes) = shake256_xof_derive_pk_seed_and_o(&SeedSK(csk.0.clone()), &params_enum);
+            assert_eq!(o_matrix, decode_o_matrix(&o_bytes, params).unwrap());
+            assert_eq!(ExpandedSecretKey(esk.0[..params.sk_seed_bytes].to_vec()).decode_o(&params_enum),
+                       Err(MayoError::InvalidKeyLength("Expanded secret key is too short to contain O")));
+        }
+    }
+
+    #[test]
+    fn test_p3_satisfies_key_relation() {
+        use crate::codec::decode_p3_matrices;
+        use crate::verify::{compute_unwhipped_p_s, decode_epk_matrices};
+        use crate::types::{GFElement, GFVector};
+
+        for params_enum in MayoParams::all_variants() {
+            let params = params_enum.variant();
+            let (csk, cpk) = compact_key_gen(&params_enum).unwrap();
+            let epk = expand_pk(&cpk, &params_enum).unwrap();
+            let (p1, p2, p3) = decode_epk_matrices(&epk, params).unwrap();
+            assert!(p3.iter().all(GFMatrix::is_upper_triangular));
+            assert_eq!(p3, decode_p3_matrices(&cpk.0[params.pk_seed_bytes..], params).unwrap());
+
+            // P*(s) vanishes on the oil space: s = (O x, x) for any x.
+            let (_, o_bytes) = shake256_xof_derive_pk_seed_and_o(&SeedSK(csk.0.clone()), &params_enum);
+            let o_matrix = decode_o_matrix(&o_bytes, params).unwrap();
+            for seed in 1..4u8 {
+                let x: GFVector = (0..params.o).map(|j| GFElement((j as u8).wrapping_mul(seed).wrapping_add(seed) & 0x0F)).collect();
+                let x_col = GFMatrix::new_with_data(params.o, 1, x.clone());
+                let mut s_vector = matrix_mul(&o_matrix, &x_col).unwrap().data;
+                s_vector.extend_from_slice(&x);
+                assert_eq!(compute_unwhipped_p_s(&s_vector, &p1, &p2, &p3, params).unwrap(), vec![GFElement(0); params.m]);
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "not upper triangular")]
+    fn test_compute_p3_rejects_dense_p1_in_debug() {
+        use crate::types::GFElement;
+        let o_matrix = GFMatrix::zero(2, 1);
+        let dense_p1 = GFMatrix::new_with_data(2, 2, vec![GFElement(1), GFElement(0), GFElement(3), GFElement(1)]);
+        let _ = compute_p3_matrices(&o_matrix, &[dense_p1], &[GFMatrix::zero(2, 1)]);
+    }
+
+    /// SHA-256 over csk || cpk || esk || epk || sig for a fixed seed, per variant, where sig is
+    /// the `sign_message_deterministic` signature of `KAT_MESSAGE`. Any change to key
+    /// generation, expansion or signing output changes these; update them only together
+    /// with an intentional algorithm change.
+    const KAT_KEY_AND_SIGNATURE_SHA256: [(&str, &str); 2] = [
+        ("MAYO1", "480c111e46743887104ca93c86f2ce97d2ed63a9d51d833b6cf074682419980b"),
+        ("MAYO2", "9f55554b586eea62842f7deae29a239c3bc7518713c23108748f04c346363cbd"),
+    ];
+    const KAT_MESSAGE: &[u8] = b"MAYO known-answer message";
+
+    #[test]
+    fn test_kat_key_and_signature_hash() {
+        use sha2::{Digest, Sha256};
+        use crate::sign::sign_message_deterministic;
+        use crate::types::Message;
+
+        for (name, expected_hex) in KAT_KEY_AND_SIGNATURE_SHA256 {
+            let params_enum = MayoParams::get_params_by_name(name).unwrap();
+            let params = params_enum.variant();
+            let csk = CompactSecretKey((0..params.sk_seed_bytes as u8).collect());
+            let cpk = csk.public_key(&params_enum).unwrap();
+            let esk = expand_sk(&csk, &params_enum).unwrap();
+            let epk = expand_pk(&cpk, &params_enum).unwrap();
+            let signature = sign_message_deterministic(&esk, &Message(KAT_MESSAGE.to_vec()), &params_enum).unwrap();
+
+            let mut hasher = Sha256::new();
+            for part in [&csk.0, &cpk.0, &esk.0, &epk.0, &signature.0] {
+                hasher.update(part);
+            }
+            let actual_hex: String = hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect();
+            assert_eq!(actual_hex, expected_hex, "KAT hash changed for {}", name);
+        }
+    }
+
 }
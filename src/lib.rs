@@ -1,6 +1,7 @@
 // use wasm_bindgen::prelude::*; // Removed as per compiler warning
 // use blake2::{Blake2b512, Digest}; // Removed as per compiler warning
 
+pub mod error;
 pub mod params;
 pub mod types;
 pub mod hash;
@@ -15,8 +16,11 @@ pub mod verify;
 
 pub mod api;
 pub use api::{keypair, sign, open};
+pub use error::MayoError;
 
+#[cfg(feature = "spacetime")]
 pub mod spacetime_hash;
+#[cfg(feature = "spacetime")]
 pub use spacetime_hash::hash_compact_secret_key;
 
 // Placeholder for any top-level library functions or re-exports if needed in the future.
@@ -33,4 +37,122 @@ mod tests {
     fn it_works_stub() {
         assert_eq!(2 + 2, 4);
     }
+
+    #[test]
+    fn test_message_digest_array_conversions() {
+        use crate::error::MayoError;
+        use crate::hash::message_digest;
+        use crate::params::MayoParams;
+        use crate::types::{Message, MessageDigest};
+
+        let params_enum = MayoParams::mayo1();
+        let digest = message_digest(&Message(b"fixed-length digest".to_vec()), &params_enum);
+        let array: &[u8; 32] = digest.as_array().unwrap();
+        assert_eq!(array.as_slice(), digest.0.as_slice());
+        assert_eq!(MessageDigest::from_array(*array), digest);
+
+        let short = MessageDigest(vec![0u8; 31]);
+        assert_eq!(short.as_array::<32>(), Err(MayoError::InvalidInput("Message digest has incorrect length")));
+    }
+
+    #[test]
+    fn test_message_conversions() {
+        use crate::types::Message;
+
+        assert_eq!(Message::from_str("hi").0, b"hi");
+        assert_eq!(Message::from("hi"), Message(b"hi".to_vec()));
+        assert_eq!(Message::from(&[0u8, 0xff][..]), Message(vec![0, 0xff]));
+    }
+
+    #[test]
+    fn test_key_and_signature_stream_round_trip() {
+        use std::io::Cursor;
+        use crate::keygen::compact_key_gen;
+        use crate::params::MayoParams;
+        use crate::types::{CompactPublicKey, CompactSecretKey, Signature};
+
+        let params_enum = MayoParams::mayo1();
+        let (csk, cpk) = compact_key_gen(&params_enum).unwrap();
+        let signature = Signature((0..params_enum.variant().sig_bytes()).map(|i| i as u8).collect());
+
+        // All three back to back in one stream, as a key file with a detached signature would be.
+        let mut stored = Vec::new();
+        csk.write_to(&mut stored, &params_enum).unwrap();
+        cpk.write_to(&mut stored, &params_enum).unwrap();
+        signature.write_to(&mut stored, &params_enum).unwrap();
+
+        let mut reader = Cursor::new(stored);
+        assert_eq!(CompactSecretKey::read_from(&mut reader, &params_enum).unwrap(), csk);
+        assert_eq!(CompactPublicKey::read_from(&mut reader, &params_enum).unwrap(), cpk);
+        assert_eq!(Signature::read_from(&mut reader, &params_enum).unwrap(), signature);
+
+        // Nothing left: a further read hits the end of the stream.
+        let err = Signature::read_from(&mut reader, &params_enum).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+
+        let err = CompactSecretKey(vec![0u8; 3]).write_to(&mut Vec::new(), &params_enum).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn test_epk_read_components_round_trip() {
+        use std::io::Cursor;
+        use crate::keygen::{compact_key_gen, expand_pk, expand_sk};
+        use crate::params::MayoParams;
+        use crate::sign::sign_message;
+        use crate::types::{ExpandedPublicKey, Message};
+        use crate::verify::verify_signature;
+
+        let params_enum = MayoParams::mayo1();
+        let (csk, cpk) = compact_key_gen(&params_enum).unwrap();
+        let epk = expand_pk(&cpk, &params_enum).unwrap();
+        let (p1_bytes, p2_bytes, p3_bytes) = epk.split_components(&params_enum).unwrap();
+
+        let mut p1_reader = Cursor::new(p1_bytes.clone());
+        let mut p2_reader = Cursor::new(p2_bytes.clone());
+        let mut p3_reader = Cursor::new(p3_bytes);
+        let read_epk = ExpandedPublicKey::read_components(&mut p1_reader, &mut p2_reader, &mut p3_reader, &params_enum).unwrap();
+        assert_eq!(read_epk, epk);
+
+        let esk = expand_sk(&csk, &params_enum).unwrap();
+        let message = Message(b"read_components".to_vec());
+        let signature = sign_message(&esk, &message, &params_enum).unwrap();
+        assert!(verify_signature(&read_epk, &message, &signature, &params_enum).unwrap());
+
+        // A truncated P3 stream is reported as an early end of file.
+        let mut p1_reader = Cursor::new(p1_bytes);
+        let mut p2_reader = Cursor::new(p2_bytes);
+        let mut short_p3 = Cursor::new(vec![0u8; params_enum.variant().p3_bytes - 1]);
+        let err = ExpandedPublicKey::read_components(&mut p1_reader, &mut p2_reader, &mut short_p3, &params_enum).unwrap_err();
+        assert_eq!(err.kind(), std::io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_tagged_compact_secret_key() {
+        use crate::error::MayoError;
+        use crate::keygen::compact_key_gen;
+        use crate::params::MayoParams;
+        use crate::types::CompactSecretKey;
+
+        for params_enum in MayoParams::all_variants() {
+            let (csk, _) = compact_key_gen(&params_enum).unwrap();
+            let tagged = csk.to_tagged_bytes(&params_enum);
+            assert_eq!(tagged.len(), 1 + params_enum.sk_seed_bytes() + 4);
+            assert_eq!(tagged[0], params_enum.variant_id());
+            assert_eq!(CompactSecretKey::from_tagged_bytes(&tagged), Ok((csk, params_enum)));
+
+            let mut corrupted = tagged.clone();
+            corrupted[1] ^= 0x01;
+            assert_eq!(CompactSecretKey::from_tagged_bytes(&corrupted), Err(MayoError::DecodeError("Tagged compact secret key checksum mismatch")));
+            let mut corrupted_checksum = tagged.clone();
+            *corrupted_checksum.last_mut().unwrap() ^= 0x80;
+            assert_eq!(CompactSecretKey::from_tagged_bytes(&corrupted_checksum), Err(MayoError::DecodeError("Tagged compact secret key checksum mismatch")));
+        }
+
+        assert_eq!(CompactSecretKey::from_tagged_bytes(&[1, 2, 3]), Err(MayoError::DecodeError("Tagged compact secret key is too short")));
+        let mut retagged = vec![0xFF; 1 + MayoParams::mayo1().sk_seed_bytes()];
+        let checksum = crate::codec::crc32(&retagged);
+        crate::codec::write_u32_be(&mut retagged, checksum);
+        assert_eq!(CompactSecretKey::from_tagged_bytes(&retagged), Err(MayoError::DecodeError("Unknown MAYO variant id")));
+    }
 }
@@ -1,7 +1,10 @@
 //! Implements matrix operations over GF(16).
 
 use crate::types::{GFElement, GFMatrix, GFVector};
-use crate::gf::{gf16_add, gf16_mul, gf16_sub}; // gf16_sub is same as gf16_add
+use crate::gf::{gf16_add, gf16_mul, gf16_sub, gf16_vec_madd}; // gf16_sub is same as gf16_add
+use crate::solver::gf16_inv;
+use crate::error::MayoError;
+use std::ops::{Index, IndexMut};
 
 // --- Implementation of GFMatrix helper functions ---
 // The GFMatrix struct is defined in types.rs. Here we add methods to it.
@@ -26,6 +29,12 @@ impl GFMatrix {
         }
     }
 
+    /// Creates a `rows x cols` matrix whose element (r, c) is `f(r, c)`.
+    pub fn from_fn(rows: usize, cols: usize, f: impl Fn(usize, usize) -> GFElement) -> Self {
+        let data = (0..rows).flat_map(|r| (0..cols).map(move |c| (r, c))).map(|(r, c)| f(r, c)).collect();
+        GFMatrix { data, rows, cols }
+    }
+
     /// Creates an identity matrix of a given size.
     pub fn identity(size: usize) -> Self {
         let mut matrix = Self::zero(size, size);
@@ -89,7 +98,8 @@ impl GFMatrix {
     }
 
     /// Creates a matrix from a `Vec` of `GFVector`s (rows).
-    /// Panics if `vecs` is empty or if rows have inconsistent lengths.
+    /// An empty `vecs` gives a 0x0 matrix and empty rows give an r x 0 matrix.
+    /// Panics if rows have inconsistent lengths.
     pub fn from_vectors(vecs: Vec<GFVector>) -> Self {
         if vecs.is_empty() {
             return Self::zero(0, 0); // Or panic, depending on desired behavior for empty input
@@ -105,15 +115,160 @@ impl GFMatrix {
         }
         GFMatrix { data, rows, cols }
     }
+
+    /// Computes A + c*I in place by adding `c` to each diagonal entry.
+    /// Returns Err if the matrix is not square.
+    pub fn add_scaled_identity(&mut self, c: GFElement) -> Result<(), MayoError> {
+        if self.rows != self.cols {
+            return Err(MayoError::DimensionMismatch("Matrix must be square to add a scaled identity"));
+        }
+        for i in 0..self.rows {
+            let val = gf16_add(self.get_unsafe(i, i), c);
+            self.set_val(i, i, val);
+        }
+        Ok(())
+    }
+
+    /// Returns true if the matrix is square and every entry below the diagonal is zero.
+    pub fn is_upper_triangular(&self) -> bool {
+        if self.rows != self.cols {
+            return false;
+        }
+        for r in 1..self.rows {
+            for c in 0..r {
+                if self.get_unsafe(r, c).0 != 0 {
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    /// Panics if the matrix is not upper triangular.
+    /// Used in debug builds where a caller-supplied matrix is assumed to be upper triangular.
+    pub fn assert_upper_triangular(&self) {
+        assert!(self.is_upper_triangular(),
+                "Matrix ({}x{}) is not upper triangular", self.rows, self.cols);
+    }
+
+    /// Returns the size*(size+1)/2 entries on and above the diagonal, row by row (r <= c).
+    /// This is the element order `decode_upper_triangular_matrix` consumes; entries below
+    /// the diagonal are not included. Returns Err if the matrix is not square.
+    pub fn upper_triangle_flat(&self) -> Result<GFVector, MayoError> {
+        if self.rows != self.cols {
+            return Err(MayoError::DimensionMismatch("Matrix must be square to extract its upper triangle"));
+        }
+        let mut elements = Vec::with_capacity(self.rows * (self.rows + 1) / 2);
+        for r in 0..self.rows {
+            elements.extend_from_slice(&self.data[r * self.cols + r..(r + 1) * self.cols]);
+        }
+        Ok(elements)
+    }
+
+    /// Returns the fraction of entries that are nonzero (0.0 for an empty matrix).
+    /// A random upper triangular n x n matrix has about (15/16) * (n+1)/(2n) nonzero entries.
+    pub fn nonzero_fraction(&self) -> f64 {
+        if self.data.is_empty() {
+            return 0.0;
+        }
+        let nonzero = self.data.iter().filter(|e| e.0 != 0).count();
+        nonzero as f64 / self.data.len() as f64
+    }
+
+    /// Returns true if the matrix is square and has full rank.
+    pub fn is_invertible(&self) -> bool {
+        self.rows == self.cols && matrix_rank(self) == self.rows
+    }
+
+    /// Creates a matrix with each cell filled by a random nibble drawn from `rng`.
+    #[cfg(test)]
+    pub fn random<R: rand_core::RngCore>(rows: usize, cols: usize, rng: &mut R) -> Self {
+        let mut bytes = vec![0u8; rows * cols];
+        rng.fill_bytes(&mut bytes);
+        let data = bytes.into_iter().map(|b| GFElement(b & 0x0F)).collect();
+        GFMatrix { data, rows, cols }
+    }
+
+    /// Creates a random square matrix, rejection-sampling until it is invertible.
+    #[cfg(test)]
+    pub fn random_invertible<R: rand_core::RngCore>(size: usize, rng: &mut R) -> Self {
+        loop {
+            let matrix = Self::random(size, size, rng);
+            if matrix.is_invertible() {
+                return matrix;
+            }
+        }
+    }
+}
+
+/// `m[(r, c)]` reads the element at row r, column c. Panics if out of bounds, like `get_unsafe`.
+impl Index<(usize, usize)> for GFMatrix {
+    type Output = GFElement;
+
+    fn index(&self, (r, c): (usize, usize)) -> &GFElement {
+        if r >= self.rows || c >= self.cols {
+            panic!("index: Index out of bounds (r={}, c={}, rows={}, cols={})", r, c, self.rows, self.cols);
+        }
+        &self.data[r * self.cols + c]
+    }
+}
+
+/// `m[(r, c)] = x` writes the element at row r, column c. Panics if out of bounds, like `set_val`.
+impl IndexMut<(usize, usize)> for GFMatrix {
+    fn index_mut(&mut self, (r, c): (usize, usize)) -> &mut GFElement {
+        if r >= self.rows || c >= self.cols {
+            panic!("index_mut: Index out of bounds (r={}, c={}, rows={}, cols={})", r, c, self.rows, self.cols);
+        }
+        &mut self.data[r * self.cols + c]
+    }
+}
+
+/// Computes the rank of a matrix by forward Gaussian elimination on a copy; the input is
+/// left untouched. Uses the same first-nonzero pivot rule as `solve_linear_system`, so
+/// `matrix_rank(A) < A.num_cols()` is exactly when A x = y has no unique solution.
+pub fn matrix_rank(matrix: &GFMatrix) -> usize {
+    let mut m = matrix.clone();
+    let mut pivot_row = 0;
+    for pivot_col in 0..m.num_cols() {
+        if pivot_row >= m.num_rows() {
+            break;
+        }
+        let mut i = pivot_row;
+        while i < m.num_rows() && m[(i, pivot_col)].0 == 0 {
+            i += 1;
+        }
+        if i == m.num_rows() {
+            continue; // No pivot in this column
+        }
+        if i != pivot_row {
+            for k in pivot_col..m.num_cols() {
+                let temp = m[(pivot_row, k)];
+                m[(pivot_row, k)] = m[(i, k)];
+                m[(i, k)] = temp;
+            }
+        }
+        let inv_pivot = gf16_inv(m[(pivot_row, pivot_col)]).expect("pivot is nonzero");
+        for r in (pivot_row + 1)..m.num_rows() {
+            let factor = gf16_mul(m[(r, pivot_col)], inv_pivot);
+            if factor.0 != 0 {
+                for k in pivot_col..m.num_cols() {
+                    let term = gf16_mul(factor, m[(pivot_row, k)]);
+                    m[(r, k)] = gf16_sub(m[(r, k)], term);
+                }
+            }
+        }
+        pivot_row += 1;
+    }
+    pivot_row
 }
 
 // --- Standalone Matrix Operations ---
 
 /// Adds two matrices over GF(16).
 /// Returns Err if dimensions are incompatible.
-pub fn matrix_add(a: &GFMatrix, b: &GFMatrix) -> Result<GFMatrix, &'static str> {
+pub fn matrix_add(a: &GFMatrix, b: &GFMatrix) -> Result<GFMatrix, MayoError> {
     if a.num_rows() != b.num_rows() || a.num_cols() != b.num_cols() {
-        return Err("Matrices must have the same dimensions for addition");
+        return Err(MayoError::DimensionMismatch("Matrices must have the same dimensions for addition"));
     }
     let mut result_data = Vec::with_capacity(a.data.len());
     for i in 0..a.data.len() {
@@ -122,10 +277,23 @@ pub fn matrix_add(a: &GFMatrix, b: &GFMatrix) -> Result<GFMatrix, &'static str>
     Ok(GFMatrix::new_with_data(a.num_rows(), a.num_cols(), result_data))
 }
 
+/// Adds matrix b into matrix a in place (a += b) over GF(16).
+/// Same result as `matrix_add` without allocating a new matrix.
+/// Returns Err if dimensions are incompatible.
+pub fn matrix_add_assign(a: &mut GFMatrix, b: &GFMatrix) -> Result<(), MayoError> {
+    if a.num_rows() != b.num_rows() || a.num_cols() != b.num_cols() {
+        return Err(MayoError::DimensionMismatch("Matrices must have the same dimensions for addition"));
+    }
+    for (a_i, b_i) in a.data.iter_mut().zip(&b.data) {
+        *a_i = gf16_add(*a_i, *b_i);
+    }
+    Ok(())
+}
+
 /// Subtracts matrix b from matrix a over GF(16).
 /// (Identical to addition in GF(2^n)).
 /// Returns Err if dimensions are incompatible.
-pub fn matrix_sub(a: &GFMatrix, b: &GFMatrix) -> Result<GFMatrix, &'static str> {
+pub fn matrix_sub(a: &GFMatrix, b: &GFMatrix) -> Result<GFMatrix, MayoError> {
     matrix_add(a, b) // In GF(2^n), subtraction is XOR, same as addition
 }
 
@@ -140,28 +308,119 @@ pub fn matrix_scalar_mul(scalar: GFElement, matrix: &GFMatrix) -> GFMatrix {
 
 /// Multiplies two matrices (a * b) over GF(16).
 /// Returns Err if dimensions are incompatible (a.cols != b.rows).
-pub fn matrix_mul(a: &GFMatrix, b: &GFMatrix) -> Result<GFMatrix, &'static str> {
+/// Zero dimensions are allowed: an r x 0 times 0 x c product is the r x c zero matrix.
+///
+/// With the `simd` feature this is `matrix_mul_packed`, otherwise `matrix_mul_scalar`.
+/// Both return identical results.
+pub fn matrix_mul(a: &GFMatrix, b: &GFMatrix) -> Result<GFMatrix, MayoError> {
+    #[cfg(feature = "simd")]
+    return matrix_mul_packed(a, b);
+    #[cfg(not(feature = "simd"))]
+    return matrix_mul_scalar(a, b);
+}
+
+/// Element-by-element `matrix_mul`, using `gf16_mul` (and so the selected `GfBackend`).
+pub fn matrix_mul_scalar(a: &GFMatrix, b: &GFMatrix) -> Result<GFMatrix, MayoError> {
     if a.num_cols() != b.num_rows() {
-        return Err("Number of columns in the first matrix must equal number of rows in the second");
+        return Err(MayoError::DimensionMismatch("Number of columns in the first matrix must equal number of rows in the second"));
     }
     let result_rows = a.num_rows();
     let result_cols = b.num_cols();
     let mut result_matrix = GFMatrix::zero(result_rows, result_cols);
 
+    // Row r of the product is sum_k a[r][k] * (row k of b)
     for r in 0..result_rows {
-        for c in 0..result_cols {
-            let mut sum = GFElement(0);
-            for k_idx in 0..a.num_cols() { // a.num_cols() or b.num_rows()
-                let val_a = a.get_unsafe(r, k_idx);
-                let val_b = b.get_unsafe(k_idx, c);
-                sum = gf16_add(sum, gf16_mul(val_a, val_b));
+        let acc = &mut result_matrix.data[r * result_cols..(r + 1) * result_cols];
+        for k_idx in 0..a.num_cols() { // a.num_cols() or b.num_rows()
+            let b_row = &b.data[k_idx * result_cols..(k_idx + 1) * result_cols];
+            gf16_vec_madd(acc, a.get_unsafe(r, k_idx), b_row);
+        }
+    }
+    Ok(result_matrix)
+}
+
+// Packed GF(16) arithmetic: 16 elements per u64, element j of a row in bits 4*(j%16)..4*(j%16)+4
+// of word j/16. Adding two packed words is a single XOR, and multiplying every lane by x is a
+// shift plus a reduction by x^4 = x + 1, so a row is scaled 16 elements at a time.
+const PACKED_LANES: usize = 16;
+const NIBBLE_LOW_BITS: u64 = 0x7777_7777_7777_7777;
+const NIBBLE_HIGH_BIT: u64 = 0x8888_8888_8888_8888;
+
+/// Multiplies every packed lane of `v` by x, reducing modulo x^4 + x + 1.
+fn packed_mul_x(v: u64) -> u64 {
+    ((v & NIBBLE_LOW_BITS) << 1) ^ (((v & NIBBLE_HIGH_BIT) >> 3) * 0b0011)
+}
+
+/// Packs the elements of one matrix row into words of `PACKED_LANES` elements.
+fn pack_row(row: &[GFElement]) -> Vec<u64> {
+    row.chunks(PACKED_LANES)
+        .map(|chunk| chunk.iter().enumerate().fold(0u64, |word, (lane, e)| word | (((e.0 & 0x0F) as u64) << (4 * lane))))
+        .collect()
+}
+
+/// `matrix_mul` computed on packed rows: each row of the product is accumulated as
+/// sum_l a[r][l] * b_row_l, with b_row_l scaled 16 elements per word operation.
+/// The multiples b_row_l * x^k (k = 0..3) are computed once per row of `b`, so scaling by
+/// a[r][l] is a masked XOR of those four. Results are identical to `matrix_mul_scalar`.
+/// This path bypasses `gf16_mul`, so it ignores the `GfBackend` setting and is not
+/// included in the `counting` feature's operation counts.
+pub fn matrix_mul_packed(a: &GFMatrix, b: &GFMatrix) -> Result<GFMatrix, MayoError> {
+    if a.num_cols() != b.num_rows() {
+        return Err(MayoError::DimensionMismatch("Number of columns in the first matrix must equal number of rows in the second"));
+    }
+    let result_cols = b.num_cols();
+    let words_per_row = result_cols.div_ceil(PACKED_LANES);
+
+    // For each row l of b: [b_l, x*b_l, x^2*b_l, x^3*b_l], each words_per_row words long.
+    let b_multiples: Vec<[Vec<u64>; 4]> = (0..b.num_rows())
+        .map(|l| {
+            let v0 = pack_row(&b.data[l * result_cols..(l + 1) * result_cols]);
+            let v1: Vec<u64> = v0.iter().map(|&w| packed_mul_x(w)).collect();
+            let v2: Vec<u64> = v1.iter().map(|&w| packed_mul_x(w)).collect();
+            let v3: Vec<u64> = v2.iter().map(|&w| packed_mul_x(w)).collect();
+            [v0, v1, v2, v3]
+        })
+        .collect();
+
+    let mut result_matrix = GFMatrix::zero(a.num_rows(), result_cols);
+    let mut acc = vec![0u64; words_per_row];
+    for r in 0..a.num_rows() {
+        acc.iter_mut().for_each(|w| *w = 0);
+        for (l, multiples) in b_multiples.iter().enumerate() {
+            let a_rl = a.get_unsafe(r, l).0;
+            for (bit, multiple) in multiples.iter().enumerate() {
+                let mask = 0u64.wrapping_sub(((a_rl >> bit) & 1) as u64);
+                for (acc_word, &word) in acc.iter_mut().zip(multiple) {
+                    *acc_word ^= word & mask;
+                }
             }
-            result_matrix.set_val(r, c, sum);
+        }
+        for c in 0..result_cols {
+            let nibble = (acc[c / PACKED_LANES] >> (4 * (c % PACKED_LANES))) & 0x0F;
+            result_matrix.data[r * result_cols + c] = GFElement(nibble as u8);
         }
     }
     Ok(result_matrix)
 }
 
+/// Computes the Kronecker product A ⊗ B over GF(16): the (a.rows*b.rows) x (a.cols*b.cols)
+/// block matrix whose (i, j) block is A[i,j] * B.
+pub fn matrix_kronecker(a: &GFMatrix, b: &GFMatrix) -> GFMatrix {
+    let (b_rows, b_cols) = (b.num_rows(), b.num_cols());
+    let mut result = GFMatrix::zero(a.num_rows() * b_rows, a.num_cols() * b_cols);
+    for i in 0..a.num_rows() {
+        for j in 0..a.num_cols() {
+            let a_ij = a.get_unsafe(i, j);
+            for r in 0..b_rows {
+                for c in 0..b_cols {
+                    result.set_val(i * b_rows + r, j * b_cols + c, gf16_mul(a_ij, b.get_unsafe(r, c)));
+                }
+            }
+        }
+    }
+    result
+}
+
 /// Transposes a matrix over GF(16).
 pub fn matrix_transpose(matrix: &GFMatrix) -> GFMatrix {
     let mut transposed_matrix = GFMatrix::zero(matrix.num_cols(), matrix.num_rows());
@@ -176,9 +435,10 @@ pub fn matrix_transpose(matrix: &GFMatrix) -> GFMatrix {
 /// Multiplies a matrix by a vector (matrix * vector) over GF(16).
 /// Treats the vector as a column vector.
 /// Returns Err if dimensions are incompatible (matrix.cols != vector.len()).
-pub fn matrix_vec_mul(matrix: &GFMatrix, vector: &GFVector) -> Result<GFVector, &'static str> {
+#[allow(clippy::needless_range_loop)]
+pub fn matrix_vec_mul(matrix: &GFMatrix, vector: &GFVector) -> Result<GFVector, MayoError> {
     if matrix.num_cols() != vector.len() {
-        return Err("Matrix columns must match vector length for multiplication");
+        return Err(MayoError::DimensionMismatch("Matrix columns must match vector length for multiplication"));
     }
     let mut result_vector = Vec::with_capacity(matrix.num_rows());
     for r in 0..matrix.num_rows() {
@@ -191,11 +451,22 @@ pub fn matrix_vec_mul(matrix: &GFMatrix, vector: &GFVector) -> Result<GFVector,
     Ok(result_vector)
 }
 
+/// Multiplies a matrix by a column vector held as an `n x 1` GFMatrix.
+/// Returns the product as an `m x 1` GFMatrix.
+/// Returns Err if `v` is not a single column or its length does not match `a.cols`.
+pub fn matrix_mat_vec(a: &GFMatrix, v: &GFMatrix) -> Result<GFMatrix, MayoError> {
+    if v.num_cols() != 1 {
+        return Err(MayoError::DimensionMismatch("Column vector matrix must have exactly one column"));
+    }
+    let result = matrix_vec_mul(a, &v.data)?;
+    Ok(GFMatrix::new_with_data(result.len(), 1, result))
+}
+
 /// Subtracts vector `b` from vector `a` over GF(16) (element-wise).
 /// Returns Err if dimensions are incompatible.
-pub fn matrix_sub_vectors_gfvector(a: &GFVector, b: &GFVector) -> Result<GFVector, &'static str> {
+pub fn matrix_sub_vectors_gfvector(a: &GFVector, b: &GFVector) -> Result<GFVector, MayoError> {
     if a.len() != b.len() {
-        return Err("Vector dimensions must match for subtraction");
+        return Err(MayoError::DimensionMismatch("Vector dimensions must match for subtraction"));
     }
     let mut result = Vec::with_capacity(a.len());
     for i in 0..a.len() {
@@ -209,9 +480,9 @@ pub fn matrix_sub_vectors_gfvector(a: &GFVector, b: &GFVector) -> Result<GFVecto
 /// Off-diagonal elements are M[i,j]+M[j,i].
 /// If M is upper triangular, M_sym[i,i]=M[i,i], M_sym[i,j]=M[i,j] for i<j, M_sym[j,i]=M[i,j] for j<i.
 /// This function computes M_sym = M + M^T directly.
-pub fn matrix_symmetrize(matrix: &GFMatrix) -> Result<GFMatrix, &'static str> {
+pub fn matrix_symmetrize(matrix: &GFMatrix) -> Result<GFMatrix, MayoError> {
     if matrix.num_rows() != matrix.num_cols() {
-        return Err("Matrix must be square to be symmetrized");
+        return Err(MayoError::DimensionMismatch("Matrix must be square to be symmetrized"));
     }
     let n = matrix.num_rows();
     let mut sym_matrix = GFMatrix::zero(n, n);
@@ -225,30 +496,76 @@ pub fn matrix_symmetrize(matrix: &GFMatrix) -> Result<GFMatrix, &'static str> {
     Ok(sym_matrix)
 }
 
+/// Computes Upper(M) for a square M: the upper triangular matrix with the same quadratic
+/// form, i.e. Upper(M)[r][r] = M[r][r] and Upper(M)[r][c] = M[r][c] + M[c][r] for r < c.
+pub fn matrix_upper(matrix: &GFMatrix) -> Result<GFMatrix, MayoError> {
+    if matrix.num_rows() != matrix.num_cols() {
+        return Err(MayoError::DimensionMismatch("Matrix must be square to take its upper form"));
+    }
+    let n = matrix.num_rows();
+    let mut upper = GFMatrix::zero(n, n);
+    for r in 0..n {
+        upper.set_val(r, r, matrix.get_unsafe(r, r));
+        for c in r + 1..n {
+            upper.set_val(r, c, gf16_add(matrix.get_unsafe(r, c), matrix.get_unsafe(c, r)));
+        }
+    }
+    Ok(upper)
+}
+
+/// Computes U + U^T for an upper triangular U by mirroring its strict upper triangle.
+/// Equals `matrix_symmetrize(U)` but only reads the upper triangle; the diagonal of the
+/// result is zero. Returns Err if `upper` is not square or has a nonzero entry below
+/// the diagonal.
+pub fn symmetrize_from_upper(upper: &GFMatrix) -> Result<GFMatrix, MayoError> {
+    if !upper.is_upper_triangular() {
+        return Err(MayoError::InvalidInput("Matrix must be upper triangular to symmetrize from the upper triangle"));
+    }
+    let n = upper.num_rows();
+    let mut sym_matrix = GFMatrix::zero(n, n);
+    for r in 0..n {
+        for c in r + 1..n {
+            let val = upper.data[r * n + c];
+            sym_matrix.data[r * n + c] = val;
+            sym_matrix.data[c * n + r] = val;
+        }
+    }
+    Ok(sym_matrix)
+}
+
 
 /// Multiplies a row vector (transpose of GFVector) by a matrix: v^T * M.
 /// vector_lhs is treated as a 1xN row vector. matrix_rhs is NxK. Result is 1xK (GFVector).
-pub fn matrix_vec_mul_transpose_gfvector(vector_lhs: &GFVector, matrix_rhs: &GFMatrix) -> Result<GFVector, &'static str> {
+pub fn matrix_vec_mul_transpose_gfvector(vector_lhs: &GFVector, matrix_rhs: &GFMatrix) -> Result<GFVector, MayoError> {
+    let mut result_vector = vec![GFElement(0); matrix_rhs.num_cols()];
+    matrix_vec_mul_transpose_into(vector_lhs, matrix_rhs, &mut result_vector)?;
+    Ok(result_vector)
+}
+
+/// Same as `matrix_vec_mul_transpose_gfvector`, but writes v^T * M into `out` instead of
+/// allocating. `out` must have exactly one element per column of `matrix_rhs`.
+pub fn matrix_vec_mul_transpose_into(vector_lhs: &[GFElement], matrix_rhs: &GFMatrix, out: &mut [GFElement]) -> Result<(), MayoError> {
     if vector_lhs.len() != matrix_rhs.num_rows() {
-        return Err("Vector length must match matrix rows for v^T * M multiplication");
+        return Err(MayoError::DimensionMismatch("Vector length must match matrix rows for v^T * M multiplication"));
+    }
+    if out.len() != matrix_rhs.num_cols() {
+        return Err(MayoError::DimensionMismatch("Output buffer length must match matrix columns for v^T * M multiplication"));
     }
-    let num_cols_result = matrix_rhs.num_cols();
-    let mut result_vector = vec![GFElement(0); num_cols_result];
 
-    for c_res in 0..num_cols_result { // For each column in the result vector (and matrix_rhs)
+    for (c_res, out_elem) in out.iter_mut().enumerate() { // For each column in the result vector (and matrix_rhs)
         let mut sum = GFElement(0);
-        for r_m_idx in 0..matrix_rhs.num_rows() { // Summing down the column of matrix_rhs
-            sum = gf16_add(sum, gf16_mul(vector_lhs[r_m_idx], matrix_rhs.get_unsafe(r_m_idx, c_res)));
+        for (r_m_idx, &lhs_elem) in vector_lhs.iter().enumerate() { // Summing down the column of matrix_rhs
+            sum = gf16_add(sum, gf16_mul(lhs_elem, matrix_rhs.get_unsafe(r_m_idx, c_res)));
         }
-        result_vector[c_res] = sum;
+        *out_elem = sum;
     }
-    Ok(result_vector)
+    Ok(())
 }
 
 /// Computes the dot product of two vectors: a^T * b.
-pub fn vector_dot_product(a: &GFVector, b: &GFVector) -> Result<GFElement, &'static str> {
+pub fn vector_dot_product(a: &GFVector, b: &GFVector) -> Result<GFElement, MayoError> {
     if a.len() != b.len() {
-        return Err("Vectors must have the same length for dot product");
+        return Err(MayoError::DimensionMismatch("Vectors must have the same length for dot product"));
     }
     if a.is_empty() { // Or b.is_empty(), since lengths must match
         return Ok(GFElement(0)); // Dot product of empty vectors is 0
@@ -260,6 +577,56 @@ pub fn vector_dot_product(a: &GFVector, b: &GFVector) -> Result<GFElement, &'sta
     Ok(sum)
 }
 
+/// Computes y += a * x element-wise over GF(16), in place.
+/// Operates on slices so callers can apply it to matrix rows without per-cell indexing.
+/// Same kernel as `gf16_vec_madd`: panics if `x` and `y` differ in length, and takes no
+/// shortcut for a = 0, as the solver calls it with secret-dependent factors.
+pub fn gfvector_axpy(y: &mut [GFElement], a: GFElement, x: &[GFElement]) {
+    gf16_vec_madd(y, a, x);
+}
+
+/// Applies the emulsifier map E of the MAYO specification to every column of `data`.
+/// `data` holds m rows of `width` elements, row-major, where row a is the coefficient of z^a;
+/// each column is multiplied by z modulo f(z) = z^m + f_tail[0] + f_tail[1] z + ... (see
+/// `MayoVariantParams::f_tail`). `width` = 1 treats `data` as a single m-element vector.
+pub fn emulsify_rows(data: &mut [GFElement], width: usize, f_tail: &[u8]) {
+    assert!(width > 0 && data.len().is_multiple_of(width), "emulsify_rows: data must hold whole rows");
+    let num_rows = data.len() / width;
+    if num_rows == 0 {
+        return;
+    }
+    // z^m = f_tail(z) in characteristic 2, so the top row folds back onto the low rows.
+    let top_row = data[(num_rows - 1) * width..].to_vec();
+    data.copy_within(..(num_rows - 1) * width, width);
+    data[..width].fill(GFElement(0));
+    for (a, &f_a) in f_tail.iter().enumerate().take(num_rows) {
+        gfvector_axpy(&mut data[a * width..(a + 1) * width], GFElement(f_a), &top_row);
+    }
+}
+
+/// The pairs (i, j), i <= j < k, of the k whipped vectors s_0, ..., s_{k-1}, in the order the
+/// MAYO specification assigns them powers of the emulsifier: pair number l is scaled by E^l.
+/// For each i, j runs down from k-1 to i, so (0, k-1) comes first and (k-1, k-1) last.
+pub fn whipping_pairs(k: usize) -> Vec<(usize, usize)> {
+    (0..k).flat_map(|i| (i..k).rev().map(move |j| (i, j))).collect()
+}
+
+/// Expands a condition into an all-ones (true) or all-zeros (false) byte mask
+/// arithmetically, so callers can select values without branching on `cond`.
+#[inline]
+fn ct_mask(cond: bool) -> u8 {
+    (cond as u8).wrapping_neg()
+}
+
+/// Selects `a` if `cond` is true and `b` otherwise, element-wise and without branching:
+/// every element is computed as `(a & mask) | (b & !mask)` for either value of `cond`.
+/// Panics if `a` and `b` have different lengths.
+pub fn gfvector_cmov(cond: bool, a: &[GFElement], b: &[GFElement]) -> GFVector {
+    assert_eq!(a.len(), b.len(), "gfvector_cmov requires vectors of equal length");
+    let mask = ct_mask(cond);
+    a.iter().zip(b).map(|(x, y)| GFElement((x.0 & mask) | (y.0 & !mask))).collect()
+}
+
 
 // --- Unit Tests ---
 #[cfg(test)]
@@ -270,6 +637,45 @@ mod tests {
     fn vec_gf(data: Vec<GFElement>) -> GFVector { data }
 
 
+    #[test]
+    fn test_from_fn() {
+        let m = GFMatrix::from_fn(3, 5, |r, c| GFElement((r + c) as u8 & 0xF));
+        assert_eq!((m.num_rows(), m.num_cols()), (3, 5));
+        assert_eq!(m[(0, 0)], gf(0));
+        assert_eq!(m[(1, 3)], gf(4));
+        assert_eq!(m[(2, 4)], gf(6));
+        assert_eq!(m, GFMatrix::new_with_data(3, 5, (0..3).flat_map(|r| (0..5).map(move |c| gf(r + c))).collect()));
+
+        assert_eq!(GFMatrix::from_fn(4, 4, |r, c| GFElement((r == c) as u8)), GFMatrix::identity(4));
+        assert_eq!(GFMatrix::from_fn(0, 3, |_, _| gf(1)), GFMatrix::zero(0, 3));
+    }
+
+    #[test]
+    fn test_tuple_indexing() {
+        let mut m = GFMatrix::new_with_data(2, 3, vec![gf(1), gf(2), gf(3), gf(4), gf(5), gf(6)]);
+        assert_eq!(m[(0, 1)], gf(2));
+        assert_eq!(m[(1, 2)], gf(6));
+        m[(0, 1)] = GFElement(5);
+        assert_eq!(m[(0, 1)], gf(5));
+        assert_eq!(m.get_unsafe(0, 1), gf(5));
+        assert_eq!(m.data, vec![gf(1), gf(5), gf(3), gf(4), gf(5), gf(6)]);
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of bounds")]
+    fn test_tuple_indexing_row_out_of_bounds() {
+        let m = GFMatrix::zero(2, 3);
+        let _ = m[(2, 0)];
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of bounds")]
+    fn test_tuple_indexing_col_out_of_bounds_on_write() {
+        // (0, 3) would land on (1, 0) in the flat data without the column check.
+        let mut m = GFMatrix::zero(2, 3);
+        m[(0, 3)] = GFElement(1);
+    }
+
     #[test]
     fn test_matrix_symmetrize() {
         // Test with an upper triangular matrix
@@ -303,6 +709,25 @@ mod tests {
         assert_eq!(matrix_symmetrize(&m_sym).unwrap().data, expected_zero_data);
     }
 
+    #[test]
+    fn test_symmetrize_from_upper_matches_matrix_symmetrize() {
+        let mut rng = rand_core::OsRng;
+        for n in [0, 1, 2, 5, 8] {
+            let mut upper = GFMatrix::random(n, n, &mut rng);
+            for r in 0..n {
+                for c in 0..r {
+                    upper.set_val(r, c, gf(0));
+                }
+            }
+            assert_eq!(symmetrize_from_upper(&upper), matrix_symmetrize(&upper), "Mismatch for n={}", n);
+        }
+
+        let mut lower_entry = GFMatrix::zero(3, 3);
+        lower_entry.set_val(2, 0, gf(7));
+        assert_eq!(symmetrize_from_upper(&lower_entry), Err(MayoError::InvalidInput("Matrix must be upper triangular to symmetrize from the upper triangle")));
+        assert!(symmetrize_from_upper(&GFMatrix::zero(2, 3)).is_err());
+    }
+
     #[test]
     fn test_matrix_vec_mul_transpose_gfvector() {
         // v^T = [1, 2, 3] (1x3)
@@ -322,6 +747,166 @@ mod tests {
         assert!(matrix_vec_mul_transpose_gfvector(&v_short, &m).is_err());
     }
 
+    #[test]
+    fn test_matrix_vec_mul_transpose_into() {
+        let v = vec_gf(vec![gf(1), gf(2), gf(3)]);
+        let m = GFMatrix::new_with_data(3, 2, vec![gf(1),gf(4), gf(2),gf(5), gf(3),gf(6)]);
+        let mut out = vec![gf(0xF); 2];
+        matrix_vec_mul_transpose_into(&v, &m, &mut out).unwrap();
+        assert_eq!(out, matrix_vec_mul_transpose_gfvector(&v, &m).unwrap());
+
+        let mut short_out = vec![gf(0); 1];
+        assert_eq!(matrix_vec_mul_transpose_into(&v, &m, &mut short_out),
+                   Err(MayoError::DimensionMismatch("Output buffer length must match matrix columns for v^T * M multiplication")));
+        assert!(matrix_vec_mul_transpose_into(&v[..2], &m, &mut out).is_err());
+    }
+
+    #[test]
+    fn test_zero_dimension_operations() {
+        let m0x3 = GFMatrix::zero(0, 3);
+        let m3x2 = GFMatrix::new_with_data(3, 2, vec![gf(1); 6]);
+        let m2x0 = GFMatrix::zero(2, 0);
+        let m0x0 = GFMatrix::from_vectors(vec![]);
+        assert_eq!((m0x0.num_rows(), m0x0.num_cols()), (0, 0));
+        assert_eq!(GFMatrix::from_vectors(vec![vec![], vec![]]), m2x0);
+
+        let prod = matrix_mul(&m0x3, &m3x2).unwrap();
+        assert_eq!((prod.num_rows(), prod.num_cols()), (0, 2));
+        // Inner dimension zero: the empty sum is zero
+        assert_eq!(matrix_mul(&m2x0, &GFMatrix::zero(0, 4)).unwrap(), GFMatrix::zero(2, 4));
+        assert!(matrix_mul(&m2x0, &m3x2).is_err());
+
+        assert_eq!(matrix_transpose(&m0x3), GFMatrix::zero(3, 0));
+        assert_eq!(matrix_add(&m0x3, &m0x3).unwrap(), m0x3);
+        assert_eq!(matrix_sub(&m2x0, &m2x0).unwrap(), m2x0);
+        assert_eq!(matrix_scalar_mul(gf(7), &m0x3), m0x3);
+        assert_eq!(matrix_symmetrize(&m0x0).unwrap(), m0x0);
+        assert_eq!(GFMatrix::identity(0), m0x0);
+
+        assert_eq!(matrix_vec_mul(&m0x3, &vec![gf(1); 3]).unwrap(), Vec::<GFElement>::new());
+        assert_eq!(matrix_vec_mul(&m2x0, &vec![]).unwrap(), vec![gf(0); 2]);
+        assert_eq!(matrix_mat_vec(&m2x0, &GFMatrix::zero(0, 1)).unwrap(), GFMatrix::zero(2, 1));
+        assert_eq!(matrix_vec_mul_transpose_gfvector(&vec![], &GFMatrix::zero(0, 3)).unwrap(), vec![gf(0); 3]);
+        assert_eq!(matrix_sub_vectors_gfvector(&vec![], &vec![]).unwrap(), Vec::<GFElement>::new());
+
+        assert!(m0x0.is_upper_triangular());
+        assert!(m0x0.is_invertible());
+        assert!(!m0x3.is_invertible());
+        assert_eq!(m0x3.nonzero_fraction(), 0.0);
+        assert_eq!(matrix_rank(&m0x3), 0);
+        assert_eq!(matrix_rank(&m2x0), 0);
+        assert_eq!(m2x0.to_vectors(), vec![Vec::<GFElement>::new(); 2]);
+    }
+
+    #[test]
+    fn test_matrix_rank() {
+        for n in [1, 2, 5, 16] {
+            assert_eq!(matrix_rank(&GFMatrix::identity(n)), n);
+        }
+
+        let deficient = GFMatrix::new_with_data(2, 2, vec![gf(1), gf(1), gf(2), gf(2)]);
+        let before = deficient.clone();
+        assert_eq!(matrix_rank(&deficient), 1);
+        assert_eq!(deficient, before); // Input is not mutated
+
+        assert_eq!(matrix_rank(&GFMatrix::zero(3, 4)), 0);
+    }
+
+    #[test]
+    fn test_matrix_mul_packed_matches_scalar() {
+        let mut rng = rand_core::OsRng;
+        for &(rows, inner, cols) in &[(1, 1, 1), (3, 5, 17), (16, 16, 16), (33, 7, 31), (60, 60, 60), (58, 58, 8), (0, 4, 3), (4, 0, 3)] {
+            let a = GFMatrix::random(rows, inner, &mut rng);
+            let b = GFMatrix::random(inner, cols, &mut rng);
+            assert_eq!(matrix_mul_packed(&a, &b).unwrap(), matrix_mul_scalar(&a, &b).unwrap(), "{}x{} * {}x{}", rows, inner, inner, cols);
+        }
+        assert!(matrix_mul_packed(&GFMatrix::zero(2, 3), &GFMatrix::zero(2, 3)).is_err());
+
+        // Every scalar times every element, one lane each.
+        let a = GFMatrix::new_with_data(16, 1, (0..16).map(gf).collect());
+        let b = GFMatrix::new_with_data(1, 16, (0..16).map(gf).collect());
+        assert_eq!(matrix_mul_packed(&a, &b).unwrap(), matrix_mul_scalar(&a, &b).unwrap());
+    }
+
+    #[test]
+    fn test_matrix_upper_preserves_quadratic_form() {
+        let mut rng = rand_core::OsRng;
+        let m = GFMatrix::random(6, 6, &mut rng);
+        let upper = matrix_upper(&m).unwrap();
+        assert!(upper.is_upper_triangular());
+        // x^T M x = x^T Upper(M) x for every x
+        for _ in 0..8 {
+            let x = GFMatrix::random(6, 1, &mut rng).data;
+            let quad = |mat: &GFMatrix| vector_dot_product(&matrix_vec_mul_transpose_gfvector(&x, mat).unwrap(), &x).unwrap();
+            assert_eq!(quad(&upper), quad(&m));
+        }
+        assert_eq!(matrix_upper(&upper).unwrap(), upper);
+        assert!(matrix_upper(&GFMatrix::zero(2, 3)).is_err());
+    }
+
+    #[test]
+    fn test_matrix_add_assign_matches_matrix_add() {
+        let mut rng = rand_core::OsRng;
+        let a = GFMatrix::random(5, 7, &mut rng);
+        let b = GFMatrix::random(5, 7, &mut rng);
+        let mut a_in_place = a.clone();
+        matrix_add_assign(&mut a_in_place, &b).unwrap();
+        assert_eq!(a_in_place, matrix_add(&a, &b).unwrap());
+        assert!(matrix_add_assign(&mut a_in_place, &GFMatrix::zero(7, 5)).is_err());
+    }
+
+    #[test]
+    fn test_add_scaled_identity() {
+        let c = gf(0x6);
+        let mut m = GFMatrix::identity(4);
+        m.add_scaled_identity(c).unwrap();
+        for r in 0..4 {
+            for col in 0..4 {
+                let expected = if r == col { gf(1 ^ 0x6) } else { gf(0) };
+                assert_eq!(m.get_unsafe(r, col), expected);
+            }
+        }
+        // Adding c*I twice cancels out in characteristic 2
+        m.add_scaled_identity(c).unwrap();
+        assert_eq!(m, GFMatrix::identity(4));
+
+        assert!(GFMatrix::zero(2, 3).add_scaled_identity(c).is_err());
+    }
+
+    #[test]
+    fn test_gfvector_cmov() {
+        let a = vec_gf(vec![gf(1), gf(0xF), gf(0), gf(7)]);
+        let b = vec_gf(vec![gf(0xA), gf(0), gf(5), gf(7)]);
+        assert_eq!(gfvector_cmov(true, &a, &b), a);
+        assert_eq!(gfvector_cmov(false, &a, &b), b);
+        assert_eq!(gfvector_cmov(true, &[], &[]), Vec::<GFElement>::new());
+
+        // Both conditions go through the same mask arithmetic; only the mask value differs.
+        assert_eq!(ct_mask(true), 0xFF);
+        assert_eq!(ct_mask(false), 0x00);
+    }
+
+    #[test]
+    #[should_panic(expected = "gf16_vec_madd: length mismatch")]
+    fn test_gfvector_axpy_length_mismatch_panics() {
+        gfvector_axpy(&mut [gf(1), gf(2)], gf(3), &[gf(4)]);
+    }
+
+    #[test]
+    fn test_gfvector_axpy() {
+        let mut y = vec_gf(vec![gf(1), gf(2), gf(3)]);
+        let x = vec_gf(vec![gf(4), gf(5), gf(6)]);
+        gfvector_axpy(&mut y, gf(2), &x);
+        assert_eq!(y, vec![gf16_add(gf(1), gf16_mul(gf(2), gf(4))), gf16_add(gf(2), gf16_mul(gf(2), gf(5))), gf16_add(gf(3), gf16_mul(gf(2), gf(6)))]);
+
+        // a = 0 leaves y unchanged; x + x = 0 in characteristic 2
+        let mut z = x.clone();
+        gfvector_axpy(&mut z, gf(0), &x);
+        assert_eq!(z, x);
+        gfvector_axpy(&mut z, gf(1), &x);
+        assert_eq!(z, vec![gf(0); 3]);
+    }
+
     #[test]
     fn test_vector_dot_product() {
         let v1 = vec_gf(vec![gf(1), gf(2), gf(3)]);
@@ -441,6 +1026,7 @@ mod tests {
         //   0xA ^ 0x4 = 0xE
         let expected = GFMatrix::new_with_data(2,2, vec![gf(0xB), gf(0x4), gf(0x0), gf(0xE)]);
         assert_eq!(matrix_mul(&a, &b).unwrap().data, expected.data);
+        assert_eq!(matrix_mul_scalar(&a, &b).unwrap().data, expected.data);
 
         let m_id = GFMatrix::identity(2);
         assert_eq!(matrix_mul(&a, &m_id).unwrap().data, a.data);
@@ -450,6 +1036,34 @@ mod tests {
         assert!(matrix_mul(&a, &c).is_err());
     }
     
+    #[test]
+    fn test_matrix_kronecker() {
+        // M = [[1,2,3],[4,5,6]]
+        let m = GFMatrix::new_with_data(2, 3, vec![gf(1),gf(2),gf(3), gf(4),gf(5),gf(6)]);
+        let kron = matrix_kronecker(&GFMatrix::identity(2), &m);
+        assert_eq!((kron.num_rows(), kron.num_cols()), (4, 6));
+        // I_2 ⊗ M = [[M, 0], [0, M]]
+        for r in 0..4 {
+            for c in 0..6 {
+                let expected = if r / 2 == c / 3 { m.get_unsafe(r % 2, c % 3) } else { gf(0) };
+                assert_eq!(kron.get_unsafe(r, c), expected, "Mismatch at ({}, {})", r, c);
+            }
+        }
+
+        // Each block of A ⊗ B is A[i,j] * B
+        let a = GFMatrix::new_with_data(1, 2, vec![gf(0x2), gf(0x3)]);
+        let kron = matrix_kronecker(&a, &m);
+        assert_eq!((kron.num_rows(), kron.num_cols()), (2, 6));
+        for r in 0..2 {
+            for c in 0..3 {
+                assert_eq!(kron.get_unsafe(r, c), gf16_mul(gf(0x2), m.get_unsafe(r, c)));
+                assert_eq!(kron.get_unsafe(r, c + 3), gf16_mul(gf(0x3), m.get_unsafe(r, c)));
+            }
+        }
+
+        assert_eq!(matrix_kronecker(&GFMatrix::zero(0, 2), &m), GFMatrix::zero(0, 6));
+    }
+
     #[test]
     fn test_matrix_transpose() {
         let m = GFMatrix::new_with_data(2,3, vec![gf(1), gf(2), gf(3), gf(4), gf(5), gf(6)]);
@@ -489,6 +1103,60 @@ mod tests {
         assert!(matrix_vec_mul(&matrix, &incompatible_vector).is_err());
     }
 
+    #[test]
+    fn test_upper_triangular_checks() {
+        let upper = GFMatrix::new_with_data(2,2, vec![gf(1), gf(2), gf(0), gf(3)]);
+        assert!(upper.is_upper_triangular());
+        upper.assert_upper_triangular();
+        assert_eq!(upper.nonzero_fraction(), 0.75);
+
+        let lower = GFMatrix::new_with_data(2,2, vec![gf(1), gf(0), gf(2), gf(3)]);
+        assert!(!lower.is_upper_triangular());
+        assert!(!GFMatrix::zero(2, 3).is_upper_triangular());
+        assert_eq!(GFMatrix::zero(0, 0).nonzero_fraction(), 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_assert_upper_triangular_panic() {
+        GFMatrix::new_with_data(2,2, vec![gf(1), gf(0), gf(2), gf(3)]).assert_upper_triangular();
+    }
+
+    #[test]
+    fn test_random_matrices() {
+        let mut rng = rand_core::OsRng;
+        let m = GFMatrix::random(3, 5, &mut rng);
+        assert_eq!(m.num_rows(), 3);
+        assert_eq!(m.num_cols(), 5);
+        assert!(m.data.iter().all(|e| e.0 < 16));
+
+        let inv = GFMatrix::random_invertible(4, &mut rng);
+        assert!(inv.is_invertible());
+
+        assert!(GFMatrix::identity(4).is_invertible());
+        assert!(!GFMatrix::new_with_data(2,2, vec![gf(1), gf(1), gf(2), gf(2)]).is_invertible());
+        assert!(!GFMatrix::zero(2, 3).is_invertible());
+    }
+
+    #[test]
+    fn test_matrix_mat_vec() {
+        let matrix = GFMatrix::new_with_data(2,3, vec![
+            gf(1), gf(2), gf(3),
+            gf(4), gf(5), gf(6)
+        ]);
+        let vector = vec_gf(vec![gf(7), gf(8), gf(9)]);
+        let column = GFMatrix::new_with_data(3, 1, vector.clone());
+
+        let result = matrix_mat_vec(&matrix, &column).unwrap();
+        assert_eq!(result.num_rows(), 2);
+        assert_eq!(result.num_cols(), 1);
+        assert_eq!(result.data, matrix_vec_mul(&matrix, &vector).unwrap());
+
+        let row = GFMatrix::new_with_data(1, 3, vector);
+        assert!(matrix_mat_vec(&matrix, &row).is_err());
+        assert!(matrix_mat_vec(&matrix, &GFMatrix::zero(2, 1)).is_err());
+    }
+
     #[test]
     fn test_matrix_sub_vectors_gfvector() {
         let v1 = vec_gf(vec![gf(5), gf(6), gf(7)]);
@@ -500,4 +1168,30 @@ mod tests {
         let v3 = vec_gf(vec![gf(1)]);
         assert!(matrix_sub_vectors_gfvector(&v1, &v3).is_err());
     }
+
+    #[test]
+    fn test_emulsify_rows() {
+        let f_tail = [8u8, 0, 2, 8]; // MAYO m = 64
+        // z * z^63 = z^64 = f_tail(z)
+        let mut top = vec![gf(0); 64];
+        top[63] = gf(1);
+        emulsify_rows(&mut top, 1, &f_tail);
+        let mut expected = vec![gf(0); 64];
+        expected[..4].copy_from_slice(&[gf(8), gf(0), gf(2), gf(8)]);
+        assert_eq!(top, expected);
+
+        // Below the top row, E is a plain shift; every column is handled independently.
+        let mut data: Vec<GFElement> = (0..64 * 2).map(|i| gf(if i < 126 { (i % 15 + 1) as u8 } else { 0 })).collect();
+        let original = data.clone();
+        emulsify_rows(&mut data, 2, &f_tail);
+        assert_eq!(&data[..2], &[gf(0), gf(0)]);
+        assert_eq!(&data[2..], &original[..126]);
+    }
+
+    #[test]
+    fn test_whipping_pairs_order() {
+        assert_eq!(whipping_pairs(1), vec![(0, 0)]);
+        assert_eq!(whipping_pairs(3), vec![(0, 2), (0, 1), (0, 0), (1, 2), (1, 1), (2, 2)]);
+        assert_eq!(whipping_pairs(9).len(), 9 * 10 / 2);
+    }
 }
@@ -1,5 +1,8 @@
 //! Defines parameters for different MAYO security levels.
 
+use serde::{Deserialize, Serialize};
+use crate::error::MayoError;
+
 /// Irreducible polynomial for GF(16): x^4 + x + 1
 /// (coefficients in little-endian for degree, e.g., 0b...c3 c2 c1 c0)
 /// x^4 + x + 1 is 1*x^4 + 0*x^3 + 0*x^2 + 1*x^1 + 1*x^0 -> 10011
@@ -7,7 +10,7 @@ pub const F_POLY_U8: u8 = 0b0001_0011; // As u8, used in some contexts if operat
 pub const F_POLY_U16: u16 = 0x13;     // As u16, matching the subtask description (0x13 = 19 = 0b10011)
 
 /// Holds the specific parameters for a MAYO variant (e.g., MAYO1, MAYO2).
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub struct MayoVariantParams {
     // Core MQ parameters
     pub n: usize, // Number of variables (elements in a solution vector s)
@@ -25,18 +28,126 @@ pub struct MayoVariantParams {
     pub o_bytes: usize,         // Serialized oil variables component (e.g., G or its seed)
     pub p1_bytes: usize,        // Serialized P1 matrix component (derived via AES-CTR from pk_seed)
     pub p2_bytes: usize,        // Serialized P2 matrix component (derived via AES-CTR from pk_seed)
-    pub p3_bytes: usize,        // Serialized P3 matrix component (derived via SHAKE from pk_seed)
-    
-    // TODO: Add any other derived byte lengths if useful, e.g., bytes for csk, cpk, esk, epk, sig.
-    // These would be calculated based on n, m, o, k, and q (field_elements_to_bytes).
-    // For example:
-    // csk_bytes: sk_seed_bytes
-    // cpk_bytes: pk_seed_bytes + bytes_for_gf16_elements(m * (n-o)*(n-o+1)/2) for P3, or its hash.
-    // sig_bytes: salt_bytes + bytes_for_gf16_elements(n) for solution s.
+    pub p3_bytes: usize,        // Serialized P3 matrix component (computed from O, P1 and P2 in key generation)
+}
+
+impl MayoVariantParams {
+    /// Checks that the parameter set is structurally usable.
+    /// Requires at least one vinegar and one oil variable (`0 < o < n`) and `m > 0`,
+    /// so the vinegar/oil split of s and the P1/P2/P3 blocks are never empty, and
+    /// `digest_bytes > 0`, so the target t is never derived from an empty message digest.
+    ///
+    /// Also requires `k >= 1` and `k * o >= m`: the whipped system of the MAYO specification
+    /// has m equations in k*o unknowns, which is only solvable in general when k*o >= m.
+    /// Finally, m must have a reduction polynomial f(z), see `f_tail`.
+    pub fn validate(&self) -> Result<(), MayoError> {
+        if self.o == 0 {
+            return Err(MayoError::InvalidParameters("Invalid parameters: o must be greater than zero"));
+        }
+        if self.o >= self.n {
+            return Err(MayoError::InvalidParameters("Invalid parameters: o must be less than n"));
+        }
+        if self.m == 0 {
+            return Err(MayoError::InvalidParameters("Invalid parameters: m must be greater than zero"));
+        }
+        if self.digest_bytes == 0 {
+            return Err(MayoError::InvalidParameters("Invalid parameters: digest_bytes must be greater than zero"));
+        }
+        if self.k == 0 {
+            return Err(MayoError::InvalidParameters("Invalid parameters: k must be greater than zero"));
+        }
+        if self.k * self.o < self.m {
+            return Err(MayoError::InvalidParameters("Invalid parameters: k * o must be at least m"));
+        }
+        self.f_tail()?;
+        Ok(())
+    }
+
+    /// Number of vinegar variables, `n - o`: the leading block of a solution vector s.
+    pub fn vinegar_vars(&self) -> usize {
+        self.n - self.o
+    }
+
+    /// Number of oil variables, `o`: the trailing block of s and the column count of A.
+    pub fn oil_vars(&self) -> usize {
+        self.o
+    }
+
+    /// Byte length of a compact public key: seed_pk || P3_bytes.
+    pub fn cpk_bytes(&self) -> usize {
+        self.pk_seed_bytes + self.p3_bytes
+    }
+
+    /// Byte length of the encoded L matrices stored in an expanded secret key:
+    /// m matrices L_i of size (n-o) x o, packed as one run of GF(16) elements.
+    pub fn l_bytes(&self) -> usize {
+        MayoParams::bytes_for_gf16_elements(self.m * (self.n - self.o) * self.o)
+    }
+
+    /// Exact byte length of an expanded secret key: seed_sk || O_bytes || P1_bytes || L_bytes.
+    /// Callers with fixed buffers can preallocate this much; `expand_sk` does the same.
+    pub fn esk_capacity(&self) -> usize {
+        self.sk_seed_bytes + self.o_bytes + self.p1_bytes + self.l_bytes()
+    }
+
+    /// Byte length of a hypothetical compact expanded secret key, seed_sk || O_bytes || L_bytes,
+    /// that would re-derive P1 from seed_pk when signing. Nothing in this crate produces or
+    /// consumes that format; this only quantifies its saving of `p1_bytes` over `esk_capacity`.
+    pub fn compact_esk_bytes(&self) -> usize {
+        self.sk_seed_bytes + self.o_bytes + self.l_bytes()
+    }
+
+    /// Byte length of an expanded public key: P1_bytes || P2_bytes || P3_bytes.
+    /// `expand_pk` preallocates exactly this much.
+    pub fn epk_bytes(&self) -> usize {
+        self.p1_bytes + self.p2_bytes + self.p3_bytes
+    }
+
+    /// Byte length of the encoded solution s = s_0 || ... || s_{k-1}: k vectors of n elements,
+    /// packed as one run of GF(16) elements.
+    pub fn s_bytes(&self) -> usize {
+        MayoParams::bytes_for_gf16_elements(self.k * self.n)
+    }
+
+    /// Byte length of a signature: the encoded solution s (k * n elements) followed by the salt.
+    pub fn sig_bytes(&self) -> usize {
+        self.s_bytes() + self.salt_bytes
+    }
+
+    /// Low-degree coefficients of the degree-m polynomial f(z) = z^m + f_0 + f_1 z + f_2 z^2 + f_3 z^3
+    /// used by the emulsifier map E (multiplication by z modulo f(z)), as in the MAYO
+    /// specification. Each f_i is a GF(16) element. Returns Err for an m the specification
+    /// does not define a polynomial for.
+    pub fn f_tail(&self) -> Result<&'static [u8], MayoError> {
+        match self.m {
+            64 => Ok(&[8, 0, 2, 8]),   // f(z) = z^64 + x^3 z^3 + x z^2 + x^3
+            78 => Ok(&[8, 1, 1, 0]),   // f(z) = z^78 + z^2 + z + x^3
+            108 => Ok(&[8, 0, 1, 7]),  // f(z) = z^108 + (x^2 + x + 1) z^3 + z^2 + x^3
+            142 => Ok(&[4, 0, 8, 1]),  // f(z) = z^142 + z^3 + x^3 z^2 + x^2
+            _ => Err(MayoError::InvalidParameters("Invalid parameters: no reduction polynomial f(z) for this m")),
+        }
+    }
+}
+
+/// Selects which published version of the MAYO parameter sets to follow.
+/// Parameter values changed between NIST submission rounds, so interop with a given
+/// reference implementation requires matching its round. Both profiles give the key and
+/// signature sizes of that round's specification tables: P1, P2 and P3 are each packed as one
+/// nibble stream. The element order within those bytes is this crate's (high nibble first,
+/// matrix after matrix), not the reference's bitsliced layout, so equal sizes do not make
+/// the keys byte-compatible with it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ReferenceProfile {
+    /// NIST Round 1 submission (MAYO1: n=66, m=64, o=8, k=9).
+    #[default]
+    Round1,
+    /// NIST Round 2 submission (MAYO1: n=86, m=78, o=8, k=10).
+    Round2,
 }
 
 /// Enum to select a specific set of MAYO parameters.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+/// Serializes as `{"MAYO1": { ...all MayoVariantParams fields... }}`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MayoParams {
     MAYO1(MayoVariantParams),
     MAYO2(MayoVariantParams),
@@ -48,36 +159,72 @@ impl MayoParams {
     pub const Q: usize = 16;
     // F_POLY is defined as a top-level constant in this file (F_POLY_U16 or F_POLY_U8).
 
-    /// Parameters for MAYO1 (NIST Level 1 equivalent).
+    /// Parameters for MAYO1 (NIST Level 1 equivalent), using the default `ReferenceProfile`.
     pub fn mayo1() -> Self {
-        MayoParams::MAYO1(MayoVariantParams {
-            n: 66, m: 64, o: 8, k: 9,
-            sk_seed_bytes: 24,  // Corresponds to NIST's rho parameter for MAYO1
-            pk_seed_bytes: 16,  // AES-128 key size
-            salt_bytes: 24,     // Corresponds to NIST's salt parameter for MAYO1
-            digest_bytes: 32,   // For a 256-bit digest (e.g. SHAKE256/256)
-            o_bytes: 232,       // From MAYO spec, Table 1 (G_bytes for MAYO1_PK)
-            p1_bytes: 54784,    // 64 * 856 (calculated for 58x58 upper triangular)
-            p2_bytes: 14848,    // 64 * 232 (calculated for 58x8)
-            p3_bytes: 1152,     // 64 * 18 (calculated for 8x8 upper triangular)
-        })
-    }
-
-    /// Parameters for MAYO2 (NIST Level 3 equivalent, if mapping directly).
+        Self::mayo1_for(ReferenceProfile::default())
+    }
+
+    /// Parameters for MAYO2, using the default `ReferenceProfile`.
     /// Note: The parameters provided (n=78, m=64, o=18, k=4) align with "mayo_2" from some reference implementations.
     /// These values are also consistent with NIST Level 3 parameters for MAYO.
     pub fn mayo2() -> Self {
-        MayoParams::MAYO2(MayoVariantParams {
-            n: 78, m: 64, o: 18, k: 4,
-            sk_seed_bytes: 24,  // Corresponds to NIST's rho parameter for MAYO2 (assuming it's MAYO-L3 mapping)
-            pk_seed_bytes: 16,  // AES-128 key size
-            salt_bytes: 24,     // Corresponds to NIST's salt parameter for MAYO2
-            digest_bytes: 32,   // For a 256-bit digest
-            o_bytes: 540,       // From MAYO spec, Table 1 (G_bytes for MAYO2_PK)
-            p1_bytes: 58560,   // 64 * 1830 (calculated for 60x60 upper triangular)
-            p2_bytes: 34560,    // 64 * 360 (calculated for 60x18)
-            p3_bytes: 5504,    // 64 * 171 (calculated for 18x18 upper triangular)
-        })
+        Self::mayo2_for(ReferenceProfile::default())
+    }
+
+    /// Parameters for MAYO1 as defined by the given reference version.
+    pub fn mayo1_for(profile: ReferenceProfile) -> Self {
+        match profile {
+            ReferenceProfile::Round1 => MayoParams::MAYO1(MayoVariantParams {
+                n: 66, m: 64, o: 8, k: 9,
+                sk_seed_bytes: 24,  // Corresponds to NIST's rho parameter for MAYO1
+                pk_seed_bytes: 16,  // AES-128 key size
+                salt_bytes: 24,     // Corresponds to NIST's salt parameter for MAYO1
+                digest_bytes: 32,   // For a 256-bit digest (e.g. SHAKE256/256)
+                o_bytes: 232,       // From MAYO spec, Table 1 (G_bytes for MAYO1_PK)
+                p1_bytes: 54752,    // 64 * 1711 / 2 (58x58 upper triangular)
+                p2_bytes: 14848,    // 64 * 464 / 2 (58x8)
+                p3_bytes: 1152,     // 64 * 36 / 2 (8x8 upper triangular)
+            }),
+            ReferenceProfile::Round2 => MayoParams::MAYO1(MayoVariantParams {
+                n: 86, m: 78, o: 8, k: 10,
+                sk_seed_bytes: 24,
+                pk_seed_bytes: 16,  // AES-128 key size
+                salt_bytes: 24,
+                digest_bytes: 32,
+                o_bytes: 312,       // (78 * 8) / 2
+                p1_bytes: 120159,   // 78 * 3081 / 2 (78x78 upper triangular)
+                p2_bytes: 24336,    // 78 * 624 / 2 (78x8)
+                p3_bytes: 1404,     // 78 * 36 / 2 (8x8 upper triangular)
+            }),
+        }
+    }
+
+    /// Parameters for MAYO2 as defined by the given reference version.
+    pub fn mayo2_for(profile: ReferenceProfile) -> Self {
+        match profile {
+            ReferenceProfile::Round1 => MayoParams::MAYO2(MayoVariantParams {
+                n: 78, m: 64, o: 18, k: 4,
+                sk_seed_bytes: 24,  // Corresponds to NIST's rho parameter for MAYO2 (assuming it's MAYO-L3 mapping)
+                pk_seed_bytes: 16,  // AES-128 key size
+                salt_bytes: 24,     // Corresponds to NIST's salt parameter for MAYO2
+                digest_bytes: 32,   // For a 256-bit digest
+                o_bytes: 540,       // From MAYO spec, Table 1 (G_bytes for MAYO2_PK)
+                p1_bytes: 58560,    // 64 * 1830 / 2 (60x60 upper triangular)
+                p2_bytes: 34560,    // 64 * 1080 / 2 (60x18)
+                p3_bytes: 5472,     // 64 * 171 / 2 (18x18 upper triangular)
+            }),
+            ReferenceProfile::Round2 => MayoParams::MAYO2(MayoVariantParams {
+                n: 81, m: 64, o: 17, k: 4,
+                sk_seed_bytes: 24,
+                pk_seed_bytes: 16,  // AES-128 key size
+                salt_bytes: 24,
+                digest_bytes: 32,
+                o_bytes: 544,       // (64 * 17) / 2
+                p1_bytes: 66560,    // 64 * 2080 / 2 (64x64 upper triangular)
+                p2_bytes: 34816,    // 64 * 1088 / 2 (64x17)
+                p3_bytes: 4896,     // 64 * 153 / 2 (17x17 upper triangular)
+            }),
+        }
     }
 
     /// Accessor method to get the underlying `MayoVariantParams`.
@@ -91,7 +238,14 @@ impl MayoParams {
     /// Helper method to calculate bytes needed to store a given number of GF(16) elements.
     /// Each GF(16) element is 4 bits (a nibble).
     pub fn bytes_for_gf16_elements(num_elements: usize) -> usize {
-        (num_elements + 1) / 2
+        num_elements.div_ceil(2)
+    }
+
+    /// The GF(16) reduction polynomial x^4 + x + 1 as a bit mask: bit i is the coefficient
+    /// of x^i, so the value is 0b1_0011 = 0x13. All variants share the same field; this is
+    /// the canonical source for `F_POLY_U16` and `F_POLY_U8`.
+    pub const fn field_polynomial() -> u16 {
+        F_POLY_U16
     }
 
     // Convenience accessors delegated to the variant
@@ -99,6 +253,8 @@ impl MayoParams {
     pub fn m(&self) -> usize { self.variant().m }
     pub fn o(&self) -> usize { self.variant().o }
     pub fn k(&self) -> usize { self.variant().k }
+    pub fn vinegar_vars(&self) -> usize { self.variant().vinegar_vars() }
+    pub fn oil_vars(&self) -> usize { self.variant().oil_vars() }
     pub fn sk_seed_bytes(&self) -> usize { self.variant().sk_seed_bytes }
     pub fn pk_seed_bytes(&self) -> usize { self.variant().pk_seed_bytes }
     pub fn salt_bytes(&self) -> usize { self.variant().salt_bytes }
@@ -108,14 +264,72 @@ impl MayoParams {
     pub fn p2_bytes(&self) -> usize { self.variant().p2_bytes }
     pub fn p3_bytes(&self) -> usize { self.variant().p3_bytes }
 
-    pub fn get_params_by_name(name: &str) -> Result<MayoParams, String> {
+    pub fn get_params_by_name(name: &str) -> Result<MayoParams, MayoError> {
+        Self::get_params_by_name_for(name, ReferenceProfile::default())
+    }
+
+    /// Like `get_params_by_name`, but selects the parameters of a specific reference version.
+    pub fn get_params_by_name_for(name: &str, profile: ReferenceProfile) -> Result<MayoParams, MayoError> {
         match name.to_lowercase().as_str() {
-            "mayo1" => Ok(MayoParams::mayo1()),
-            "mayo2" => Ok(MayoParams::mayo2()),
+            "mayo1" => Ok(MayoParams::mayo1_for(profile)),
+            "mayo2" => Ok(MayoParams::mayo2_for(profile)),
             // Add other variants if they exist in the future
-            _ => Err(format!("Unknown MAYO variant name: {}", name)),
+            _ => Err(MayoError::UnknownVariant(name.to_string())),
         }
     }
+
+    /// Serializes the variant name and all of its parameters to JSON, for build tooling
+    /// and cross-language test harnesses. See `all_variants` for the full list.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("MayoParams contains only integer fields and always serializes")
+    }
+
+    /// Returns every supported variant with the default `ReferenceProfile`.
+    pub fn all_variants() -> Vec<MayoParams> {
+        vec![MayoParams::mayo1(), MayoParams::mayo2()]
+    }
+
+    /// One-byte identifier of the variant, used to tag serialized keys. Stable across releases.
+    pub fn variant_id(&self) -> u8 {
+        match self {
+            MayoParams::MAYO1(_) => 1,
+            MayoParams::MAYO2(_) => 2,
+        }
+    }
+
+    /// Inverse of `variant_id`, returning the variant with the default `ReferenceProfile`.
+    pub fn from_variant_id(id: u8) -> Result<MayoParams, MayoError> {
+        Self::all_variants()
+            .into_iter()
+            .find(|params| params.variant_id() == id)
+            .ok_or(MayoError::DecodeError("Unknown MAYO variant id"))
+    }
+
+    /// Returns the lowercase name of every supported variant, as accepted by `get_params_by_name`.
+    pub fn supported_variant_names() -> Vec<&'static str> {
+        Self::all_variants()
+            .iter()
+            .map(|params| match params {
+                MayoParams::MAYO1(_) => "mayo1",
+                MayoParams::MAYO2(_) => "mayo2",
+            })
+            .collect()
+    }
+
+    /// Checks that no two variants share a cpk, epk, or signature length.
+    /// Detecting a variant from the length of a key or signature is only safe while this holds.
+    pub fn lengths_are_unambiguous() -> bool {
+        let variants = Self::all_variants();
+        for (i, a) in variants.iter().enumerate() {
+            for b in &variants[i + 1..] {
+                let (a, b) = (a.variant(), b.variant());
+                if a.cpk_bytes() == b.cpk_bytes() || a.epk_bytes() == b.epk_bytes() || a.sig_bytes() == b.sig_bytes() {
+                    return false;
+                }
+            }
+        }
+        true
+    }
 }
 
 // Example usage:
@@ -125,3 +339,160 @@ impl MayoParams {
 // let p1_bytes_val = specific_variant_params.p1_bytes;
 // or directly:
 // let p1_bytes_val_direct = params_mayo1.p1_bytes();
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Sizes in bytes as listed in the specification tables of each round:
+    // (cpk, sig, O, P1, P2, P3). P1..P3 are m * (entries per matrix) nibbles, packed.
+    fn check_spec_sizes(params: &MayoVariantParams, expected: (usize, usize, usize, usize, usize, usize)) {
+        let actual = (params.cpk_bytes(), params.sig_bytes(), params.o_bytes, params.p1_bytes, params.p2_bytes, params.p3_bytes);
+        assert_eq!(actual, expected);
+        assert_eq!(params.epk_bytes(), expected.3 + expected.4 + expected.5);
+    }
+
+    #[test]
+    fn test_reference_profile_round1() {
+        let mayo1 = MayoParams::mayo1_for(ReferenceProfile::Round1);
+        assert_eq!(mayo1, MayoParams::mayo1());
+        let p = mayo1.variant();
+        assert_eq!((p.n, p.m, p.o, p.k), (66, 64, 8, 9));
+        assert_eq!((p.sk_seed_bytes, p.pk_seed_bytes, p.salt_bytes, p.digest_bytes), (24, 16, 24, 32));
+        check_spec_sizes(p, (1168, 321, 232, 54752, 14848, 1152));
+
+        let p2 = MayoParams::mayo2_for(ReferenceProfile::Round1);
+        assert_eq!((p2.n(), p2.m(), p2.o(), p2.k()), (78, 64, 18, 4));
+        check_spec_sizes(p2.variant(), (5488, 180, 540, 58560, 34560, 5472));
+    }
+
+    #[test]
+    fn test_reference_profile_round2() {
+        let mayo1 = MayoParams::mayo1_for(ReferenceProfile::Round2);
+        let p = mayo1.variant();
+        assert_eq!((p.n, p.m, p.o, p.k), (86, 78, 8, 10));
+        assert_eq!((p.sk_seed_bytes, p.pk_seed_bytes, p.salt_bytes, p.digest_bytes), (24, 16, 24, 32));
+        check_spec_sizes(p, (1420, 454, 312, 120159, 24336, 1404));
+
+        let p2 = MayoParams::mayo2_for(ReferenceProfile::Round2);
+        assert_eq!((p2.n(), p2.m(), p2.o(), p2.k()), (81, 64, 17, 4));
+        check_spec_sizes(p2.variant(), (4912, 186, 544, 66560, 34816, 4896));
+
+        assert_eq!(MayoParams::get_params_by_name_for("MAYO1", ReferenceProfile::Round2), Ok(mayo1));
+    }
+
+    #[test]
+    fn test_variant_id_round_trip() {
+        for params in MayoParams::all_variants() {
+            assert_eq!(MayoParams::from_variant_id(params.variant_id()), Ok(params));
+        }
+        assert_eq!(MayoParams::from_variant_id(0), Err(MayoError::DecodeError("Unknown MAYO variant id")));
+    }
+
+    #[test]
+    fn test_supported_variant_names_parse() {
+        let names = MayoParams::supported_variant_names();
+        assert_eq!(names, vec!["mayo1", "mayo2"]);
+        for (name, expected) in names.iter().zip(MayoParams::all_variants()) {
+            assert_eq!(MayoParams::get_params_by_name(name), Ok(expected));
+        }
+    }
+
+    #[test]
+    fn test_validate_rejects_degenerate_dimensions() {
+        for params in MayoParams::all_variants() {
+            assert_eq!(params.variant().validate(), Ok(()));
+        }
+
+        let mut no_vinegar = *MayoParams::mayo1().variant();
+        no_vinegar.o = no_vinegar.n;
+        assert_eq!(no_vinegar.validate(), Err(MayoError::InvalidParameters("Invalid parameters: o must be less than n")));
+
+        let mut no_oil = *MayoParams::mayo1().variant();
+        no_oil.o = 0;
+        assert_eq!(no_oil.validate(), Err(MayoError::InvalidParameters("Invalid parameters: o must be greater than zero")));
+
+        let mut no_digest = *MayoParams::mayo1().variant();
+        no_digest.digest_bytes = 0;
+        assert_eq!(no_digest.validate(), Err(MayoError::InvalidParameters("Invalid parameters: digest_bytes must be greater than zero")));
+    }
+
+    #[test]
+    fn test_shipped_variants_satisfy_whipping_bound() {
+        for profile in [ReferenceProfile::Round1, ReferenceProfile::Round2] {
+            for params in [MayoParams::mayo1_for(profile), MayoParams::mayo2_for(profile)] {
+                let p = params.variant();
+                assert!(p.k >= 1 && p.k * p.o >= p.m, "{:?} violates k * o >= m", params);
+                assert_eq!(p.validate(), Ok(()));
+            }
+        }
+
+        let mut no_k = *MayoParams::mayo1().variant();
+        no_k.k = 0;
+        assert_eq!(no_k.validate(), Err(MayoError::InvalidParameters("Invalid parameters: k must be greater than zero")));
+
+        let mut small_k = *MayoParams::mayo1().variant();
+        small_k.k = 7; // 7 * 8 = 56 < 64
+        assert_eq!(small_k.validate(), Err(MayoError::InvalidParameters("Invalid parameters: k * o must be at least m")));
+    }
+
+    #[test]
+    fn test_lengths_are_unambiguous() {
+        // Fails if a new variant collides with an existing one on any serialized length.
+        assert!(MayoParams::lengths_are_unambiguous());
+
+        let mayo1 = MayoParams::mayo1();
+        let p = mayo1.variant();
+        assert_eq!(p.cpk_bytes(), 16 + 1152);
+        assert_eq!(p.epk_bytes(), 54752 + 14848 + 1152);
+        assert_eq!(p.s_bytes(), 9 * 66 / 2);
+        assert_eq!(p.sig_bytes(), 297 + 24);
+    }
+
+    #[test]
+    fn test_vinegar_oil_split() {
+        for params in MayoParams::all_variants() {
+            assert_eq!(params.vinegar_vars() + params.oil_vars(), params.n());
+            assert_eq!(params.oil_vars(), params.o());
+        }
+        for profile in [ReferenceProfile::Round1, ReferenceProfile::Round2] {
+            for params in [MayoParams::mayo1_for(profile), MayoParams::mayo2_for(profile)] {
+                let p = params.variant();
+                assert_eq!(p.vinegar_vars() + p.oil_vars(), p.n);
+            }
+        }
+    }
+
+    #[test]
+    fn test_compact_esk_saves_p1_bytes() {
+        for params in MayoParams::all_variants() {
+            let p = params.variant();
+            assert!(p.compact_esk_bytes() < p.esk_capacity());
+            assert_eq!(p.esk_capacity() - p.compact_esk_bytes(), p.p1_bytes);
+        }
+    }
+
+    #[test]
+    fn test_field_polynomial() {
+        assert_eq!(MayoParams::field_polynomial(), 0x13);
+        assert_eq!(MayoParams::field_polynomial() as u8, F_POLY_U8);
+        assert_eq!(MayoParams::field_polynomial() >> 8, 0);
+    }
+
+    #[test]
+    fn test_to_json() {
+        let mayo1 = MayoParams::mayo1();
+        let json = mayo1.to_json();
+        assert!(json.starts_with("{\"MAYO1\":"), "unexpected JSON: {}", json);
+        assert!(json.contains("\"n\":66"));
+        assert!(json.contains("\"p1_bytes\":54752"));
+        assert!(json.contains("\"p2_bytes\":14848"));
+        assert!(json.contains("\"p3_bytes\":1152"));
+        assert!(json.contains("\"salt_bytes\":24"));
+
+        for params in MayoParams::all_variants() {
+            let round_tripped: MayoParams = serde_json::from_str(&params.to_json()).unwrap();
+            assert_eq!(round_tripped, params);
+        }
+    }
+}
@@ -1,105 +1,373 @@
 //! Implements MAYO.Sign (Algorithm 8).
 
 use crate::types::{
-    ExpandedSecretKey, Message, Signature, GFVector, Salt, SeedSK, // Removed MessageDigest
+    ExpandedSecretKey, ExpandedPublicKey, ExpandedSignature, Message, MessageDigest, Signature, SignatureOptions, GFVector, Salt, SeedSK,
     GFElement // For random vinegar variables
 };
 use crate::params::{MayoParams, MayoVariantParams};
-use crate::hash::{shake256_digest, shake256_derive_target_t, shake256_xof_derive_pk_seed_and_o, shake256_xof_derive_p3};
+use crate::hash::{
+    message_digest, public_key_fingerprint, shake256_derive_deterministic_salt_and_seed, shake256_derive_target_t_with_binding,
+    shake256_derive_vinegar_bytes, shake256_xof_derive_pk_seed_and_o
+};
 use crate::aes_ctr::derive_p2_bytes; // Removed derive_p1_bytes
 use crate::codec::{
-    decode_p1_matrices, decode_l_matrices, decode_gf_elements, encode_s_vector
+    decode_p1_matrices, decode_l_matrices, decode_target_vector, encode_s_vector
 };
-// Removed: decode_o_matrix, decode_p2_matrices, decode_p3_matrices
-use crate::types::GFMatrix;
+use crate::keygen::compute_p3_bytes;
+// Removed: decode_p2_matrices, decode_p3_matrices
+use crate::types::{GFMatrix, WipeOnDrop, Wipe, wipe_elements};
 use crate::matrix::{
-    matrix_sub_vectors_gfvector, matrix_symmetrize, 
-    matrix_vec_mul_transpose_gfvector, vector_dot_product
+    emulsify_rows, gfvector_axpy, matrix_sub_vectors_gfvector, matrix_vec_mul,
+    matrix_vec_mul_transpose_into, symmetrize_from_upper, whipping_pairs
 };
+use crate::gf::{gf16_add, gf16_mul};
 use crate::solver::solve_linear_system;
 use getrandom::getrandom;
+use rand_core::{OsRng, RngCore};
+use crate::error::MayoError;
 
 const MAX_SIGN_RETRIES: usize = 256;
 
-/// Placeholder for the core cryptographic math of MAYO signing.
-/// This function would compute the linearized system matrix A and target vector y'
-/// based on the vinegar variables and secret key components.
+/// Source of the per-attempt randomness used by MAYO.Sign.
+/// `sign_message_with_randomness` asks for a fresh salt, fresh vinegar variables and a fresh
+/// solution offset on every retry, in that order, so an implementor controls exactly which
+/// systems the solver sees and which of their solutions is picked.
+pub trait SigningRandomness {
+    /// Returns `len` salt bytes.
+    fn salt(&mut self, len: usize) -> Result<Vec<u8>, MayoError>;
+    /// Returns `count` vinegar variables.
+    fn vinegar(&mut self, count: usize) -> Result<GFVector, MayoError>;
+    /// Returns the `count` elements of r, the random offset of SampleSolution: the solver
+    /// finds x' with A x' = t - y' - A r and signing uses x = x' + r, so the free variables
+    /// of x are uniform instead of zero.
+    fn solution_offset(&mut self, count: usize) -> Result<GFVector, MayoError>;
+}
+
+/// `SigningRandomness` backed by the operating system RNG (`getrandom`).
+/// This is the source used by `sign_message` and `sign_message_with_options`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct OsRandomness;
+
+impl SigningRandomness for OsRandomness {
+    fn salt(&mut self, len: usize) -> Result<Vec<u8>, MayoError> {
+        let mut salt_bytes = vec![0u8; len];
+        getrandom(&mut salt_bytes).map_err(|_| MayoError::RngFailure("Failed to generate random salt"))?;
+        Ok(salt_bytes)
+    }
+
+    fn vinegar(&mut self, count: usize) -> Result<GFVector, MayoError> {
+        let mut v_bytes = vec![0u8; count];
+        getrandom(&mut v_bytes).map_err(|_| MayoError::RngFailure("Failed to generate random vinegar variable"))?;
+        Ok(v_bytes.into_iter().map(|b| GFElement(b & 0x0F)).collect()) // Ensure each is a nibble
+    }
+
+    fn solution_offset(&mut self, count: usize) -> Result<GFVector, MayoError> {
+        let mut r_bytes = vec![0u8; count];
+        getrandom(&mut r_bytes).map_err(|_| MayoError::RngFailure("Failed to generate random solution offset"))?;
+        Ok(r_bytes.into_iter().map(|b| GFElement(b & 0x0F)).collect())
+    }
+}
+
+/// `SigningRandomness` that draws every salt and vinegar byte from a caller-supplied
+/// `rand_core::RngCore`, e.g. a seeded RNG in tests or a hardware RNG.
+/// This is the source used by `sign_message_with_rng`.
+#[derive(Debug)]
+pub struct RngCoreRandomness<'a, R: RngCore>(pub &'a mut R);
+
+impl<R: RngCore> SigningRandomness for RngCoreRandomness<'_, R> {
+    fn salt(&mut self, len: usize) -> Result<Vec<u8>, MayoError> {
+        let mut salt_bytes = vec![0u8; len];
+        self.0.try_fill_bytes(&mut salt_bytes).map_err(|_| MayoError::RngFailure("Failed to generate random salt"))?;
+        Ok(salt_bytes)
+    }
+
+    fn vinegar(&mut self, count: usize) -> Result<GFVector, MayoError> {
+        let mut v_bytes = vec![0u8; count];
+        self.0.try_fill_bytes(&mut v_bytes).map_err(|_| MayoError::RngFailure("Failed to generate random vinegar variable"))?;
+        Ok(v_bytes.into_iter().map(|b| GFElement(b & 0x0F)).collect())
+    }
+
+    fn solution_offset(&mut self, count: usize) -> Result<GFVector, MayoError> {
+        let mut r_bytes = vec![0u8; count];
+        self.0.try_fill_bytes(&mut r_bytes).map_err(|_| MayoError::RngFailure("Failed to generate random solution offset"))?;
+        Ok(r_bytes.into_iter().map(|b| GFElement(b & 0x0F)).collect())
+    }
+}
+
+/// `SigningRandomness` that hands out caller-provided salts in order, then falls back to
+/// `fallback` once they are exhausted. Vinegar variables and solution offsets always come
+/// from `fallback`.
+/// Lets tests replay salts known to produce inconsistent systems before a good one.
+#[derive(Debug, Clone)]
+pub struct SaltPool<'a, R: SigningRandomness = OsRandomness> {
+    salts: &'a [Salt],
+    salts_used: usize,
+    fallback: R,
+}
+
+impl<'a, R: SigningRandomness> SaltPool<'a, R> {
+    /// Creates a pool that yields `salts` in order before drawing from `fallback`.
+    pub fn new(salts: &'a [Salt], fallback: R) -> Self {
+        SaltPool { salts, salts_used: 0, fallback }
+    }
+
+    /// Number of pooled salts handed out so far (at most `salts.len()`).
+    pub fn salts_used(&self) -> usize {
+        self.salts_used
+    }
+}
+
+impl<R: SigningRandomness> SigningRandomness for SaltPool<'_, R> {
+    fn salt(&mut self, len: usize) -> Result<Vec<u8>, MayoError> {
+        match self.salts.get(self.salts_used) {
+            Some(salt) => {
+                self.salts_used += 1;
+                Ok(salt.0.clone())
+            }
+            None => self.fallback.salt(len),
+        }
+    }
+
+    fn vinegar(&mut self, count: usize) -> Result<GFVector, MayoError> {
+        self.fallback.vinegar(count)
+    }
+
+    fn solution_offset(&mut self, count: usize) -> Result<GFVector, MayoError> {
+        self.fallback.solution_offset(count)
+    }
+}
+
+/// `SigningRandomness` for deterministic signing, see `sign_message_deterministic`.
+/// The salt and a vinegar seed come from SHAKE256(seedsk || M); every attempt reuses the
+/// salt and reads its vinegar variables, then its solution offset r, from
+/// SHAKE256(vinegar_seed || ctr), where `ctr` counts the attempts. The same key and message
+/// therefore replay the same attempts.
+#[derive(Debug, Clone)]
+pub struct DeterministicRandomness {
+    salt: Salt,
+    vinegar_seed: Vec<u8>,
+    ctr: u32,
+    // Bytes of this attempt's stream already taken by the vinegar variables.
+    vinegar_len: usize,
+}
+
+impl DeterministicRandomness {
+    /// Derives the salt and vinegar seed for signing `message` under `seedsk`.
+    pub fn new(seedsk: &SeedSK, message: &Message, params_enum: &MayoParams) -> Self {
+        let (salt, vinegar_seed) = shake256_derive_deterministic_salt_and_seed(seedsk, message, params_enum);
+        DeterministicRandomness { salt, vinegar_seed, ctr: 0, vinegar_len: 0 }
+    }
+}
+
+impl SigningRandomness for DeterministicRandomness {
+    fn salt(&mut self, _len: usize) -> Result<Vec<u8>, MayoError> {
+        Ok(self.salt.0.clone())
+    }
+
+    fn vinegar(&mut self, count: usize) -> Result<GFVector, MayoError> {
+        let v_bytes = shake256_derive_vinegar_bytes(&self.vinegar_seed, self.ctr, count);
+        self.vinegar_len = count;
+        Ok(v_bytes.into_iter().map(|b| GFElement(b & 0x0F)).collect())
+    }
+
+    // r continues the attempt's stream after the vinegar bytes; it closes the attempt.
+    fn solution_offset(&mut self, count: usize) -> Result<GFVector, MayoError> {
+        let stream = shake256_derive_vinegar_bytes(&self.vinegar_seed, self.ctr, self.vinegar_len + count);
+        self.ctr += 1;
+        Ok(stream[self.vinegar_len..].iter().map(|&b| GFElement(b & 0x0F)).collect())
+    }
+}
+
+/// Builds the linearized whipped system of MAYO.Sign for the k vinegar vectors.
+///
+/// Signing picks s_i = (v_i + O x_i, x_i). The oil space is isotropic for P, so with
+/// L_a = (P1_a + P1_a^T)O + P2_a the whipped map becomes affine in x = x_0 || ... || x_{k-1}:
+/// P*(s) = A x + y'. Pair number l = (i, j) of `whipping_pairs` contributes E^l times
+/// * v_i^T P1_a v_i + v_i^T L_a x_i for i = j, and
+/// * v_i^T P1_a v_j + v_j^T P1_a v_i + v_i^T L_a x_j + v_j^T L_a x_i otherwise.
 ///
 /// # Arguments
-/// * `vinegar_vars` - The randomly sampled vinegar variables (n-o elements).
-/// * `o_matrix` - The secret O matrix.
-/// * `p1_matrices` - The set of m P1_i matrices.
-/// * `p2_matrices` - The set of m P2_i matrices.
-/// * `p3_matrices` - The set of m P3_i matrices.
-/// * `l_matrices` - The set of m L_i matrices (L_i = (P1_i + P1_i^T)O + P2_i).
+/// * `vinegar_vars` - The k vinegar vectors v_0 || ... || v_{k-1}, k*(n-o) elements.
+/// * `matrices` - The decoded P1_a, P1_a + P1_a^T and L_a matrices of the esk.
 /// * `params` - MAYO variant parameters.
 ///
 /// # Returns
-/// `Ok((GFMatrix /*A (m x o)*/, GFVector /*y_prime (m elements)*/))` or an error.
+/// `Ok((GFMatrix /*A (m x k*o)*/, GFVector /*y_prime (m elements)*/))` or an error.
 fn compute_lin_system_components(
-    vinegar_vars: &GFVector,        // s_V, length n-o
-    p1_mats: &[GFMatrix],           // Source for P_i^1, m of them, each (n-o)x(n-o)
-    l_mats: &[GFMatrix],            // P_i^2, m of them, each (n-o)xo
+    vinegar_vars: &GFVector,        // v_0 || ... || v_{k-1}, length k*(n-o)
+    matrices: &SigningMatrices,
     params: &MayoVariantParams
-) -> Result<(GFMatrix /*A*/, GFVector /*y_prime*/), &'static str> {
+) -> Result<(GFMatrix /*A*/, GFVector /*y_prime*/), MayoError> {
     
-    let num_vinegar_vars = params.n - params.o;
-    let num_oil_vars = params.o;
-    let m = params.m;
+    let num_vinegar_vars = params.vinegar_vars();
+    let num_oil_vars = params.oil_vars();
+    let (m, k) = (params.m, params.k);
+    let width = k * num_oil_vars;
 
-    if vinegar_vars.len() != num_vinegar_vars {
-        return Err("Vinegar variables vector has incorrect length");
+    if vinegar_vars.len() != k * num_vinegar_vars {
+        return Err(MayoError::DimensionMismatch("Vinegar variables vector has incorrect length"));
     }
-    if p1_mats.len() != m {
-        return Err("Incorrect number of P1 matrices");
+    let (p1_mats, p1_sym_mats, l_mats) = (&matrices.p1, &matrices.p1_sym, &matrices.l);
+    if p1_mats.len() != m || p1_sym_mats.len() != m {
+        return Err(MayoError::DimensionMismatch("Incorrect number of P1 matrices"));
     }
     if l_mats.len() != m {
-        return Err("Incorrect number of L matrices");
+        return Err(MayoError::DimensionMismatch("Incorrect number of L matrices"));
     }
+    let f_tail = params.f_tail()?;
+    let v_i = |i: usize| &vinegar_vars[i * num_vinegar_vars..(i + 1) * num_vinegar_vars];
+    let dot = |a: &[GFElement], b: &[GFElement]| {
+        a.iter().zip(b).fold(GFElement(0), |sum, (&x, &y)| gf16_add(sum, gf16_mul(x, y)))
+    };
+    let pairs = whipping_pairs(k);
 
-    let mut y_prime_elements = Vec::with_capacity(m);
-    let mut a_matrix_rows_as_vectors: Vec<GFVector> = Vec::with_capacity(m);
+    // Row a of `v_l` holds the k blocks v_i^T L_a (o elements each).
+    let mut v_l = vec![GFElement(0); m * width];
+    // u[l * m + a] is coordinate a of the constant term of pair l.
+    let mut u = vec![GFElement(0); pairs.len() * m];
+    // v_i^T (P1_a + P1_a^T) and v_i^T P1_a v_i for every i, reused across the m coordinates
+    let mut p1_sym_rows = vec![vec![GFElement(0); num_vinegar_vars]; k];
+    let mut p1_squares = vec![GFElement(0); k];
 
-    for i in 0..m {
-        let p1_i = &p1_mats[i];
-        if p1_i.num_rows() != num_vinegar_vars || p1_i.num_cols() != num_vinegar_vars {
-            return Err("P1 matrix has incorrect dimensions");
+    for a in 0..m {
+        let (p1_a, p1_sym_a) = (&p1_mats[a], &p1_sym_mats[a]);
+        if p1_a.num_rows() != num_vinegar_vars || p1_a.num_cols() != num_vinegar_vars
+            || p1_sym_a.num_rows() != num_vinegar_vars || p1_sym_a.num_cols() != num_vinegar_vars {
+            return Err(MayoError::DimensionMismatch("P1 matrix has incorrect dimensions"));
+        }
+        let l_a = &l_mats[a]; // (n-o) x o
+        if l_a.num_rows() != num_vinegar_vars || l_a.num_cols() != num_oil_vars {
+            return Err(MayoError::DimensionMismatch("L matrix has incorrect dimensions"));
         }
-        
-        // y_prime_i = s_V^T * P_i^1_symmetric * s_V
-        // P_i^1_symmetric = P1_i + P1_i^T
-        let p1_i_symmetric = matrix_symmetrize(p1_i)?; // M + M^T
-        // temp_y_vec = s_V^T * P_i^1_symmetric
-        let temp_y_vec = matrix_vec_mul_transpose_gfvector(vinegar_vars, &p1_i_symmetric)?;
-        // y_prime_i = temp_y_vec * s_V
-        let y_prime_i = vector_dot_product(&temp_y_vec, vinegar_vars)?;
-        y_prime_elements.push(y_prime_i);
 
-        // A_row_i = s_V^T * P_i^2
-        // P_i^2 is l_mats[i]
-        let l_i = &l_mats[i]; // (n-o) x o
-        if l_i.num_rows() != num_vinegar_vars || l_i.num_cols() != num_oil_vars {
-            return Err("L matrix has incorrect dimensions");
+        let v_l_row = &mut v_l[a * width..(a + 1) * width];
+        for (i, p1_sym_row) in p1_sym_rows.iter_mut().enumerate() {
+            matrix_vec_mul_transpose_into(v_i(i), l_a, &mut v_l_row[i * num_oil_vars..(i + 1) * num_oil_vars])?;
+            matrix_vec_mul_transpose_into(v_i(i), p1_sym_a, p1_sym_row)?;
+            p1_squares[i] = upper_quadratic_form(v_i(i), p1_a);
+        }
+        for (l, &(i, j)) in pairs.iter().enumerate() {
+            // v_i^T P1_a v_j + v_j^T P1_a v_i = v_i^T (P1_a + P1_a^T) v_j; for i = j that
+            // bilinear form vanishes in characteristic 2, so the quadratic form is used instead.
+            u[l * m + a] = if i == j { p1_squares[i] } else { dot(&p1_sym_rows[i], v_i(j)) };
         }
-        let a_row_i = matrix_vec_mul_transpose_gfvector(vinegar_vars, l_i)?; // (1 x (n-o)) * ((n-o) x o) = (1 x o)
-        a_matrix_rows_as_vectors.push(a_row_i);
     }
-    
-    // Construct A matrix from its rows
-    let a_matrix = GFMatrix::from_vectors(a_matrix_rows_as_vectors); // from_vectors checks for consistent row lengths
-    if a_matrix.num_rows() != m || a_matrix.num_cols() != num_oil_vars {
-        // This check should ideally be redundant if from_vectors is correct and inputs were okay
-        return Err("Constructed A matrix has incorrect dimensions");
+
+    // Horner over the pairs, applying E to A and y' together: block i of pair (i, j) picks up
+    // v_j^T L_a (the coefficient of x_i) and, for i != j, block j picks up v_i^T L_a.
+    let mut a_matrix_data = vec![GFElement(0); m * width];
+    let mut y_prime_elements = vec![GFElement(0); m];
+    for (l, &(i, j)) in pairs.iter().enumerate().rev() {
+        emulsify_rows(&mut a_matrix_data, width, f_tail);
+        emulsify_rows(&mut y_prime_elements, 1, f_tail);
+        for a in 0..m {
+            y_prime_elements[a] = gf16_add(y_prime_elements[a], u[l * m + a]);
+            let a_row = &mut a_matrix_data[a * width..(a + 1) * width];
+            let v_l_row = &v_l[a * width..(a + 1) * width];
+            let block = |b: usize| b * num_oil_vars..(b + 1) * num_oil_vars;
+            gfvector_axpy(&mut a_row[block(i)], GFElement(1), &v_l_row[block(j)]);
+            if i != j {
+                gfvector_axpy(&mut a_row[block(j)], GFElement(1), &v_l_row[block(i)]);
+            }
+        }
     }
 
+    let a_matrix = GFMatrix::new_with_data(m, width, a_matrix_data);
+
     Ok((a_matrix, y_prime_elements))
 }
 
+/// Computes v^T U v for an upper triangular U, reading only the upper triangle.
+fn upper_quadratic_form(v: &[GFElement], upper: &GFMatrix) -> GFElement {
+    let n = upper.num_cols();
+    let mut sum = GFElement(0);
+    for (r, &v_r) in v.iter().enumerate() {
+        let row = &upper.data[r * n + r..(r + 1) * n];
+        let row_dot = row.iter().zip(&v[r..]).fold(GFElement(0), |acc, (&u, &v_c)| gf16_add(acc, gf16_mul(u, v_c)));
+        sum = gf16_add(sum, gf16_mul(v_r, row_dot));
+    }
+    sum
+}
+
+/// The P1 and L matrix sets of an expanded secret key, decoded once so that no signing
+/// attempt repeats the decoding. The secret L_i are wiped on drop.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningMatrices {
+    /// The upper-triangular P1_i as stored in the esk.
+    pub p1: Vec<GFMatrix>,
+    /// P1_i + P1_i^T, computed once at decode time for the cross terms of the whipped map.
+    pub p1_sym: Vec<GFMatrix>,
+    /// The L_i matrices as stored in the esk.
+    pub l: Vec<GFMatrix>,
+}
+
+impl SigningMatrices {
+    /// Decodes P1 and L from their esk byte blobs and symmetrizes P1.
+    pub fn decode(p1_all_bytes: &[u8], l_all_bytes: &[u8], params: &MayoVariantParams) -> Result<Self, MayoError> {
+        let p1 = decode_p1_matrices(p1_all_bytes, params)?;
+        let p1_sym = p1.iter().map(symmetrize_from_upper).collect::<Result<Vec<_>, _>>()?;
+        let l = decode_l_matrices(l_all_bytes, params)?;
+        Ok(SigningMatrices { p1, p1_sym, l })
+    }
+}
+
+impl Drop for SigningMatrices {
+    fn drop(&mut self) {
+        self.l.wipe();
+    }
+}
 
 /// Implements MAYO.Sign (Algorithm 8 from the MAYO specification).
 /// Generates a signature for a given message using an expanded secret key.
-pub fn sign_message(esk: &ExpandedSecretKey, message: &Message, params_enum: &MayoParams) -> Result<Signature, String> {
+pub fn sign_message(esk: &ExpandedSecretKey, message: &Message, params_enum: &MayoParams) -> Result<Signature, MayoError> {
+    sign_message_with_rng(esk, message, &mut OsRng, params_enum)
+}
+
+/// Same as `sign_message`, drawing the salt and vinegar variables of every attempt from
+/// `rng` instead of the operating system. See `RngCoreRandomness`.
+pub fn sign_message_with_rng<R: RngCore>(esk: &ExpandedSecretKey, message: &Message, rng: &mut R, params_enum: &MayoParams) -> Result<Signature, MayoError> {
+    sign_message_with_randomness(esk, message, &SignatureOptions::default(), &mut RngCoreRandomness(rng), params_enum)
+}
+
+/// Same as `sign_message`, with `options` applied to the target derivation.
+/// The verifier must pass the same options to `verify_signature_with_options`.
+pub fn sign_message_with_options(esk: &ExpandedSecretKey, message: &Message, options: &SignatureOptions, params_enum: &MayoParams) -> Result<Signature, MayoError> {
+    sign_message_with_randomness(esk, message, options, &mut OsRandomness, params_enum)
+}
+
+/// Same as `sign_message`, but the first attempts use `salts` in order; once they are
+/// exhausted, further attempts draw fresh salts from the operating system. See `SaltPool`.
+pub fn sign_message_with_salts(esk: &ExpandedSecretKey, message: &Message, salts: &[Salt], params_enum: &MayoParams) -> Result<Signature, MayoError> {
+    sign_message_with_randomness(esk, message, &SignatureOptions::default(), &mut SaltPool::new(salts, OsRandomness), params_enum)
+}
+
+/// Deterministic MAYO.Sign: instead of the operating system RNG, the salt and vinegar
+/// variables are derived from the secret key seed and the message (see
+/// `DeterministicRandomness`), so the same `(esk, message)` always gives the same signature.
+pub fn sign_message_deterministic(esk: &ExpandedSecretKey, message: &Message, params_enum: &MayoParams) -> Result<Signature, MayoError> {
+    let params = params_enum.variant();
+    params.validate()?;
+    let seedsk_bytes = esk.0.get(..params.sk_seed_bytes)
+        .ok_or(MayoError::InvalidKeyLength("Expanded secret key has incorrect total length based on components"))?;
+    let mut rng = DeterministicRandomness::new(&SeedSK(seedsk_bytes.to_vec()), message, params_enum);
+    sign_message_with_randomness(esk, message, &SignatureOptions::default(), &mut rng, params_enum)
+}
+
+/// Same as `sign_message_with_options`, drawing the salt and vinegar variables of every
+/// attempt from `rng` instead of the operating system.
+pub fn sign_message_with_randomness<R: SigningRandomness>(esk: &ExpandedSecretKey, message: &Message, options: &SignatureOptions, rng: &mut R, params_enum: &MayoParams) -> Result<Signature, MayoError> {
+    sign_expanded_with_randomness(esk, message, options, rng, params_enum).map(|expanded| expanded.signature)
+}
+
+/// Same as `sign_message_with_options`, also returning the signer's evaluation y = P*(s),
+/// computed from the solved linear system as A x + y'. See `ExpandedSignature`.
+pub fn sign_message_expanded(esk: &ExpandedSecretKey, message: &Message, options: &SignatureOptions, params_enum: &MayoParams) -> Result<ExpandedSignature, MayoError> {
+    sign_expanded_with_randomness(esk, message, options, &mut OsRandomness, params_enum)
+}
+
+fn sign_expanded_with_randomness<R: SigningRandomness>(esk: &ExpandedSecretKey, message: &Message, options: &SignatureOptions, rng: &mut R, params_enum: &MayoParams) -> Result<ExpandedSignature, MayoError> {
     let params = params_enum.variant();
+    params.validate()?;
 
     // 1. Parse esk and re-derive necessary components
     //    esk = seedsk || O_bytes || P1_all_bytes || L_all_bytes
@@ -108,11 +376,10 @@ pub fn sign_message(esk: &ExpandedSecretKey, message: &Message, params_enum: &Ma
     let o_bytes_len = params.o_bytes;
     let p1_all_bytes_len = params.p1_bytes;
     // L_all_bytes length is the rest, or can be calculated:
-    let num_l_elements = params.m * (params.n - params.o) * params.o;
-    let l_all_bytes_len_expected = MayoParams::bytes_for_gf16_elements(num_l_elements);
+    let l_all_bytes_len_expected = params.l_bytes();
 
     if esk.0.len() != seedsk_bytes_len + o_bytes_len + p1_all_bytes_len + l_all_bytes_len_expected {
-        return Err("Expanded secret key has incorrect total length based on components".to_string());
+        return Err(MayoError::InvalidKeyLength("Expanded secret key has incorrect total length based on components"));
     }
 
     let seedsk_bytes_slice = &esk.0[0..seedsk_bytes_len];
@@ -123,110 +390,210 @@ pub fn sign_message(esk: &ExpandedSecretKey, message: &Message, params_enum: &Ma
     let l_all_bytes_slice = &esk.0[seedsk_bytes_len + o_bytes_len + p1_all_bytes_len ..];
     
     if l_all_bytes_slice.len() != l_all_bytes_len_expected {
-        return Err("L_all_bytes component of ESK has unexpected length".to_string());
+        return Err(MayoError::InvalidKeyLength("L_all_bytes component of ESK has unexpected length"));
     }
 
     // Re-derive seedpk to get P2_bytes and P3_bytes (P1_bytes also re-derived for consistency, though available in esk)
     let (seedpk, derived_o_bytes) = shake256_xof_derive_pk_seed_and_o(&seedsk, params_enum);
+    let derived_o_bytes = WipeOnDrop(derived_o_bytes);
     if derived_o_bytes.as_slice() != o_bytes_slice { // Compare Vec<u8> with &[u8]
-        return Err("O_bytes in ESK does not match derivation from seedsk in ESK".to_string());
+        return Err(MayoError::InvalidKey("O_bytes in ESK does not match derivation from seedsk in ESK"));
     }
     
+    // O maps the oil variables into the vinegar coordinates of s, see step 8.
+    let o_matrix = WipeOnDrop(esk.decode_o(params_enum)?);
+
     // P1 matrices can be decoded from esk's p1_all_bytes, or re-derived from seedpk.
     // Let's use re-derived ones as per typical flow where esk might only store minimal seeds.
     // However, Algorithm 6 stores O_bytes, P1_all_bytes, L_all_bytes in esk.
     // So, we should use P1_all_bytes from esk.
     let p1_all_bytes_from_esk_slice = &esk.0[seedsk_bytes_len + o_bytes_len .. seedsk_bytes_len + o_bytes_len + p1_all_bytes_len];
 
-    let p1_matrices = decode_p1_matrices(p1_all_bytes_from_esk_slice, params).map_err(|e_str| e_str.to_string())?;
-    
-    // P2 and P3 are not in esk, they are derived from seedpk.
-    let _p2_all_bytes_from_seedpk = derive_p2_bytes(&seedpk, params); // Renamed as it's not used directly after this
-    let _p3_all_bytes_from_seedpk = shake256_xof_derive_p3(&seedpk, params_enum); // Renamed
+    // For key binding, rebuild epk = P1 || P2 || P3 so the fingerprint matches the verifier's.
+    // P2 and P3 are not in esk: P2 is derived from seedpk and P3 is computed from O, P1 and P2.
+    let pk_fingerprint = if options.bind_public_key {
+        let p2_all_bytes_from_seedpk = derive_p2_bytes(&seedpk, params)?;
+        let p3_all_bytes = compute_p3_bytes(&seedpk, &o_matrix, params_enum)?;
+        let mut epk_bytes = Vec::with_capacity(params.epk_bytes());
+        epk_bytes.extend_from_slice(p1_all_bytes_from_esk_slice);
+        epk_bytes.extend_from_slice(&p2_all_bytes_from_seedpk);
+        epk_bytes.extend_from_slice(&p3_all_bytes);
+        Some(public_key_fingerprint(&ExpandedPublicKey(epk_bytes), params_enum))
+    } else {
+        None
+    };
 
     // _p2_matrices was here
     // _p3_matrices was here
     
-    // O and L matrices are from esk.
-    // _o_matrix was here
-    let l_matrices = decode_l_matrices(l_all_bytes_slice, params).map_err(|e_str| e_str.to_string())?;
+    // L matrices are from esk.
+    // Only the vinegar variables change between retries, so P1 and L are decoded once.
+    let matrices = SigningMatrices::decode(p1_all_bytes_from_esk_slice, l_all_bytes_slice, params)?;
 
 
     // 2. Hash message M to M_digest
-    let m_digest = shake256_digest(&message.0, params_enum);
+    let m_digest = message_digest(message, params_enum);
     
     let mut no_solution_count = 0;
     let mut solver_error_count = 0;
-    let mut last_solver_error: Option<&'static str> = None;
+    let mut last_solver_error: Option<MayoError> = None;
 
     for _retry_count in 0..MAX_SIGN_RETRIES {
         // 3. Sample salt
-        let mut salt_bytes_vec = vec![0u8; params.salt_bytes];
-        getrandom(&mut salt_bytes_vec).map_err(|_| "Failed to generate random salt".to_string())?;
-        let salt = Salt(salt_bytes_vec);
+        let salt = sample_salt(rng, params)?;
 
         // 4. Derive target vector t
-        let t_bytes = shake256_derive_target_t(&m_digest, &salt, params_enum);
-        let t_vector = decode_gf_elements(&t_bytes, params.m).map_err(|e_str| e_str.to_string())?;
-
-        // 5. Sample random vinegar variables (n-o variables)
-        let num_vinegar_vars = params.n - params.o;
-        let mut vinegar_vars_vec = Vec::with_capacity(num_vinegar_vars);
-        for _ in 0..num_vinegar_vars {
-            let mut v_byte = [0u8;1];
-            getrandom(&mut v_byte).map_err(|_| "Failed to generate random vinegar variable".to_string())?;
-            vinegar_vars_vec.push(GFElement(v_byte[0] & 0x0F)); // Ensure it's a nibble
-        }
-        let vinegar_vars = vinegar_vars_vec;
-
-        // 6. Compute matrix A (m x o) and vector y_prime (m elements)
-        // Note: P2 and P3 matrices are not directly used by compute_lin_system_components
-        // under the current interpretation. o_matrix is also not used.
-        let (a_matrix, y_prime_vector) = match compute_lin_system_components(
-            &vinegar_vars, &p1_matrices, &l_matrices, params
-        ) {
-            Ok(res) => res,
-            Err(e) => return Err(e.to_string()), 
-        };
+        let t_vector = derive_target(&m_digest, &salt, pk_fingerprint.as_deref(), params_enum)?;
 
-        // 7. Solve Ax = t - y_prime for x (o elements - oil variables)
-        let target_for_solver = matrix_sub_vectors_gfvector(&t_vector, &y_prime_vector).map_err(|e_str| e_str.to_string())?;
-        
-        match solve_linear_system(&a_matrix, &target_for_solver) {
-            Ok(Some(x_solution_oils)) => { // x_solution_oils has 'o' elements
-                if x_solution_oils.len() != params.o {
-                    // Should be guaranteed by solver if A is m x o.
-                    return Err("Solver returned oil solution of incorrect length".to_string());
-                }
-                // 8. Construct signature vector s (n elements = n-o vinegar + o oil)
-                let mut s_elements: GFVector = Vec::with_capacity(params.n);
-                s_elements.extend_from_slice(&vinegar_vars);
-                s_elements.extend_from_slice(&x_solution_oils);
-                
-                // 9. Encode s and concatenate with salt
-                let s_bytes = encode_s_vector(&s_elements, params);
-                
-                let mut sig_bytes = Vec::with_capacity(s_bytes.len() + params.salt_bytes);
-                sig_bytes.extend_from_slice(&s_bytes);
-                sig_bytes.extend_from_slice(&salt.0);
-                
-                return Ok(Signature(sig_bytes));
+        // 5. Sample vinegar variables (k vectors of n-o variables)
+        let mut vinegar_vars = sample_vinegar(rng, params)?;
+        let mut solution_offset = sample_solution_offset(rng, params)?;
+
+        // 6-7. Build the whipped linear system for these vinegar variables and solve it for the oil variables
+        let solution = match try_solve(&vinegar_vars, &solution_offset, &t_vector, &matrices, params) {
+            Ok(AttemptOutcome::Solved(x_solution_oils, y_vector)) => {
+                // 8. Construct s from the k vinegar vectors and the k*o oil variables
+                construct_s(&vinegar_vars, &x_solution_oils, &o_matrix, params).map(|s_elements| Some((s_elements, y_vector)))
             }
-            Ok(None) => {
+            Ok(AttemptOutcome::NoSolution) => {
                 no_solution_count += 1;
-                continue;
+                Ok(None)
             }
-            Err(e) => {
+            Ok(AttemptOutcome::SolverError(e)) => {
                 solver_error_count += 1;
                 last_solver_error = Some(e);
-                // eprintln!("Solver error: {}", e); // Keep for local debugging if desired, but will be in final error string
-                continue;
+                Ok(None)
+            }
+            Err(e) => Err(e),
+        };
+        // Together with s, the vinegar variables reveal O x_i; wipe them whatever the outcome.
+        wipe_elements(&mut vinegar_vars);
+        wipe_elements(&mut solution_offset);
+
+        if let Some((s_elements, y_vector)) = solution? {
+            // 9. Encode s and concatenate with salt
+            let s_bytes = encode_s_vector(&s_elements, params);
+            
+            let mut sig_bytes = Vec::with_capacity(s_bytes.len() + params.salt_bytes);
+            sig_bytes.extend_from_slice(&s_bytes);
+            sig_bytes.extend_from_slice(&salt.0);
+            // The verifier re-derives t from the embedded salt, so it must be the one t came from.
+            debug_assert_eq!(&sig_bytes[s_bytes.len()..], salt.0.as_slice(), "Embedded salt differs from the salt used to derive t");
+            
+            return Ok(ExpandedSignature { signature: Signature(sig_bytes), y: y_vector });
+        }
+    }
+    Err(MayoError::SignRetriesExhausted {
+        attempts: MAX_SIGN_RETRIES,
+        no_solution: no_solution_count,
+        solver_errors: solver_error_count,
+        last_solver_error: last_solver_error.map(Box::new),
+    })
+}
+
+
+/// Result of solving one attempt's linear system. Only errors that make every attempt fail
+/// (malformed key material) abort signing; these outcomes just decide whether to retry.
+enum AttemptOutcome {
+    /// The oil variables x solving A x = t - y', and the resulting P*(s) = A x + y'.
+    Solved(GFVector, GFVector),
+    /// A x = t - y' is inconsistent for this salt and these vinegar variables.
+    NoSolution,
+    /// The solver rejected the system; the attempt is retried like `NoSolution`.
+    SolverError(MayoError),
+}
+
+/// Step 3: draws a fresh salt from `rng` and checks its length.
+fn sample_salt<R: SigningRandomness>(rng: &mut R, params: &MayoVariantParams) -> Result<Salt, MayoError> {
+    let salt_bytes_vec = rng.salt(params.salt_bytes)?;
+    if salt_bytes_vec.len() != params.salt_bytes {
+        return Err(MayoError::RngFailure("Randomness source returned salt of incorrect length"));
+    }
+    Ok(Salt(salt_bytes_vec))
+}
+
+/// Step 4: derives the m-element target t from the message digest and salt
+/// (and the public key fingerprint for key-bound signatures).
+fn derive_target(m_digest: &MessageDigest, salt: &Salt, pk_fingerprint: Option<&[u8]>, params_enum: &MayoParams) -> Result<GFVector, MayoError> {
+    let t_bytes = shake256_derive_target_t_with_binding(m_digest, salt, pk_fingerprint, params_enum);
+    decode_target_vector(&t_bytes, params_enum.variant().m)
+}
+
+/// Step 5: draws k*(n-o) vinegar variables (v_0 || ... || v_{k-1}) from `rng` and checks their count.
+fn sample_vinegar<R: SigningRandomness>(rng: &mut R, params: &MayoVariantParams) -> Result<GFVector, MayoError> {
+    let count = params.k * params.vinegar_vars();
+    let vinegar_vars = rng.vinegar(count)?;
+    if vinegar_vars.len() != count {
+        return Err(MayoError::RngFailure("Randomness source returned vinegar variables of incorrect length"));
+    }
+    Ok(vinegar_vars)
+}
+
+/// Step 6 (SampleSolution): draws the k*o elements of the solution offset r from `rng`.
+fn sample_solution_offset<R: SigningRandomness>(rng: &mut R, params: &MayoVariantParams) -> Result<GFVector, MayoError> {
+    let count = params.k * params.oil_vars();
+    let r = rng.solution_offset(count)?;
+    if r.len() != count {
+        return Err(MayoError::RngFailure("Randomness source returned solution offset of incorrect length"));
+    }
+    Ok(r)
+}
+
+/// Step 8: s = s_0 || ... || s_{k-1} with s_i = (v_i + O x_i, x_i) (n elements each).
+/// Adding O x_i moves x_i into the oil space, where the public map only contributes
+/// the bilinear terms v^T L_a x that A x accounts for.
+fn construct_s(vinegar_vars: &GFVector, x_solution_oils: &GFVector, o_matrix: &GFMatrix, params: &MayoVariantParams) -> Result<GFVector, MayoError> {
+    let mut s_elements: GFVector = Vec::with_capacity(params.k * params.n);
+    for (v_i, x_i) in vinegar_vars.chunks(params.vinegar_vars()).zip(x_solution_oils.chunks(params.oil_vars())) {
+        let o_x = matrix_vec_mul(o_matrix, &x_i.to_vec())?;
+        s_elements.extend(v_i.iter().zip(&o_x).map(|(&v, &ox)| gf16_add(v, ox)));
+        s_elements.extend_from_slice(x_i);
+    }
+    Ok(s_elements)
+}
+
+/// Steps 6-7: computes A and y' for `vinegar_vars` and solves A x = t - y' for the k*o oil
+/// variables. As in SampleSolution, the solver works on A x' = t - y' - A r and the result is
+/// x = x' + r, so the variables the solver leaves free take the random values of r.
+fn try_solve(
+    vinegar_vars: &GFVector,
+    solution_offset: &GFVector,
+    t_vector: &GFVector,
+    matrices: &SigningMatrices,
+    params: &MayoVariantParams
+) -> Result<AttemptOutcome, MayoError> {
+    // Note: P2 enters through L and P3 through the isotropy of the oil space;
+    // O is applied to the solution when s is built.
+    let (a_matrix, y_prime_vector) = compute_lin_system_components(vinegar_vars, matrices, params)?;
+    let a_r = matrix_vec_mul(&a_matrix, solution_offset)?;
+    let target_for_solver = matrix_sub_vectors_gfvector(&matrix_sub_vectors_gfvector(t_vector, &y_prime_vector)?, &a_r)?;
+
+    match solve_linear_system(&a_matrix, &target_for_solver) {
+        Ok(Some(mut x_solution_oils)) => {
+            if x_solution_oils.len() != params.k * params.oil_vars() {
+                // Should be guaranteed by solver if A is m x k*o.
+                return Err(MayoError::DimensionMismatch("Solver returned oil solution of incorrect length"));
             }
+            gfvector_axpy(&mut x_solution_oils, GFElement(1), solution_offset);
+            let a_x = matrix_vec_mul(&a_matrix, &x_solution_oils)?;
+            let y_vector = a_x.iter().zip(&y_prime_vector).map(|(&ax, &y)| gf16_add(ax, y)).collect();
+            Ok(AttemptOutcome::Solved(x_solution_oils, y_vector))
         }
+        Ok(None) => Ok(AttemptOutcome::NoSolution),
+        Err(e) => Ok(AttemptOutcome::SolverError(e)),
     }
-    Err(format!("MAYO.Sign failed after maximum retries ({} attempts). No solution found: {} times. Solver errored: {} times. Last solver error: {:?}", MAX_SIGN_RETRIES, no_solution_count, solver_error_count, last_solver_error))
 }
 
+/// Produces `count` independent signatures over the same message.
+/// Each signature is generated by a separate `sign_message` call, so each one
+/// samples its own salt and vinegar variables.
+pub fn sign_multiple(esk: &ExpandedSecretKey, message: &Message, count: usize, params_enum: &MayoParams) -> Result<Vec<Signature>, MayoError> {
+    let mut signatures = Vec::with_capacity(count);
+    for _ in 0..count {
+        signatures.push(sign_message(esk, message, params_enum)?);
+    }
+    Ok(signatures)
+}
 
 #[cfg(test)]
 mod tests {
@@ -235,6 +602,9 @@ mod tests {
     use crate::types::ExpandedSecretKey as EskTypeForTest;
     use crate::params::MayoParams;
     use crate::keygen::{compact_key_gen, expand_sk}; // For generating esk
+    use crate::codec::{decode_s_vector, encode_gf_elements};
+    use crate::hash::shake256_derive_target_t;
+    use crate::matrix::matrix_vec_mul;
 
     // Helper to create a dummy ESK for testing the flow
     // This is complex because ESK structure is seedsk | O_bytes | P1_bytes | L_bytes
@@ -251,24 +621,9 @@ mod tests {
         let esk = create_dummy_esk(&params_enum);
         let message = Message(b"test message".to_vec());
 
-        let sign_result = sign_message(&esk, &message, &params_enum);
-        
-        // With compute_lin_system_components implemented, we expect either Ok (if solvable by chance)
-        // or Err from solver or the detailed "MAYO.Sign failed after maximum retries..." string.
-        // For this test, we are checking that it doesn't panic and proceeds past component computation.
-        // If it returns Ok, A and y_prime were computed with correct dimensions.
-        match sign_result {
-            Ok(sig) => {
-                let expected_sig_len = MayoParams::bytes_for_gf16_elements(params_variant.n) + params_variant.salt_bytes;
-                assert_eq!(sig.0.len(), expected_sig_len, "Signature length is incorrect");
-            },
-            Err(e_string) => { // e_string is now String
-                assert!(
-                    e_string.contains("MAYO.Sign failed after maximum retries") || e_string.contains("Solver error"),
-                    "Expected sign failure (max retries or solver error), got: {}", e_string
-                );
-            }
-        }
+        // A is m x k*o with k*o >= m, so a random key is solvable within a few attempts.
+        let sig = sign_message(&esk, &message, &params_enum).expect("MAYO1 signing should succeed");
+        assert_eq!(sig.0.len(), params_variant.sig_bytes(), "Signature length is incorrect");
     }
 
     #[test]
@@ -278,35 +633,698 @@ mod tests {
         let esk = create_dummy_esk(&params_enum);
         let message = Message(b"another test message".to_vec());
 
-        let sign_result = sign_message(&esk, &message, &params_enum);
-        match sign_result {
-            Ok(sig) => {
-                let expected_sig_len = MayoParams::bytes_for_gf16_elements(params_variant.n) + params_variant.salt_bytes;
-                assert_eq!(sig.0.len(), expected_sig_len, "Signature length is incorrect");
-            },
-            Err(e_string) => { // e_string is now String
-                assert!(
-                    e_string.contains("MAYO.Sign failed after maximum retries") || e_string.contains("Solver error"),
-                    "Expected sign failure (max retries or solver error), got: {}", e_string
-                );
+        let sig = sign_message(&esk, &message, &params_enum).expect("MAYO2 signing should succeed");
+        assert_eq!(sig.0.len(), params_variant.sig_bytes(), "Signature length is incorrect");
+    }
+
+    #[test]
+    fn test_signature_verifies_under_matching_public_key() {
+        use crate::keygen::expand_pk;
+        use crate::verify::verify_signature;
+
+        for params_enum in MayoParams::all_variants() {
+            let (csk, cpk) = compact_key_gen(&params_enum).unwrap();
+            let esk = expand_sk(&csk, &params_enum).unwrap();
+            let epk = expand_pk(&cpk, &params_enum).unwrap();
+            let message = Message(b"end-to-end signature".to_vec());
+
+            let sig = sign_message(&esk, &message, &params_enum).unwrap();
+            assert_eq!(verify_signature(&epk, &message, &sig, &params_enum), Ok(true));
+            assert_eq!(verify_signature(&epk, &Message(b"another message".to_vec()), &sig, &params_enum), Ok(false));
+        }
+    }
+
+    /// `SigningRandomness` that forwards to `inner` and counts the salts drawn, i.e. attempts.
+    struct AttemptCounter<R: SigningRandomness> {
+        inner: R,
+        attempts: usize,
+    }
+
+    impl<R: SigningRandomness> SigningRandomness for AttemptCounter<R> {
+        fn salt(&mut self, len: usize) -> Result<Vec<u8>, MayoError> {
+            self.attempts += 1;
+            self.inner.salt(len)
+        }
+
+        fn vinegar(&mut self, count: usize) -> Result<GFVector, MayoError> {
+            self.inner.vinegar(count)
+        }
+
+        fn solution_offset(&mut self, count: usize) -> Result<GFVector, MayoError> {
+            self.inner.solution_offset(count)
+        }
+    }
+
+    /// Signs `num_trials` random messages under `csk` and returns the fraction that succeeded
+    /// on the first attempt, i.e. whose first linear system A x = t - y' was solvable.
+    fn solver_success_rate(csk: &crate::types::CompactSecretKey, num_trials: usize, params_enum: &MayoParams) -> f64 {
+        let esk = expand_sk(csk, params_enum).unwrap();
+        let mut first_attempt_successes = 0;
+        for _ in 0..num_trials {
+            let mut message_bytes = vec![0u8; 32];
+            getrandom(&mut message_bytes).unwrap();
+            let mut rng = AttemptCounter { inner: OsRandomness, attempts: 0 };
+            sign_message_with_randomness(&esk, &Message(message_bytes), &SignatureOptions::default(), &mut rng, params_enum).unwrap();
+            if rng.attempts == 1 {
+                first_attempt_successes += 1;
             }
         }
+        first_attempt_successes as f64 / num_trials as f64
+    }
+
+    /// Signs `message` under `csk`, then compares the target t re-derived from the signature's
+    /// salt with the verifier's P*(s). Returns `(coordinate, t_i, P*(s)_i)` wherever they differ,
+    /// so a broken round trip shows exactly which coordinates of the public map disagree.
+    fn diff_sign_verify_map(csk: &crate::types::CompactSecretKey, message: &Message, params_enum: &MayoParams) -> Result<Vec<(usize, GFElement, GFElement)>, MayoError> {
+        let params = params_enum.variant();
+        let signature = sign_message(&expand_sk(csk, params_enum)?, message, params_enum)?;
+        let epk = crate::keygen::expand_pk(&csk.public_key(params_enum)?, params_enum)?;
+
+        let salt = Salt(signature.0[params.s_bytes()..].to_vec());
+        let t_bytes = shake256_derive_target_t(&message_digest(message, params_enum), &salt, params_enum);
+        let t_vector = decode_target_vector(&t_bytes, params.m)?;
+        let y_vector = crate::verify::evaluate_public_map(&epk, &signature, params_enum)?;
+        Ok(t_vector.into_iter().zip(y_vector).enumerate()
+            .filter(|(_, (t_i, y_i))| t_i != y_i)
+            .map(|(i, (t_i, y_i))| (i, t_i, y_i))
+            .collect())
+    }
+
+    #[test]
+    fn test_diff_sign_verify_map_is_empty_for_valid_signature() {
+        // MAYO1 is the smallest shipped parameter set.
+        let params_enum = MayoParams::mayo1();
+        let (csk, _) = compact_key_gen(&params_enum).unwrap();
+        assert_eq!(diff_sign_verify_map(&csk, &Message(b"diff the public map".to_vec()), &params_enum), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_solver_success_rate() {
+        // A is m x k*o with k*o > m for both variants, so it has full row rank (and every
+        // target is reachable) far more often than the 1 - 1/q of a square system. A broken
+        // linearization shows up as a rate near zero.
+        for params_enum in MayoParams::all_variants() {
+            let (csk, _) = compact_key_gen(&params_enum).unwrap();
+            let rate = solver_success_rate(&csk, 16, &params_enum);
+            assert!(rate >= 0.85, "first-attempt success rate {} is too low", rate);
+        }
+    }
+
+    #[test]
+    fn test_deterministic_signature_is_reproducible_and_verifies() {
+        for params_enum in MayoParams::all_variants() {
+            let (csk, cpk) = compact_key_gen(&params_enum).unwrap();
+            let esk = expand_sk(&csk, &params_enum).unwrap();
+            let epk = crate::keygen::expand_pk(&cpk, &params_enum).unwrap();
+            let message = Message(b"derandomized".to_vec());
+
+            let sig = sign_message_deterministic(&esk, &message, &params_enum).unwrap();
+            assert_eq!(sign_message_deterministic(&esk, &message, &params_enum), Ok(sig.clone()));
+            assert_eq!(crate::verify::verify_signature(&epk, &message, &sig, &params_enum), Ok(true));
+
+            // The salt comes from SHAKE256(seedsk || M), so another message gets another salt.
+            let other = sign_message_deterministic(&esk, &Message(b"derandomized!".to_vec()), &params_enum).unwrap();
+            let s_bytes_len = params_enum.variant().s_bytes();
+            assert_ne!(other.0[s_bytes_len..], sig.0[s_bytes_len..]);
+        }
+    }
+
+    #[test]
+    fn test_deterministic_randomness_advances_vinegar_per_attempt() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let seedsk = SeedSK(vec![7u8; params.sk_seed_bytes]);
+        let mut rng = DeterministicRandomness::new(&seedsk, &Message(b"retries".to_vec()), &params_enum);
+        let count = params.k * params.vinegar_vars();
+        let r_count = params.k * params.oil_vars();
+
+        // Each attempt draws a salt, vinegar variables and a solution offset, in that order.
+        let (salt_0, vinegar_0) = (rng.salt(params.salt_bytes).unwrap(), rng.vinegar(count).unwrap());
+        let r_0 = rng.solution_offset(r_count).unwrap();
+        let (salt_1, vinegar_1) = (rng.salt(params.salt_bytes).unwrap(), rng.vinegar(count).unwrap());
+        let r_1 = rng.solution_offset(r_count).unwrap();
+        assert_eq!(salt_0.len(), params.salt_bytes);
+        assert_eq!(salt_0, salt_1, "every attempt reuses the derived salt");
+        assert_eq!(vinegar_0.len(), count);
+        assert_ne!(vinegar_0, vinegar_1, "each attempt must draw fresh vinegar variables");
+        assert_eq!(r_0.len(), r_count);
+        assert_ne!(r_0, r_1, "each attempt must draw a fresh solution offset");
+        assert_ne!(r_0[..], vinegar_0[..r_count], "r continues the stream after the vinegar variables");
+
+        let mut replay = DeterministicRandomness::new(&seedsk, &Message(b"retries".to_vec()), &params_enum);
+        assert_eq!(replay.vinegar(count).unwrap(), vinegar_0);
+    }
+
+    /// Seeded SplitMix64 generator: the same seed always yields the same byte stream.
+    struct SeededRng(u64);
+
+    impl RngCore for SeededRng {
+        fn next_u32(&mut self) -> u32 {
+            self.next_u64() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+            let mut z = self.0;
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            rand_core::impls::fill_bytes_via_next(self, dest)
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_seeded_rng_reproduces_key_pair_and_signature() {
+        let params_enum = MayoParams::mayo1();
+        let message = Message(b"seeded rng".to_vec());
+        let run = |seed: u64| {
+            let mut rng = SeededRng(seed);
+            let (csk, cpk) = crate::keygen::compact_key_gen_with_rng(&mut rng, &params_enum).unwrap();
+            let esk = expand_sk(&csk, &params_enum).unwrap();
+            let sig = sign_message_with_rng(&esk, &message, &mut rng, &params_enum).unwrap();
+            (csk, cpk, sig)
+        };
+
+        let (csk, cpk, sig) = run(42);
+        assert_eq!(run(42), (csk.clone(), cpk.clone(), sig.clone()));
+        let epk = crate::keygen::expand_pk(&cpk, &params_enum).unwrap();
+        assert_eq!(crate::verify::verify_signature(&epk, &message, &sig, &params_enum), Ok(true));
+
+        let (other_csk, _, _) = run(43);
+        assert_ne!(other_csk, csk);
+    }
+
+    #[test]
+    fn test_sign_multiple_zero_count() {
+        let params_enum = MayoParams::mayo1();
+        let esk = create_dummy_esk(&params_enum);
+        let message = Message(b"no signatures requested".to_vec());
+        assert_eq!(sign_multiple(&esk, &message, 0, &params_enum), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn test_free_oil_variables_are_randomized() {
+        // A has more columns than rows, so the solver leaves variables of x_{k-1} free.
+        // Without the solution offset they would be zero in every signature.
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let (csk, cpk) = compact_key_gen(&params_enum).unwrap();
+        let esk = expand_sk(&csk, &params_enum).unwrap();
+        let epk = crate::keygen::expand_pk(&cpk, &params_enum).unwrap();
+
+        let mut nonzero_tails = 0;
+        for i in 0..4u8 {
+            let message = Message(vec![i; 8]);
+            let sig = sign_message(&esk, &message, &params_enum).unwrap();
+            assert_eq!(crate::verify::verify_signature(&epk, &message, &sig, &params_enum), Ok(true));
+            let s = crate::codec::decode_s_vector(&sig.0[..params.s_bytes()], params).unwrap();
+            if s[params.k * params.n - params.o..].iter().any(|e| e.0 != 0) {
+                nonzero_tails += 1;
+            }
+        }
+        assert!(nonzero_tails > 0, "x_(k-1) was zero in every signature");
+    }
+
+    #[test]
+    fn test_sign_multiple_distinct_salts() {
+        let params_enum = MayoParams::mayo1();
+        let params_variant = params_enum.variant();
+        let (csk, cpk) = compact_key_gen(&params_enum).unwrap();
+        let esk = expand_sk(&csk, &params_enum).unwrap();
+        let epk = crate::keygen::expand_pk(&cpk, &params_enum).unwrap();
+        let message = Message(b"message signed several times".to_vec());
+
+        let sigs = sign_multiple(&esk, &message, 2, &params_enum).unwrap();
+        assert_eq!(sigs.len(), 2);
+        let s_bytes_len = params_variant.s_bytes();
+        assert_ne!(&sigs[0].0[s_bytes_len..], &sigs[1].0[s_bytes_len..], "Signatures should use distinct salts");
+        for sig in &sigs {
+            assert_eq!(crate::verify::verify_signature(&epk, &message, sig, &params_enum), Ok(true));
+        }
+    }
+
+    #[test]
+    fn test_signing_matrices_cache_matches_on_the_fly() {
+        use crate::matrix::matrix_symmetrize;
+
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let esk = create_dummy_esk(&params_enum);
+
+        let p1_start = params.sk_seed_bytes + params.o_bytes;
+        let p1_end = p1_start + params.p1_bytes;
+        let cached = SigningMatrices::decode(&esk.0[p1_start..p1_end], &esk.0[p1_end..], params).unwrap();
+        assert_eq!(cached.p1, decode_p1_matrices(&esk.0[p1_start..p1_end], params).unwrap());
+        assert_eq!(cached.l, decode_l_matrices(&esk.0[p1_end..], params).unwrap());
+        for (p1_i, p1_sym_i) in cached.p1.iter().zip(&cached.p1_sym) {
+            assert_eq!(p1_sym_i, &matrix_symmetrize(p1_i).unwrap());
+        }
+
+        // The cross terms from P1 + P1^T and the squares from the upper triangle give the same
+        // constant term as evaluating v_i^T P1 v_j + v_j^T P1 v_i on the fly.
+        let vinegar_vars: GFVector = (0..params.k * params.vinegar_vars()).map(|i| GFElement((i * 5 % 16) as u8)).collect();
+        let v_i = |i: usize| vinegar_vars[i * params.vinegar_vars()..(i + 1) * params.vinegar_vars()].to_vec();
+        let quadratic = |a: &GFVector, p1: &GFMatrix, b: &GFVector| {
+            crate::matrix::vector_dot_product(&crate::matrix::matrix_vec_mul_transpose_gfvector(a, p1).unwrap(), b).unwrap()
+        };
+        let mut expected_y_prime = vec![GFElement(0); params.m];
+        for &(i, j) in whipping_pairs(params.k).iter().rev() {
+            emulsify_rows(&mut expected_y_prime, 1, params.f_tail().unwrap());
+            for (y, p1_a) in expected_y_prime.iter_mut().zip(&cached.p1) {
+                let mut u = quadratic(&v_i(i), p1_a, &v_i(j));
+                if i != j {
+                    u = gf16_add(u, quadratic(&v_i(j), p1_a, &v_i(i)));
+                }
+                *y = gf16_add(*y, u);
+            }
+        }
+        let (_, y_prime) = compute_lin_system_components(&vinegar_vars, &cached, params).unwrap();
+        assert_eq!(y_prime, expected_y_prime);
+    }
+
+    #[test]
+    fn test_linearized_system_matches_whipped_public_map() {
+        use crate::keygen::expand_pk;
+        use crate::verify::{compute_whipped_p_star, decode_epk_matrices};
+
+        for params_enum in MayoParams::all_variants() {
+            let params = params_enum.variant();
+            let (csk, cpk) = compact_key_gen(&params_enum).unwrap();
+            let esk = expand_sk(&csk, &params_enum).unwrap();
+            let (p1, p2, p3) = decode_epk_matrices(&expand_pk(&cpk, &params_enum).unwrap(), params).unwrap();
+            let matrices = SigningMatrices::decode(&esk.0[params.sk_seed_bytes + params.o_bytes..params.sk_seed_bytes + params.o_bytes + params.p1_bytes],
+                                                   &esk.0[params.sk_seed_bytes + params.o_bytes + params.p1_bytes..], params).unwrap();
+            let o_matrix = esk.decode_o(&params_enum).unwrap();
+
+            let vinegar_vars = sample_vinegar(&mut OsRandomness, params).unwrap();
+            let x = OsRandomness.vinegar(params.k * params.oil_vars()).unwrap();
+            let (a_matrix, y_prime) = compute_lin_system_components(&vinegar_vars, &matrices, params).unwrap();
+            assert_eq!((a_matrix.num_rows(), a_matrix.num_cols()), (params.m, params.k * params.oil_vars()));
+
+            // s_i = (v_i + O x_i, x_i) must satisfy P*(s) = A x + y' for any x.
+            let mut s = Vec::with_capacity(params.k * params.n);
+            for (v_i, x_i) in vinegar_vars.chunks(params.vinegar_vars()).zip(x.chunks(params.oil_vars())) {
+                let o_x = matrix_vec_mul(&o_matrix, &x_i.to_vec()).unwrap();
+                s.extend(v_i.iter().zip(&o_x).map(|(&v, &ox)| gf16_add(v, ox)));
+                s.extend_from_slice(x_i);
+            }
+            let a_x = matrix_vec_mul(&a_matrix, &x).unwrap();
+            let expected: GFVector = a_x.iter().zip(&y_prime).map(|(&ax, &y)| gf16_add(ax, y)).collect();
+            assert_eq!(compute_whipped_p_star(&s, &p1, &p2, &p3, params).unwrap(), expected);
+        }
+    }
+
+    /// Replays fixed salts and vinegar vectors, one per signing attempt. The solution offset
+    /// is always zero, so each attempt takes the solver's particular solution.
+    struct ScriptedRandomness {
+        salts: Vec<Vec<u8>>,
+        vinegars: Vec<GFVector>,
+        salts_drawn: usize,
+        vinegars_drawn: usize,
+    }
+
+    impl SigningRandomness for ScriptedRandomness {
+        fn salt(&mut self, _len: usize) -> Result<Vec<u8>, MayoError> {
+            let salt = self.salts.get(self.salts_drawn).cloned().ok_or(MayoError::RngFailure("Scripted salts exhausted"))?;
+            self.salts_drawn += 1;
+            Ok(salt)
+        }
+
+        fn vinegar(&mut self, _count: usize) -> Result<GFVector, MayoError> {
+            let vinegar = self.vinegars.get(self.vinegars_drawn).cloned().ok_or(MayoError::RngFailure("Scripted vinegar exhausted"))?;
+            self.vinegars_drawn += 1;
+            Ok(vinegar)
+        }
+
+        fn solution_offset(&mut self, count: usize) -> Result<GFVector, MayoError> {
+            Ok(vec![GFElement(0); count])
+        }
+    }
+
+    /// The k vinegar vectors v_0 = e_0 and v_1 = ... = v_{k-1} = 0, as one flat draw.
+    fn vinegar_e_0(params: &MayoVariantParams) -> GFVector {
+        let mut vinegar = vec![GFElement(0); params.k * params.vinegar_vars()];
+        vinegar[0] = GFElement(1);
+        vinegar
+    }
+
+    /// The m x k matrix with columns t, E t, ..., E^{k-1} t: the targets a key from
+    /// `esk_solvable_for_target` can reach with `vinegar_e_0`.
+    fn reachable_targets(target: &GFVector, params: &MayoVariantParams) -> GFMatrix {
+        let mut columns = vec![target.clone()];
+        for _ in 1..params.k {
+            let mut next = columns.last().unwrap().clone();
+            emulsify_rows(&mut next, 1, params.f_tail().unwrap());
+            columns.push(next);
+        }
+        GFMatrix::from_vectors((0..params.m).map(|a| columns.iter().map(|c| c[a]).collect()).collect())
+    }
+
+    /// Returns an esk with P1 = 0 whose L matrices make `target` reachable with `vinegar_e_0`.
+    /// With those vinegar vectors y' = 0, only v_0^T L_a = row 0 of L_a is nonzero, and
+    /// column b*o + c of A is E^{k-1-b} applied to column c of that row. Putting `target` in
+    /// L_a[0][0] (rest zero) makes x_{k-1} = e_0 a solution, while any target outside the
+    /// span of `reachable_targets` is inconsistent.
+    fn esk_solvable_for_target(target: &GFVector, params_enum: &MayoParams) -> EskTypeForTest {
+        esk_solvable_for_target_from(&create_dummy_esk(params_enum), target, params_enum)
+    }
+
+    /// Same as `esk_solvable_for_target`, keeping the seed and O of `esk`.
+    fn esk_solvable_for_target_from(esk: &EskTypeForTest, target: &GFVector, params_enum: &MayoParams) -> EskTypeForTest {
+        esk_with_l_row_0(esk, std::slice::from_ref(target), params_enum)
+    }
+
+    /// Replaces P1 in `esk` with zeros and L with matrices whose only nonzero row is row 0,
+    /// holding `columns[c]` (one element per L_a) in column c.
+    fn esk_with_l_row_0(esk: &EskTypeForTest, columns: &[GFVector], params_enum: &MayoParams) -> EskTypeForTest {
+        let params = params_enum.variant();
+        let l_elements_per_mat = params.vinegar_vars() * params.oil_vars();
+        let mut l_elements = vec![GFElement(0); params.m * l_elements_per_mat];
+        for (c, column) in columns.iter().enumerate() {
+            for (a, &t_a) in column.iter().enumerate() {
+                l_elements[a * l_elements_per_mat + c] = t_a;
+            }
+        }
+        let p1_start = params.sk_seed_bytes + params.o_bytes;
+        let mut crafted_esk_bytes = esk.0[..p1_start].to_vec();
+        crafted_esk_bytes.extend_from_slice(&vec![0u8; params.p1_bytes]);
+        crafted_esk_bytes.extend_from_slice(&encode_gf_elements(&l_elements));
+        EskTypeForTest(crafted_esk_bytes)
+    }
+
+    /// Builds the epk of a key with P1 = 0 whose O and L are those of `esk`:
+    /// P2_i = L_i - (P1_i + P1_i^T) O = L_i and P3_i = Upper(-O^T (P1_i O + P2_i)) = Upper(O^T L_i).
+    /// This is the key `esk` belongs to when its P1 is zero, as for `esk_with_l_row_0`.
+    fn epk_for_esk_with_zero_p1(esk: &EskTypeForTest, params_enum: &MayoParams) -> ExpandedPublicKey {
+        let params = params_enum.variant();
+        let l_start = params.sk_seed_bytes + params.o_bytes + params.p1_bytes;
+        let l_matrices = decode_l_matrices(&esk.0[l_start..], params).unwrap();
+        let zero_p1 = vec![GFMatrix::zero(params.vinegar_vars(), params.vinegar_vars()); params.m];
+        let p3_matrices = crate::keygen::compute_p3_matrices(&esk.decode_o(params_enum).unwrap(), &zero_p1, &l_matrices).unwrap();
+
+        let mut epk_bytes = vec![0u8; params.p1_bytes];
+        epk_bytes.extend_from_slice(&esk.0[l_start..]);
+        epk_bytes.extend_from_slice(&crate::codec::encode_p3_matrices(&p3_matrices, params).unwrap());
+        ExpandedPublicKey(epk_bytes)
+    }
+
+    #[test]
+    fn test_scripted_randomness_retries_after_inconsistent_attempt() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let num_vinegar_vars = params.vinegar_vars();
+        let message = Message(b"scripted signing attempts".to_vec());
+        let salt_inconsistent = vec![0x11; params.salt_bytes];
+        let salt_consistent = vec![0x22; params.salt_bytes];
+
+        let m_digest = message_digest(&message, &params_enum);
+        let target_for = |salt: &[u8]| {
+            let t_bytes = shake256_derive_target_t(&m_digest, &Salt(salt.to_vec()), &params_enum);
+            decode_target_vector(&t_bytes, params.m).unwrap()
+        };
+        let t_consistent = target_for(&salt_consistent);
+        let t_inconsistent = target_for(&salt_inconsistent);
+
+        // The crafted key only solves the second target; the first must be out of reach.
+        assert_eq!(solve_linear_system(&reachable_targets(&t_consistent, params), &t_inconsistent), Ok(None),
+                   "Scripted first attempt must be inconsistent");
+        let crafted_esk = esk_solvable_for_target(&t_consistent, &params_enum);
+
+        let mut rng = ScriptedRandomness {
+            salts: vec![salt_inconsistent, salt_consistent.clone()],
+            vinegars: vec![vinegar_e_0(params); 2],
+            salts_drawn: 0,
+            vinegars_drawn: 0,
+        };
+
+        let sig = sign_message_with_randomness(&crafted_esk, &message, &SignatureOptions::default(), &mut rng, &params_enum)
+            .expect("Second scripted attempt should produce a signature");
+        assert_eq!(rng.salts_drawn, 2);
+        assert_eq!(rng.vinegars_drawn, 2);
+
+        let s_bytes_len = params.s_bytes();
+        assert_eq!(sig.0.len(), s_bytes_len + params.salt_bytes);
+        assert_eq!(&sig.0[s_bytes_len..], salt_consistent.as_slice());
+        let s = decode_s_vector(&sig.0[..s_bytes_len], params).unwrap();
+        // x_{k-1} = e_0 and every other x_i = 0, so s_0 = (e_0, 0), s_{k-1} = (O e_0, e_0)
+        // picks up column 0 of O, and the vectors in between are zero.
+        let o_matrix = crafted_esk.decode_o(&params_enum).unwrap();
+        let mut expected_s = vec![GFElement(0); params.k * params.n];
+        expected_s[0] = GFElement(1);
+        let last = (params.k - 1) * params.n;
+        for r in 0..num_vinegar_vars {
+            expected_s[last + r] = o_matrix.get_unsafe(r, 0);
+        }
+        expected_s[last + num_vinegar_vars] = GFElement(1);
+        assert_eq!(s, expected_s);
+        let epk = epk_for_esk_with_zero_p1(&crafted_esk, &params_enum);
+        assert_eq!(crate::verify::verify_signature(&epk, &message, &sig, &params_enum), Ok(true));
+    }
+
+    #[test]
+    fn test_inconsistent_attempts_exhaust_retries() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let message = Message(b"never solvable".to_vec());
+        let salt_reachable = vec![0x22; params.salt_bytes];
+        let salt_unreachable = vec![0x11; params.salt_bytes];
+
+        let m_digest = message_digest(&message, &params_enum);
+        let target_for = |salt: &[u8]| {
+            let t_bytes = shake256_derive_target_t(&m_digest, &Salt(salt.to_vec()), &params_enum);
+            decode_target_vector(&t_bytes, params.m).unwrap()
+        };
+        let t_reachable = target_for(&salt_reachable);
+        assert_eq!(solve_linear_system(&reachable_targets(&t_reachable, params), &target_for(&salt_unreachable)), Ok(None));
+        let crafted_esk = esk_solvable_for_target(&t_reachable, &params_enum);
+
+        let mut rng = ScriptedRandomness {
+            salts: vec![salt_unreachable; MAX_SIGN_RETRIES],
+            vinegars: vec![vinegar_e_0(params); MAX_SIGN_RETRIES],
+            salts_drawn: 0,
+            vinegars_drawn: 0,
+        };
+        let result = sign_message_with_randomness(&crafted_esk, &message, &SignatureOptions::default(), &mut rng, &params_enum);
+        assert_eq!(result, Err(MayoError::SignRetriesExhausted {
+            attempts: MAX_SIGN_RETRIES,
+            no_solution: MAX_SIGN_RETRIES,
+            solver_errors: 0,
+            last_solver_error: None,
+        }));
+    }
+
+    #[test]
+    fn test_salt_pool_advances_through_supplied_salts() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let message = Message(b"salt pool signing".to_vec());
+        let salts: Vec<Salt> = [0x11u8, 0x33, 0x22].iter().map(|&b| Salt(vec![b; params.salt_bytes])).collect();
+
+        let m_digest = message_digest(&message, &params_enum);
+        let target_for = |salt: &Salt| decode_target_vector(&shake256_derive_target_t(&m_digest, salt, &params_enum), params.m).unwrap();
+        let t_good = target_for(&salts[2]);
+        let reachable = reachable_targets(&t_good, params);
+        for bad_salt in &salts[..2] {
+            assert_eq!(solve_linear_system(&reachable, &target_for(bad_salt)), Ok(None), "Pooled bad salts must be inconsistent");
+        }
+        let crafted_esk = esk_solvable_for_target(&t_good, &params_enum);
+
+        let fallback = ScriptedRandomness { salts: Vec::new(), vinegars: vec![vinegar_e_0(params); 3], salts_drawn: 0, vinegars_drawn: 0 };
+        let mut pool = SaltPool::new(&salts, fallback);
+
+        let sig = sign_message_with_randomness(&crafted_esk, &message, &SignatureOptions::default(), &mut pool, &params_enum)
+            .expect("Third pooled salt should produce a signature");
+        assert_eq!(pool.salts_used(), 3);
+        assert_eq!(pool.fallback.salts_drawn, 0);
+        assert_eq!(&sig.0[params.s_bytes()..], salts[2].0.as_slice());
+
+        // Once the pool is exhausted, salts come from the fallback.
+        let mut exhausted = SaltPool::new(&salts[..1], ScriptedRandomness { salts: vec![vec![0x44; 4]], vinegars: Vec::new(), salts_drawn: 0, vinegars_drawn: 0 });
+        assert_eq!(exhausted.salt(params.salt_bytes).unwrap(), salts[0].0);
+        assert_eq!(exhausted.salt(4).unwrap(), vec![0x44; 4]);
+        assert_eq!(exhausted.salts_used(), 1);
+    }
+
+    #[test]
+    fn test_concurrent_signing_with_shared_esk() {
+        use std::sync::Arc;
+        use crate::verify::verify_signature;
+
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<ExpandedSecretKey>();
+        assert_send_sync::<ExpandedPublicKey>();
+        assert_send_sync::<SigningMatrices>();
+
+        // A genuine key pair; every thread signs with `sign_message` and its OS randomness.
+        let params_enum = MayoParams::mayo1();
+        let num_threads = 4;
+        let (csk, cpk) = compact_key_gen(&params_enum).unwrap();
+        let esk = Arc::new(expand_sk(&csk, &params_enum).unwrap());
+        let epk = Arc::new(crate::keygen::expand_pk(&cpk, &params_enum).unwrap());
+
+        let handles: Vec<_> = (0..num_threads).map(|j| {
+            let (esk, epk) = (Arc::clone(&esk), Arc::clone(&epk));
+            std::thread::spawn(move || {
+                let message = Message(format!("thread {}", j).into_bytes());
+                let sig = sign_message(&esk, &message, &params_enum).unwrap();
+                verify_signature(&epk, &message, &sig, &params_enum).unwrap()
+            })
+        }).collect();
+        for handle in handles {
+            assert!(handle.join().unwrap(), "A signature produced on a worker thread failed to verify");
+        }
+    }
+
+    #[test]
+    fn test_sample_vinegar_and_salt_lengths() {
+        for params_enum in MayoParams::all_variants() {
+            let params = params_enum.variant();
+            let vinegar_vars = sample_vinegar(&mut OsRandomness, params).unwrap();
+            assert_eq!(vinegar_vars.len(), params.k * (params.n - params.o));
+            assert!(vinegar_vars.iter().all(|v| v.0 < 16));
+            assert_eq!(sample_salt(&mut OsRandomness, params).unwrap().0.len(), params.salt_bytes);
+        }
+
+        let params_enum = MayoParams::mayo1();
+        let mut short_vinegar = ScriptedRandomness { salts: vec![], vinegars: vec![vec![GFElement(1); 3]], salts_drawn: 0, vinegars_drawn: 0 };
+        assert_eq!(sample_vinegar(&mut short_vinegar, params_enum.variant()),
+                   Err(MayoError::RngFailure("Randomness source returned vinegar variables of incorrect length")));
+    }
+
+    #[test]
+    fn test_derive_target_length_and_binding() {
+        for params_enum in MayoParams::all_variants() {
+            let params = params_enum.variant();
+            let m_digest = message_digest(&Message(b"target".to_vec()), &params_enum);
+            let salt = Salt(vec![0x42; params.salt_bytes]);
+            let t_vector = derive_target(&m_digest, &salt, None, &params_enum).unwrap();
+            assert_eq!(t_vector.len(), params.m);
+            let t_bytes = shake256_derive_target_t(&m_digest, &salt, &params_enum);
+            let nibbles = crate::codec::unpack_nibbles(&t_bytes, params.m).unwrap();
+            assert_eq!(t_vector, nibbles.into_iter().map(GFElement).collect::<GFVector>());
+            assert_ne!(derive_target(&m_digest, &salt, Some(b"fingerprint"), &params_enum).unwrap(), t_vector);
+        }
+    }
+
+    #[cfg(feature = "counting")]
+    #[test]
+    fn test_sign_gf_op_count_is_stable() {
+        use crate::gf::{gf_op_count, reset_gf_op_count};
+
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let message = Message(b"counted signing".to_vec());
+        let salt = vec![0x33; params.salt_bytes];
+        let m_digest = message_digest(&message, &params_enum);
+        let t_bytes = shake256_derive_target_t(&m_digest, &Salt(salt.clone()), &params_enum);
+        let esk = esk_solvable_for_target(&decode_target_vector(&t_bytes, params.m).unwrap(), &params_enum);
+        let e_0 = vinegar_e_0(params);
+
+        // One scripted attempt is deterministic, so the field-operation count must repeat exactly.
+        let mut counts = Vec::new();
+        for _ in 0..2 {
+            let mut rng = ScriptedRandomness { salts: vec![salt.clone()], vinegars: vec![e_0.clone()], salts_drawn: 0, vinegars_drawn: 0 };
+            reset_gf_op_count();
+            sign_message_with_randomness(&esk, &message, &SignatureOptions::default(), &mut rng, &params_enum).unwrap();
+            counts.push(gf_op_count());
+        }
+        assert!(counts[0] > 0);
+        assert_eq!(counts[0], counts[1]);
+    }
+
+    #[test]
+    fn test_scripted_randomness_wrong_salt_length_rejected() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let esk = create_dummy_esk(&params_enum);
+        let mut rng = ScriptedRandomness {
+            salts: vec![vec![0u8; params.salt_bytes - 1]],
+            vinegars: vec![vec![GFElement(0); params.k * params.vinegar_vars()]],
+            salts_drawn: 0,
+            vinegars_drawn: 0,
+        };
+        let result = sign_message_with_randomness(&esk, &Message(b"short salt".to_vec()), &SignatureOptions::default(), &mut rng, &params_enum);
+        assert_eq!(result, Err(MayoError::RngFailure("Randomness source returned salt of incorrect length")));
+    }
+
+    #[test]
+    fn test_embedded_salt_derives_solved_target() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let num_vinegar_vars = params.vinegar_vars();
+        let message = Message(b"salt consistency".to_vec());
+        let salt = vec![0x5C; params.salt_bytes];
+        let m_digest = message_digest(&message, &params_enum);
+        let t_bytes = shake256_derive_target_t(&m_digest, &Salt(salt.clone()), &params_enum);
+        let esk = esk_solvable_for_target(&decode_target_vector(&t_bytes, params.m).unwrap(), &params_enum);
+
+        let mut rng = ScriptedRandomness { salts: vec![salt], vinegars: vec![vinegar_e_0(params)], salts_drawn: 0, vinegars_drawn: 0 };
+        let sig = sign_message_with_randomness(&esk, &message, &SignatureOptions::default(), &mut rng, &params_enum).unwrap();
+
+        // Re-derive t from the salt embedded in the signature and check the accepted
+        // solution satisfies A x + y' = t for that t.
+        let s_bytes_len = params.s_bytes();
+        let embedded_salt = Salt(sig.0[s_bytes_len..].to_vec());
+        let t_vector = decode_target_vector(&shake256_derive_target_t(&m_digest, &embedded_salt, &params_enum), params.m).unwrap();
+        let s = decode_s_vector(&sig.0[..s_bytes_len], params).unwrap();
+        // s_i = (v_i + O x_i, x_i), so the sampled vinegar variables are v_i = s_iV - O x_i.
+        let o_matrix = esk.decode_o(&params_enum).unwrap();
+        let mut vinegar_vars = Vec::with_capacity(params.k * num_vinegar_vars);
+        let mut oil_vars = Vec::with_capacity(params.k * params.oil_vars());
+        for s_i in s.chunks(params.n) {
+            let (s_vinegar, x_i) = s_i.split_at(num_vinegar_vars);
+            let o_x = matrix_vec_mul(&o_matrix, &x_i.to_vec()).unwrap();
+            vinegar_vars.extend(matrix_sub_vectors_gfvector(&s_vinegar.to_vec(), &o_x).unwrap());
+            oil_vars.extend_from_slice(x_i);
+        }
+
+        let p1_start = params.sk_seed_bytes + params.o_bytes;
+        let p1_end = p1_start + params.p1_bytes;
+        let matrices = SigningMatrices::decode(&esk.0[p1_start..p1_end], &esk.0[p1_end..], params).unwrap();
+        let (a_matrix, y_prime) = compute_lin_system_components(&vinegar_vars, &matrices, params).unwrap();
+        let a_x = matrix_vec_mul(&a_matrix, &oil_vars).unwrap();
+        assert_eq!(matrix_sub_vectors_gfvector(&t_vector, &y_prime).unwrap(), a_x);
+    }
+
+    /// Signature bytes (s || salt) of `sign_message_deterministic` for the seeded key and
+    /// message of `test_seeded_key_gives_known_deterministic_signature`.
+    const KNOWN_MAYO1_SIGNATURE_HEX: &str = "e3259844d9e62c425c28c2452d64544b999d390a8562a9ebe3ff12b01f406d52888d83be6445626e85357c68343e363a19102694e823c26f4168ccdbd9fa8fbab3a85f2efdedf11b5c4573b6ce735d054d24b79259c474f6da88676a13e884050783c224461bc6f75fe436fa9ec5cb90e26e6c229aa9580f5083c3d2f19abe109c58d13b7ca5367a73d322b8287920f79babb7e96609e9b87fb5bf0728c7340f427b38740b3f2bbf46598a3a42d7c6420739a48e1b7809f60134b3f4ebd76959409192144a4cf8f00c5bd8e53c18e91dd8144186e1ede8378c4a6b7736511015115e01da62bec20fcea95340cafe158c7f95b61ca2e1735dc224e73a02ff482da71177c347678748f984fee38686e7c8cefe24b430c4c4af977ebfec052d6d06f392346da715c60def2f033d2007e00c9e36dcbf126d14a2af9ddb9b8def8f8c63";
+
+    #[test]
+    fn test_zero_digest_bytes_rejected_before_signing() {
+        let mut variant = *MayoParams::mayo1().variant();
+        variant.digest_bytes = 0;
+        let params_enum = MayoParams::MAYO1(variant);
+        // The esk is empty, so any work past parameter validation would fail differently.
+        let result = sign_message(&EskTypeForTest(vec![]), &Message(b"misconfigured".to_vec()), &params_enum);
+        assert_eq!(result, Err(MayoError::InvalidParameters("Invalid parameters: digest_bytes must be greater than zero")));
+    }
+
+    #[test]
+    fn test_seeded_key_gives_known_deterministic_signature() {
+        use crate::keygen::{compact_key_gen_from_seed, expand_pk};
+
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let seed: Vec<u8> = (0..params.sk_seed_bytes as u8).collect();
+        let (csk, cpk) = compact_key_gen_from_seed(&seed, &params_enum).unwrap();
+        let esk = expand_sk(&csk, &params_enum).unwrap();
+        let message = Message(b"known answer".to_vec());
+        let sig = sign_message_deterministic(&esk, &message, &params_enum).unwrap();
+
+        let sig_hex: String = sig.0.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(sig_hex, KNOWN_MAYO1_SIGNATURE_HEX);
+        let epk = expand_pk(&cpk, &params_enum).unwrap();
+        assert_eq!(crate::verify::verify_signature(&epk, &message, &sig, &params_enum), Ok(true));
     }
-    
-    // TODO: More detailed tests once compute_Y_A_yprime_and_s_components is implemented.
-    // These tests would involve:
-    // 1. Mocking or providing a test implementation for compute_Y_A_yprime_and_s_components.
-    // 2. Scenario 1: Test solver integration (inconsistent system):
-    //    - Craft dummy_A, dummy_y_prime, and t_vector such that Ax = t - y_prime is inconsistent.
-    //    - Assert that sign_message (possibly by controlling MAX_SIGN_RETRIES for the test)
-    //      returns Err("MAYO.Sign failed after maximum retries").
-    // 3. Scenario 2: Test solver integration (consistent system leading to signature):
-    //    - Craft dummy_A, dummy_y_prime, and t_vector such that Ax = t - y_prime is consistent
-    //      and solve_linear_system returns Ok(Some(dummy_x_solution)).
-    //    - Assert that sign_message returns Ok(Signature(...)).
-    //    - Check the structure of the returned Signature:
-    //        - Its length should be params.bytes_for_gf16_elements(params.n) + params.salt_bytes.
-    //        - The salt part of the signature should match the dummy salt used in the test setup.
 
     // TODO: Implement Known Answer Tests (KATs) for sign_message 
     // once compute_Y_A_yprime_and_s_components is fully implemented.
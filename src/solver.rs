@@ -1,54 +1,122 @@
 //! Implements a linear system solver over GF(16) using Gaussian elimination.
 
 use crate::types::{GFElement, GFMatrix, GFVector};
-use crate::gf::{gf16_mul, gf16_pow, gf16_sub}; // gf16_sub is same as gf16_add; removed gf16_add as unused
+use crate::gf::{gf16_mul, gf16_sub, GF16_INV}; // gf16_sub is same as gf16_add; removed gf16_add as unused
+use crate::matrix::{gfvector_axpy, gfvector_cmov, matrix_rank};
+use crate::error::MayoError;
 // Note: GFMatrix type is from crate::types, its methods are in crate::matrix
 // We'll use the struct directly and its public fields (data, rows, cols)
-// and helper methods like `get_unsafe`, `set_val` defined in `crate::matrix`.
+// and helper methods like `get_unsafe`, `set_val` and `m[(r, c)]` indexing defined in `crate::matrix`.
 
-/// Computes the multiplicative inverse of an element in GF(16).
-/// GF(16)* is a cyclic group of order 15. The inverse of `a` is `a^(15-1) = a^14`.
-pub fn gf16_inv(element: GFElement) -> Result<GFElement, &'static str> {
+/// Computes the multiplicative inverse of an element in GF(16) by lookup in `GF16_INV`.
+/// Equivalent to `a^14`, since GF(16)* is a cyclic group of order 15.
+pub fn gf16_inv(element: GFElement) -> Result<GFElement, MayoError> {
     if element.0 == 0 {
-        Err("Cannot invert zero element")
+        Err(MayoError::DivisionByZero("Cannot invert zero element"))
     } else {
-        // For GF(q), inverse of a is a^(q-2). Here q=16, so a^14.
-        Ok(gf16_pow(element, 14))
+        Ok(GFElement(GF16_INV[(element.0 & 0x0F) as usize]))
     }
 }
 
-/// Solves a linear system Ax = y over GF(16) using Gaussian elimination.
-///
-/// # Arguments
-/// * `a_matrix` - The M x N coefficient matrix A.
-/// * `y_vector` - The M x 1 constant vector y.
-///
-/// # Returns
-/// * `Ok(Some(x_vector))` - If a solution x (N x 1 vector) is found. If multiple solutions
-///   exist (due to free variables), one particular solution is returned (typically by
-///   setting free variables to 0).
-/// * `Ok(None)` - If the system is inconsistent (no solution).
-/// * `Err(&'static str)` - For dimension mismatches or other errors during solving.
-pub fn solve_linear_system(a_matrix: &GFMatrix, y_vector: &GFVector) -> Result<Option<GFVector>, &'static str> {
+/// Result of solving a linear system with `solve_outcome`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SolveOutcome {
+    /// A has full column rank and the system has exactly one solution.
+    Unique(GFVector),
+    /// The system is consistent but A is rank-deficient. Holds the particular solution
+    /// (free variables set to zero) and a basis of the kernel of A, one vector per free variable.
+    Underdetermined(GFVector, Vec<GFVector>),
+    /// The system has no solution.
+    Inconsistent,
+}
+
+/// Augmented matrix [A|y] in reduced row echelon form.
+struct ReducedSystem {
+    aug: GFMatrix,
+    /// Pivot column of each of the first `rank` rows.
+    pivot_cols: Vec<usize>,
+    num_equations: usize,
+    num_variables: usize,
+}
+
+impl ReducedSystem {
+    fn rank(&self) -> usize {
+        self.pivot_cols.len()
+    }
+
+    /// A row [0 0 ... 0 | c] with c != 0 below the pivot rows means no solution.
+    fn is_consistent(&self) -> bool {
+        (self.rank()..self.num_equations).all(|r| self.aug[(r, self.num_variables)].0 == 0)
+    }
+
+    /// Solution with every free variable set to 0.
+    ///
+    /// Every column runs the same back-substitution (free columns on row 0) and
+    /// `gfvector_cmov` keeps the result only for pivot columns, so the work done does not
+    /// depend on which variables are free.
+    #[allow(clippy::needless_range_loop)]
+    fn particular_solution(&self) -> GFVector {
+        let mut solution = vec![GFElement(0); self.num_variables];
+        if self.num_equations == 0 {
+            return solution;
+        }
+        let mut pivot_row_of = vec![None; self.num_variables];
+        for (r, &p_col) in self.pivot_cols.iter().enumerate() {
+            pivot_row_of[p_col] = Some(r);
+        }
+        let zero = [GFElement(0)];
+        // Iterate from the last column leftwards
+        for c in (0..self.num_variables).rev() {
+            let r = pivot_row_of[c].unwrap_or(0);
+            let mut val = self.aug[(r, self.num_variables)]; // y_r'
+            for c_idx in (c + 1)..self.num_variables {
+                let term = gf16_mul(self.aug[(r, c_idx)], solution[c_idx]);
+                val = gf16_sub(val, term);
+            }
+            // aug(r, c) is 1 for a pivot column; a free column keeps 0
+            solution[c] = gfvector_cmov(pivot_row_of[c].is_some(), &[val], &zero)[0];
+        }
+        solution
+    }
+
+    /// One kernel vector per free column f: x_f = 1, other free variables 0,
+    /// and each pivot variable set to -aug[r][f] (= aug[r][f] in characteristic 2).
+    fn kernel_basis(&self) -> Vec<GFVector> {
+        let free_cols = (0..self.num_variables).filter(|c| !self.pivot_cols.contains(c));
+        free_cols.map(|f| {
+            let mut basis_vec = vec![GFElement(0); self.num_variables];
+            basis_vec[f] = GFElement(1);
+            for (r, &p_col) in self.pivot_cols.iter().enumerate() {
+                basis_vec[p_col] = gf16_sub(GFElement(0), self.aug[(r, f)]);
+            }
+            basis_vec
+        }).collect()
+    }
+}
+
+/// Builds the augmented matrix [A|y] and brings it to reduced row echelon form.
+fn reduce_system(a_matrix: &GFMatrix, y_vector: &GFVector) -> Result<ReducedSystem, MayoError> {
     let num_equations = a_matrix.num_rows();
     let num_variables = a_matrix.num_cols();
 
     if num_equations != y_vector.len() {
-        return Err("Matrix A rows must match y_vector length");
+        return Err(MayoError::DimensionMismatch("Matrix A rows must match y_vector length"));
     }
 
     // 1. Construct augmented matrix [A|y]
     let mut aug_matrix_data = Vec::with_capacity(num_equations * (num_variables + 1));
     for r in 0..num_equations {
         for c in 0..num_variables {
-            aug_matrix_data.push(a_matrix.get_unsafe(r, c));
+            aug_matrix_data.push(a_matrix[(r, c)]);
         }
         aug_matrix_data.push(y_vector[r]);
     }
     // Directly using GFMatrix::new_with_data which is in matrix.rs impl block
     let mut aug = GFMatrix::new_with_data(num_equations, num_variables + 1, aug_matrix_data);
 
-    // 2. Forward Elimination (to Row Echelon Form)
+    // 2. Forward Elimination (to reduced Row Echelon Form), one row slice at a time
+    let width = num_variables + 1;
+    let mut pivot_cols = Vec::new();
     let mut pivot_row = 0;
     for pivot_col in 0..num_variables {
         if pivot_row >= num_equations {
@@ -56,88 +124,110 @@ pub fn solve_linear_system(a_matrix: &GFMatrix, y_vector: &GFVector) -> Result<O
         }
 
         // Find pivot for this column
-        let mut i = pivot_row;
-        while i < num_equations && aug.get_unsafe(i, pivot_col).0 == 0 {
-            i += 1;
-        }
+        let Some(i) = (pivot_row..num_equations).find(|&r| aug.data[r * width + pivot_col].0 != 0) else {
+            // No non-zero pivot in this column (below current pivot_row): it corresponds
+            // to a free variable. Move to the next column.
+            continue;
+        };
 
-        if i < num_equations { // Found a non-zero pivot
-            // Swap rows if necessary
-            if i != pivot_row {
-                for k in pivot_col..(num_variables + 1) {
-                    let temp = aug.get_unsafe(pivot_row, k);
-                    aug.set_val(pivot_row, k, aug.get_unsafe(i, k));
-                    aug.set_val(i, k, temp);
-                }
-            }
+        // Swap rows if necessary
+        if i != pivot_row {
+            let (upper, lower) = aug.data.split_at_mut(i * width);
+            upper[pivot_row * width..(pivot_row + 1) * width].swap_with_slice(&mut lower[..width]);
+        }
 
-            // Normalize pivot row (make pivot element 1)
-            let pivot_val = aug.get_unsafe(pivot_row, pivot_col);
-            let inv_pivot_val = gf16_inv(pivot_val)?; // Can fail if pivot_val is 0, but loop above should prevent
-            for k in pivot_col..(num_variables + 1) {
-                aug.set_val(pivot_row, k, gf16_mul(aug.get_unsafe(pivot_row, k), inv_pivot_val));
-            }
+        // Normalize pivot row (make pivot element 1)
+        let pivot_row_slice = &mut aug.data[pivot_row * width..(pivot_row + 1) * width];
+        let inv_pivot_val = gf16_inv(pivot_row_slice[pivot_col])?; // Nonzero by the search above
+        for val in &mut pivot_row_slice[pivot_col..] {
+            *val = gf16_mul(*val, inv_pivot_val);
+        }
 
-            // Eliminate other rows
-            for r_idx in 0..num_equations {
-                if r_idx != pivot_row {
-                    let factor = aug.get_unsafe(r_idx, pivot_col);
-                    if factor.0 != 0 { // Only if there's something to eliminate
-                        for k_idx in pivot_col..(num_variables + 1) {
-                            let term = gf16_mul(factor, aug.get_unsafe(pivot_row, k_idx));
-                            let current_val = aug.get_unsafe(r_idx, k_idx);
-                            aug.set_val(r_idx, k_idx, gf16_sub(current_val, term)); // current - factor * pivot_row_val
-                        }
-                    }
-                }
-            }
-            pivot_row += 1;
+        // Eliminate other rows: row_r -= factor * pivot_row (subtraction is addition in GF(16))
+        let (before, rest) = aug.data.split_at_mut(pivot_row * width);
+        let (pivot_slice, after) = rest.split_at_mut(width);
+        let pivot_slice = &pivot_slice[pivot_col..];
+        for row in before.chunks_exact_mut(width).chain(after.chunks_exact_mut(width)) {
+            let factor = row[pivot_col];
+            gfvector_axpy(&mut row[pivot_col..], factor, pivot_slice);
         }
-        // If no non-zero pivot found in this column (below current pivot_row),
-        // this column corresponds to a free variable. We move to the next column.
+        pivot_cols.push(pivot_col);
+        pivot_row += 1;
     }
-    let rank = pivot_row; // Number of non-zero rows after REF
+
+    Ok(ReducedSystem { aug, pivot_cols, num_equations, num_variables })
+}
+
+/// Solves a linear system Ax = y over GF(16) using Gaussian elimination.
+///
+/// # Arguments
+/// * `a_matrix` - The M x N coefficient matrix A.
+/// * `y_vector` - The M x 1 constant vector y.
+///
+/// # Returns
+/// * `Ok(Some(x_vector))` - If a solution x (N x 1 vector) is found. If multiple solutions
+///   exist (due to free variables), one particular solution is returned, with every
+///   free variable set to 0.
+/// * `Ok(None)` - If the system is inconsistent (no solution).
+/// * `Err(MayoError::DimensionMismatch)` - For dimension mismatches or other errors during solving.
+///
+/// # Pivot rule
+/// Columns are scanned left to right; the pivot for a column is the lowest-index remaining
+/// row with a nonzero entry there, swapped into place and scaled to 1. Columns without such
+/// a row are free. Together with free variables being 0, this fixes the returned solution
+/// for a given (A, y), which is part of this function's contract.
+pub fn solve_linear_system(a_matrix: &GFMatrix, y_vector: &GFVector) -> Result<Option<GFVector>, MayoError> {
+    let system = reduce_system(a_matrix, y_vector)?;
 
     // 3. Check for No Solution (inconsistency)
-    // If any row [0 0 ... 0 | c] has c != 0, then system is inconsistent.
-    for r_idx in rank..num_equations {
-        if aug.get_unsafe(r_idx, num_variables).0 != 0 {
-            return Ok(None); // Inconsistent system
-        }
+    if !system.is_consistent() {
+        return Ok(None); // Inconsistent system
     }
 
     // 4. Back-Substitution (and handle free variables by setting them to 0)
-    let mut solution = vec![GFElement(0); num_variables];
-    
-    // Iterate from the last pivot row upwards
-    // pivot_row here is `rank`
-    for r_idx_piv in (0..rank).rev() {
-        // Find the pivot column for this row. It's the first '1' from left.
-        let mut p_col = 0;
-        while p_col < num_variables && aug.get_unsafe(r_idx_piv, p_col).0 == 0 {
-            p_col += 1;
-        }
-        // If p_col == num_variables, it's a zero row, should have been handled by rank check.
-        // This implies aug.get_unsafe(r_idx_piv, p_col) is 1 (due to normalization).
+    Ok(Some(system.particular_solution()))
+}
 
-        let mut val = aug.get_unsafe(r_idx_piv, num_variables); // y_i'
-        for c_idx in (p_col + 1)..num_variables {
-            let term = gf16_mul(aug.get_unsafe(r_idx_piv, c_idx), solution[c_idx]);
-            val = gf16_sub(val, term);
-        }
-        solution[p_col] = val; // Since aug(r_idx_piv, p_col) is 1
+/// Solves Ax = y and reports whether the solution is unique, underdetermined, or nonexistent.
+/// Unlike `solve_linear_system`, a rank-deficient but consistent system also yields a
+/// basis of the kernel of A, so callers can tell it apart from a unique solution.
+pub fn solve_outcome(a_matrix: &GFMatrix, y_vector: &GFVector) -> Result<SolveOutcome, MayoError> {
+    let system = reduce_system(a_matrix, y_vector)?;
+    if !system.is_consistent() {
+        return Ok(SolveOutcome::Inconsistent);
     }
-    
-    // Free variables (if rank < num_variables) are already effectively set to 0
-    // because `solution` was initialized to zeros and corresponding x_j are not updated by back-substitution if they are free.
-    
-    Ok(Some(solution))
+    let particular = system.particular_solution();
+    if system.rank() == system.num_variables {
+        Ok(SolveOutcome::Unique(particular))
+    } else {
+        Ok(SolveOutcome::Underdetermined(particular, system.kernel_basis()))
+    }
+}
+
+/// Solves A x = y and returns every solution: a particular solution (free variables set
+/// to zero) and a basis of the kernel of A, one vector per free variable. The solutions
+/// are exactly `particular + sum_i c_i * basis[i]`; the basis is empty when the solution
+/// is unique. Returns `Ok(None)` if the system is inconsistent.
+pub fn solve_linear_system_with_kernel(a_matrix: &GFMatrix, y_vector: &GFVector) -> Result<Option<(GFVector, Vec<GFVector>)>, MayoError> {
+    let system = reduce_system(a_matrix, y_vector)?;
+    if !system.is_consistent() {
+        return Ok(None);
+    }
+    Ok(Some((system.particular_solution(), system.kernel_basis())))
+}
+
+/// Dimension of the solution space of A x = y when it is consistent: the number of
+/// variables minus rank(A). Zero means a consistent system has exactly one solution.
+/// Useful for studying how many free oil variables signing attempts leave.
+pub fn solution_space_dim(a_matrix: &GFMatrix) -> usize {
+    a_matrix.num_cols() - matrix_rank(a_matrix)
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::gf::gf16_pow;
     // Helper to create GFElement for tests
     fn gf(val: u8) -> GFElement { GFElement(val) }
     // Helper to create GFMatrix from Vec<Vec<GFElement>> for tests
@@ -150,7 +240,7 @@ mod tests {
 
     #[test]
     fn test_gf16_inv() {
-        assert_eq!(gf16_inv(gf(0)).err(), Some("Cannot invert zero element"));
+        assert_eq!(gf16_inv(gf(0)).err(), Some(MayoError::DivisionByZero("Cannot invert zero element")));
         assert_eq!(gf16_inv(gf(1)).unwrap().0, 1); // 1^-1 = 1
 
         // x^4 + x + 1 = 0 (0x13, or 0b10011)
@@ -300,6 +390,206 @@ mod tests {
         // Verify: (1*5)^(1*0)^(1*0) = 5. Correct.
     }
     
+    #[test]
+    fn test_solve_outcome_variants() {
+        // Unique: identity system
+        let a_id = mat(vec![vec![gf(1),gf(0)], vec![gf(0),gf(1)]]);
+        let y_id = vec_gf(vec![gf(5),gf(7)]);
+        assert_eq!(solve_outcome(&a_id, &y_id).unwrap(), SolveOutcome::Unique(vec![gf(5), gf(7)]));
+
+        // Inconsistent: x1 + x2 = 1 and x1 + x2 = 2
+        let a_incons = mat(vec![vec![gf(1), gf(1)], vec![gf(1), gf(1)]]);
+        let y_incons = vec_gf(vec![gf(1), gf(2)]);
+        assert_eq!(solve_outcome(&a_incons, &y_incons).unwrap(), SolveOutcome::Inconsistent);
+
+        // Underdetermined: x1 + x2 + x3 = 5
+        let a = mat(vec![vec![gf(1),gf(1),gf(1)]]);
+        let y = vec_gf(vec![gf(5)]);
+        match solve_outcome(&a, &y).unwrap() {
+            SolveOutcome::Underdetermined(x, basis) => {
+                assert_eq!(x, vec![gf(5), gf(0), gf(0)]);
+                assert_eq!(basis.len(), 2);
+                for b in &basis {
+                    assert_eq!(crate::matrix::matrix_vec_mul(&a, b).unwrap(), vec![gf(0)]);
+                }
+            }
+            other => panic!("Expected underdetermined outcome, got {:?}", other),
+        }
+    }
+
+    /// The original cell-by-cell elimination, kept as a reference for the slice-based solver.
+    #[allow(clippy::needless_range_loop)]
+    fn solve_linear_system_cellwise(a_matrix: &GFMatrix, y_vector: &GFVector) -> Option<GFVector> {
+        let num_equations = a_matrix.num_rows();
+        let num_variables = a_matrix.num_cols();
+        let mut aug = GFMatrix::zero(num_equations, num_variables + 1);
+        for r in 0..num_equations {
+            for c in 0..num_variables {
+                aug.set_val(r, c, a_matrix.get_unsafe(r, c));
+            }
+            aug.set_val(r, num_variables, y_vector[r]);
+        }
+        let mut pivot_row = 0;
+        for pivot_col in 0..num_variables {
+            if pivot_row >= num_equations {
+                break;
+            }
+            let mut i = pivot_row;
+            while i < num_equations && aug.get_unsafe(i, pivot_col).0 == 0 {
+                i += 1;
+            }
+            if i < num_equations {
+                for k in pivot_col..(num_variables + 1) {
+                    let temp = aug.get_unsafe(pivot_row, k);
+                    aug.set_val(pivot_row, k, aug.get_unsafe(i, k));
+                    aug.set_val(i, k, temp);
+                }
+                let inv_pivot_val = gf16_inv(aug.get_unsafe(pivot_row, pivot_col)).unwrap();
+                for k in pivot_col..(num_variables + 1) {
+                    aug.set_val(pivot_row, k, gf16_mul(aug.get_unsafe(pivot_row, k), inv_pivot_val));
+                }
+                for r_idx in 0..num_equations {
+                    if r_idx != pivot_row {
+                        let factor = aug.get_unsafe(r_idx, pivot_col);
+                        for k_idx in pivot_col..(num_variables + 1) {
+                            let term = gf16_mul(factor, aug.get_unsafe(pivot_row, k_idx));
+                            aug.set_val(r_idx, k_idx, gf16_sub(aug.get_unsafe(r_idx, k_idx), term));
+                        }
+                    }
+                }
+                pivot_row += 1;
+            }
+        }
+        if (pivot_row..num_equations).any(|r| aug.get_unsafe(r, num_variables).0 != 0) {
+            return None;
+        }
+        let mut solution = vec![GFElement(0); num_variables];
+        for r_idx_piv in (0..pivot_row).rev() {
+            let mut p_col = 0;
+            while aug.get_unsafe(r_idx_piv, p_col).0 == 0 {
+                p_col += 1;
+            }
+            let mut val = aug.get_unsafe(r_idx_piv, num_variables);
+            for c_idx in (p_col + 1)..num_variables {
+                val = gf16_sub(val, gf16_mul(aug.get_unsafe(r_idx_piv, c_idx), solution[c_idx]));
+            }
+            solution[p_col] = val;
+        }
+        Some(solution)
+    }
+
+    #[test]
+    fn test_slice_solver_matches_cellwise() {
+        let mut cases = vec![
+            (mat(vec![vec![gf(2), gf(1)], vec![gf(1), gf(2)]]), vec_gf(vec![gf(1), gf(1)])),
+            (mat(vec![vec![gf(1), gf(0)], vec![gf(0), gf(1)]]), vec_gf(vec![gf(5), gf(7)])),
+            (mat(vec![vec![gf(1), gf(1)], vec![gf(1), gf(1)]]), vec_gf(vec![gf(1), gf(2)])),
+            (mat(vec![vec![gf(1), gf(1)], vec![gf(2), gf(2)]]), vec_gf(vec![gf(1), gf(2)])),
+            (mat(vec![vec![gf(1),gf(0)],vec![gf(0),gf(1)],vec![gf(1),gf(1)]]), vec_gf(vec![gf(1),gf(2),gf(3)])),
+            (mat(vec![vec![gf(1),gf(1),gf(1)]]), vec_gf(vec![gf(5)])),
+        ];
+        let mut rng = rand_core::OsRng;
+        for (rows, cols) in [(4, 4), (8, 5), (5, 8), (16, 16)] {
+            let a = GFMatrix::random(rows, cols, &mut rng);
+            let y = GFMatrix::random(rows, 1, &mut rng).data;
+            cases.push((a, y));
+        }
+        for (a, y) in &cases {
+            assert_eq!(solve_linear_system(a, y).unwrap(), solve_linear_system_cellwise(a, y), "Mismatch for A = {:?}, y = {:?}", a, y);
+        }
+    }
+
+    #[test]
+    fn test_solve_zero_dimensions() {
+        // No equations, no unknowns: the empty solution is unique
+        let a0 = GFMatrix::zero(0, 0);
+        assert_eq!(solve_outcome(&a0, &vec![]).unwrap(), SolveOutcome::Unique(vec![]));
+
+        // No equations, three unknowns: every vector is a solution
+        let a_no_rows = GFMatrix::zero(0, 3);
+        match solve_outcome(&a_no_rows, &vec![]).unwrap() {
+            SolveOutcome::Underdetermined(x, basis) => {
+                assert_eq!(x, vec![gf(0); 3]);
+                assert_eq!(basis.len(), 3);
+            }
+            other => panic!("Expected underdetermined outcome, got {:?}", other),
+        }
+
+        // No unknowns: consistent only if y = 0
+        let a_no_cols = GFMatrix::zero(2, 0);
+        assert_eq!(solve_linear_system(&a_no_cols, &vec![gf(0), gf(0)]).unwrap(), Some(vec![]));
+        assert_eq!(solve_linear_system(&a_no_cols, &vec![gf(0), gf(3)]).unwrap(), None);
+    }
+
+    #[test]
+    fn test_solve_returns_pinned_particular_solution() {
+        // Column 0: both rows are nonzero, row 0 is taken. Column 1 pivots on row 1,
+        // column 2 is free and set to 0. By hand, RREF is [[1,0,4|D],[0,1,9|8]].
+        let a = mat(vec![
+            vec![gf(0x2), gf(0x1), gf(0x1)],
+            vec![gf(0x2), gf(0x3), gf(0x0)],
+        ]);
+        let y = vec_gf(vec![gf(0x1), gf(0x2)]);
+        assert_eq!(solve_linear_system(&a, &y).unwrap(), Some(vec_gf(vec![gf(0xD), gf(0x8), gf(0x0)])));
+
+        // Row 0 is zero in column 0, so row 1 is swapped up; column 1 is then free.
+        let a_swap = mat(vec![
+            vec![gf(0x0), gf(0x0), gf(0x1)],
+            vec![gf(0x4), gf(0x2), gf(0x0)],
+        ]);
+        // x0 = 0x4^-1 * 0x3 = 0xD * 0x3 = x^4 + x^2 + x + 1 = x^2 = 0x4, x2 = 0x7
+        let y_swap = vec_gf(vec![gf(0x7), gf(0x3)]);
+        assert_eq!(solve_linear_system(&a_swap, &y_swap).unwrap(), Some(vec_gf(vec![gf(0x4), gf(0x0), gf(0x7)])));
+    }
+
+    #[test]
+    fn test_solve_with_kernel_underdetermined() {
+        use crate::gf::gf16_add;
+        use crate::matrix::matrix_vec_mul;
+
+        // 2 equations, 4 unknowns, rank 2: a two-dimensional kernel
+        let a = mat(vec![
+            vec![gf(1), gf(2), gf(3), gf(4)],
+            vec![gf(5), gf(6), gf(7), gf(8)],
+        ]);
+        let y = vec_gf(vec![gf(0x9), gf(0xA)]);
+        let (particular, basis) = solve_linear_system_with_kernel(&a, &y).unwrap().unwrap();
+        assert_eq!(matrix_vec_mul(&a, &particular).unwrap(), y);
+        assert_eq!(basis.len(), 2);
+        for b in &basis {
+            assert!(b.iter().any(|e| e.0 != 0));
+            assert_eq!(matrix_vec_mul(&a, b).unwrap(), vec![gf(0); 2]);
+            let shifted: GFVector = particular.iter().zip(b).map(|(&p, &k)| gf16_add(p, k)).collect();
+            assert_eq!(matrix_vec_mul(&a, &shifted).unwrap(), y);
+        }
+
+        // Unique solution: empty basis
+        let (x, basis) = solve_linear_system_with_kernel(&GFMatrix::identity(3), &vec_gf(vec![gf(1), gf(2), gf(3)])).unwrap().unwrap();
+        assert_eq!(x, vec_gf(vec![gf(1), gf(2), gf(3)]));
+        assert!(basis.is_empty());
+
+        // Inconsistent: x1 + x2 = 1 and x1 + x2 = 2
+        let a_bad = mat(vec![vec![gf(1), gf(1)], vec![gf(1), gf(1)]]);
+        assert_eq!(solve_linear_system_with_kernel(&a_bad, &vec_gf(vec![gf(1), gf(2)])), Ok(None));
+    }
+
+    #[test]
+    fn test_solution_space_dim() {
+        // Full-rank square A: unique solutions
+        let a_full = mat(vec![vec![gf(1), gf(2)], vec![gf(3), gf(4)]]);
+        assert_eq!(solution_space_dim(&a_full), 0);
+        assert_eq!(solution_space_dim(&GFMatrix::identity(5)), 0);
+
+        // Rank 1 in 2 variables: second row is 2 * the first
+        let a_rank1 = mat(vec![vec![gf(1), gf(2)], vec![gf(2), gf(4)]]);
+        assert_eq!(solution_space_dim(&a_rank1), 1);
+        assert!(matches!(solve_outcome(&a_rank1, &vec_gf(vec![gf(1), gf(2)])), Ok(SolveOutcome::Underdetermined(_, ref basis)) if basis.len() == 1));
+
+        // Wide and zero matrices
+        assert_eq!(solution_space_dim(&GFMatrix::zero(2, 3)), 3);
+        assert_eq!(solution_space_dim(&mat(vec![vec![gf(1), gf(0), gf(5)]])), 2);
+    }
+
     #[test]
     fn test_dimension_mismatch() {
         let a = mat(vec![vec![gf(1)]]);
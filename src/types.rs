@@ -1,5 +1,6 @@
 use wasm_bindgen::prelude::*;
-// use crate::params::MayoParams; // Removed as per compiler warning
+use crate::params::MayoParams;
+use crate::error::MayoError;
 
 // Field element for GF(16), represented as a nibble in a u8.
 // The actual value should be in the lower 4 bits.
@@ -95,6 +96,112 @@ impl CompactPublicKey {
 #[derive(Debug, Clone, PartialEq, Eq)] // Ensure no Copy
 pub struct ExpandedSecretKey(pub Vec<u8>);
 
+impl ExpandedSecretKey {
+    /// Overwrites the key bytes with zeros, so the expanded secret material does not
+    /// linger in memory once the key is no longer needed. The length is kept.
+    pub fn wipe(&mut self) {
+        wipe_bytes(&mut self.0);
+    }
+}
+
+/// Overwrites `bytes` with zeros using volatile writes, which the compiler may not elide
+/// even though the buffer is never read again.
+pub(crate) fn wipe_bytes(bytes: &mut [u8]) {
+    for byte in bytes.iter_mut() {
+        // SAFETY: `byte` is a valid, aligned, exclusive reference into `bytes`.
+        unsafe { std::ptr::write_volatile(byte, 0) };
+    }
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
+
+/// Same as `wipe_bytes` for GF(16) elements, e.g. vinegar variables held as a `GFVector`.
+pub(crate) fn wipe_elements(elements: &mut [GFElement]) {
+    for element in elements.iter_mut() {
+        // SAFETY: `element` is a valid, aligned, exclusive reference into `elements`.
+        unsafe { std::ptr::write_volatile(element, GFElement(0)) };
+    }
+    std::sync::atomic::compiler_fence(std::sync::atomic::Ordering::SeqCst);
+}
+
+/// Secret intermediates that can be overwritten with zeros in place, see `WipeOnDrop`.
+pub(crate) trait Wipe {
+    fn wipe(&mut self);
+}
+
+impl Wipe for Vec<u8> {
+    fn wipe(&mut self) {
+        wipe_bytes(self);
+    }
+}
+
+impl Wipe for GFVector {
+    fn wipe(&mut self) {
+        wipe_elements(self);
+    }
+}
+
+impl Wipe for GFMatrix {
+    fn wipe(&mut self) {
+        wipe_elements(&mut self.data);
+    }
+}
+
+impl Wipe for Vec<GFMatrix> {
+    fn wipe(&mut self) {
+        self.iter_mut().for_each(Wipe::wipe);
+    }
+}
+
+/// Guard that wipes a secret intermediate (O, L, their encodings) when it goes out of scope,
+/// so early returns through `?` do not leave copies behind. Derefs to the wrapped value.
+pub(crate) struct WipeOnDrop<T: Wipe>(pub T);
+
+impl<T: Wipe> std::ops::Deref for WipeOnDrop<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: Wipe> std::ops::DerefMut for WipeOnDrop<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: Wipe> Drop for WipeOnDrop<T> {
+    fn drop(&mut self) {
+        self.0.wipe();
+    }
+}
+
+// Secret key material is zeroed when dropped, so copies do not outlive their owner in freed
+// memory. Only the bytes are wiped; the length is kept, as it is public.
+impl Drop for SeedSK {
+    fn drop(&mut self) {
+        wipe_bytes(&mut self.0);
+    }
+}
+
+impl Drop for CompactSecretKey {
+    fn drop(&mut self) {
+        wipe_bytes(&mut self.0);
+    }
+}
+
+impl Drop for ExpandedSecretKey {
+    fn drop(&mut self) {
+        self.wipe();
+    }
+}
+
+impl Drop for Salt {
+    fn drop(&mut self) {
+        wipe_bytes(&mut self.0);
+    }
+}
+
 /// ExpandedPublicKey contains the full public key components derived from SeedPK.
 /// This would include P1, P2, P3 (or parts of them, or their public representation).
 #[derive(Debug, Clone, PartialEq, Eq)] // Ensure no Copy
@@ -117,6 +224,17 @@ impl Signature {
     }
 }
 
+/// A signature bundled with the signer's evaluation y = P*(s) of the public map, for audit
+/// protocols that cross-check it. Produced by `sign_message_expanded` and checked by
+/// `verify_expanded`, which recomputes P*(s) and requires it to equal both `y` and t.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExpandedSignature {
+    /// The regular signature, s || salt.
+    pub signature: Signature,
+    /// The signer's claimed P*(s), m elements.
+    pub y: GFVector,
+}
+
 #[wasm_bindgen(getter_with_clone)]
 #[derive(Debug, Clone, PartialEq, Eq)] // Removed Copy
 pub struct Message(pub Vec<u8>);
@@ -133,12 +251,190 @@ impl Message {
     }
 }
 
+impl Message {
+    /// Creates a message from the UTF-8 bytes of `s`.
+    #[allow(clippy::should_implement_trait)] // Infallible, so `FromStr`'s Result would only add noise
+    pub fn from_str(s: &str) -> Message {
+        Message(s.as_bytes().to_vec())
+    }
+}
+
+impl From<&[u8]> for Message {
+    fn from(bytes: &[u8]) -> Self {
+        Message(bytes.to_vec())
+    }
+}
+
+impl From<&str> for Message {
+    fn from(s: &str) -> Self {
+        Message::from_str(s)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct MessageDigest(pub Vec<u8>);
 
+impl MessageDigest {
+    /// Borrows the digest as a fixed-size array, e.g. `[u8; 32]` for `digest_bytes = 32`.
+    /// Returns Err if the digest is not exactly `N` bytes long.
+    pub fn as_array<const N: usize>(&self) -> Result<&[u8; N], MayoError> {
+        self.0.as_slice().try_into().map_err(|_| MayoError::InvalidInput("Message digest has incorrect length"))
+    }
+
+    /// Wraps a fixed-size digest, e.g. one computed on the stack by a prehash caller.
+    pub fn from_array<const N: usize>(bytes: [u8; N]) -> Self {
+        MessageDigest(bytes.to_vec())
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Salt(pub Vec<u8>);
 
+/// Layout of a signed message blob. MAYO's `open` uses signature || message;
+/// some legacy formats put the message first.
+#[wasm_bindgen]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SignatureOrder {
+    /// signature || message (the default)
+    #[default]
+    SignatureFirst,
+    /// message || signature
+    MessageFirst,
+}
+
+/// Options shared by signer and verifier. Both sides must use the same options,
+/// otherwise they derive different targets and verification fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SignatureOptions {
+    /// Absorb a fingerprint of the public key into the target derivation, so a
+    /// signature only verifies under the key it was made for.
+    pub bind_public_key: bool,
+}
+
+/// Reads exactly `len` bytes from `reader`. A short stream is an `UnexpectedEof` error.
+fn read_exact_vec<R: std::io::Read>(reader: &mut R, len: usize) -> std::io::Result<Vec<u8>> {
+    let mut bytes = vec![0u8; len];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+/// Writes `bytes` after checking they have the variant's expected length.
+fn write_checked<W: std::io::Write>(writer: &mut W, bytes: &[u8], expected_len: usize, what: &str) -> std::io::Result<()> {
+    if bytes.len() != expected_len {
+        return Err(std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("{} has incorrect length", what)));
+    }
+    writer.write_all(bytes)
+}
+
+// Stream I/O for file-based storage. Each object is stored as its raw bytes, so exactly
+// the variant's length is read or written; no framing is added.
+impl CompactSecretKey {
+    /// Reads a compact secret key of `sk_seed_bytes` bytes from `reader`.
+    pub fn read_from<R: std::io::Read>(reader: &mut R, params_enum: &MayoParams) -> std::io::Result<Self> {
+        Ok(Self(read_exact_vec(reader, params_enum.sk_seed_bytes())?))
+    }
+
+    /// Writes the key to `writer`, rejecting a key of the wrong length for the variant.
+    pub fn write_to<W: std::io::Write>(&self, writer: &mut W, params_enum: &MayoParams) -> std::io::Result<()> {
+        write_checked(writer, &self.0, params_enum.sk_seed_bytes(), "Compact secret key")
+    }
+}
+
+// Self-describing storage format for compact secret keys:
+// [variant_id: u8][key bytes][crc32 of the preceding bytes: 4 bytes, big-endian].
+impl CompactSecretKey {
+    /// Serializes the key tagged with its variant and followed by a CRC-32 checksum,
+    /// so `from_tagged_bytes` can recover the variant and detect corruption.
+    pub fn to_tagged_bytes(&self, params_enum: &MayoParams) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + self.0.len() + 4);
+        bytes.push(params_enum.variant_id());
+        bytes.extend_from_slice(&self.0);
+        let checksum = crate::codec::crc32(&bytes);
+        crate::codec::write_u32_be(&mut bytes, checksum);
+        bytes
+    }
+
+    /// Parses the output of `to_tagged_bytes`, checking the checksum and the key length
+    /// for the tagged variant. The variant is returned with the default `ReferenceProfile`.
+    pub fn from_tagged_bytes(bytes: &[u8]) -> Result<(CompactSecretKey, MayoParams), MayoError> {
+        if bytes.len() < 1 + 4 {
+            return Err(MayoError::DecodeError("Tagged compact secret key is too short"));
+        }
+        let (body, checksum_bytes) = bytes.split_at(bytes.len() - 4);
+        let (stored_checksum, _) = crate::codec::read_u32_be(checksum_bytes)?;
+        if crate::codec::crc32(body) != stored_checksum {
+            return Err(MayoError::DecodeError("Tagged compact secret key checksum mismatch"));
+        }
+        let params_enum = MayoParams::from_variant_id(body[0])?;
+        let key_bytes = &body[1..];
+        if key_bytes.len() != params_enum.sk_seed_bytes() {
+            return Err(MayoError::InvalidKeyLength("Compact secret key has incorrect length"));
+        }
+        Ok((CompactSecretKey(key_bytes.to_vec()), params_enum))
+    }
+}
+
+// Variant-tagged signature format: [variant_id: u8][signature bytes].
+// No checksum is added: a corrupted signature simply fails verification.
+impl Signature {
+    /// Serializes the signature prefixed with its variant id, so a verifier can recover the
+    /// variant with `from_tagged_bytes` without knowing it upfront.
+    pub fn to_tagged_bytes(&self, params_enum: &MayoParams) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + self.0.len());
+        bytes.push(params_enum.variant_id());
+        bytes.extend_from_slice(&self.0);
+        bytes
+    }
+
+    /// Parses the output of `to_tagged_bytes`, checking the signature length for the tagged
+    /// variant. The variant is returned with the default `ReferenceProfile`.
+    pub fn from_tagged_bytes(bytes: &[u8]) -> Result<(Signature, MayoParams), MayoError> {
+        let (&variant_id, sig_bytes) = bytes.split_first().ok_or(MayoError::DecodeError("Tagged signature is empty"))?;
+        let params_enum = MayoParams::from_variant_id(variant_id)?;
+        if sig_bytes.len() != params_enum.variant().sig_bytes() {
+            return Err(MayoError::InvalidSignatureLength("Signature has incorrect length"));
+        }
+        Ok((Signature(sig_bytes.to_vec()), params_enum))
+    }
+}
+
+impl CompactPublicKey {
+    /// Reads a compact public key of `cpk_bytes` bytes from `reader`.
+    pub fn read_from<R: std::io::Read>(reader: &mut R, params_enum: &MayoParams) -> std::io::Result<Self> {
+        Ok(Self(read_exact_vec(reader, params_enum.variant().cpk_bytes())?))
+    }
+
+    /// Writes the key to `writer`, rejecting a key of the wrong length for the variant.
+    pub fn write_to<W: std::io::Write>(&self, writer: &mut W, params_enum: &MayoParams) -> std::io::Result<()> {
+        write_checked(writer, &self.0, params_enum.variant().cpk_bytes(), "Compact public key")
+    }
+}
+
+impl Signature {
+    /// Reads a signature of `sig_bytes` bytes from `reader`.
+    pub fn read_from<R: std::io::Read>(reader: &mut R, params_enum: &MayoParams) -> std::io::Result<Self> {
+        Ok(Self(read_exact_vec(reader, params_enum.variant().sig_bytes())?))
+    }
+
+    /// Writes the signature to `writer`, rejecting one of the wrong length for the variant.
+    pub fn write_to<W: std::io::Write>(&self, writer: &mut W, params_enum: &MayoParams) -> std::io::Result<()> {
+        write_checked(writer, &self.0, params_enum.variant().sig_bytes(), "Signature")
+    }
+}
+
+impl ExpandedPublicKey {
+    /// Reads P1, P2 and P3 from their own readers (e.g. three files) and assembles the epk
+    /// with `from_components`. Exactly `p1_bytes`, `p2_bytes` and `p3_bytes` are read.
+    pub fn read_components<R: std::io::Read>(p1_reader: &mut R, p2_reader: &mut R, p3_reader: &mut R, params_enum: &MayoParams) -> std::io::Result<Self> {
+        let params = params_enum.variant();
+        let p1_bytes = read_exact_vec(p1_reader, params.p1_bytes)?;
+        let p2_bytes = read_exact_vec(p2_reader, params.p2_bytes)?;
+        let p3_bytes = read_exact_vec(p3_reader, params.p3_bytes)?;
+        Self::from_components(&p1_bytes, &p2_bytes, &p3_bytes, params_enum)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e.to_string()))
+    }
+}
+
 // Implementations for converting to/from bytes for these types might be useful later.
 // e.g., impl From<Vec<u8>> for SeedSK ...
 // impl AsRef<[u8]> for SeedSK ...
@@ -1,43 +1,45 @@
 //! Implements MAYO.Verify (Algorithm 9).
 
-use crate::types::{ExpandedPublicKey, Message, Signature, GFVector, Salt, GFMatrix}; // Removed MessageDigest, GFElement
+use crate::types::{CompactPublicKey, ExpandedPublicKey, ExpandedSignature, Message, MessageDigest, Signature, SignatureOptions, GFElement, GFVector, Salt, GFMatrix}; // Removed MessageDigest
 use crate::params::{MayoParams, MayoVariantParams};
-use crate::hash::{shake256_digest, shake256_derive_target_t};
-use crate::codec::{decode_p1_matrices, decode_p2_matrices, decode_p3_matrices, decode_s_vector, decode_gf_elements};
-use crate::matrix::{matrix_symmetrize, matrix_vec_mul_transpose_gfvector, vector_dot_product};
-use crate::gf::gf16_add;
+use crate::hash::{message_digest, message_digest_from_reader, public_key_fingerprint, shake256_derive_target_t_with_binding, MessageDigester};
+use crate::codec::{decode_p1_matrices, decode_p2_matrices, decode_p3_matrices, decode_s_vector, decode_target_vector};
+use crate::matrix::{emulsify_rows, matrix_vec_mul_transpose_into, vector_dot_product, whipping_pairs};
+use crate::gf::{gf16_add, gf16_mul};
+use crate::error::MayoError;
 
-/// Computes the public map P*(s) for MAYO verification.
+/// Computes the public map P(s) = (s^T P_i s)_i on a single n-element vector.
+/// Verification whips k such vectors together, see `compute_whipped_p_star`.
 ///
 /// # Arguments
-/// * `s_vector` - The solution vector from the signature (n elements).
+/// * `s_vector` - A vector of n elements.
 /// * `p1_matrices` - The set of m P1_i matrices from epk, each (n-o)x(n-o) upper triangular.
 /// * `p2_matrices` - The set of m P2_i matrices from epk, each (n-o)xo.
 /// * `p3_matrices` - The set of m P3_i matrices from epk, each oxo upper triangular.
 /// * `params` - MAYO variant parameters.
 ///
 /// # Returns
-/// `Ok(GFVector /* y_vector, m elements */)` or an error string.
-fn compute_p_star_s(
+/// `Ok(GFVector /* y_vector, m elements */)` or a `MayoError`.
+pub fn compute_unwhipped_p_s(
     s_vector: &GFVector,
     p1_matrices: &[GFMatrix],
     p2_matrices: &[GFMatrix],
     p3_matrices: &[GFMatrix],
     params: &MayoVariantParams
-) -> Result<GFVector /* y_vector */, &'static str> {
+) -> Result<GFVector /* y_vector */, MayoError> {
     if s_vector.len() != params.n {
-        return Err("Signature vector s has incorrect length");
+        return Err(MayoError::InvalidSignatureLength("Signature vector s has incorrect length"));
     }
     if p1_matrices.len() != params.m || p2_matrices.len() != params.m || p3_matrices.len() != params.m {
-        return Err("Incorrect number of P matrices");
+        return Err(MayoError::DimensionMismatch("Incorrect number of P matrices"));
     }
 
-    let num_vinegar_vars = params.n - params.o;
-    let num_oil_vars = params.o;
+    let num_vinegar_vars = params.vinegar_vars();
+    let num_oil_vars = params.oil_vars();
 
     // Check consistency of s_vector length with n-o and o
     if num_vinegar_vars + num_oil_vars != params.n {
-        return Err("Internal error: n-o + o != n");
+        return Err(MayoError::InvalidParameters("Internal error: n-o + o != n"));
     }
 
     let s_v = &s_vector[0..num_vinegar_vars];
@@ -47,6 +49,9 @@ fn compute_p_star_s(
     let s_o_gfvec = s_o.to_vec();
 
     let mut y_elements: GFVector = Vec::with_capacity(params.m);
+    // Intermediate row vectors, reused across the m coordinates
+    let mut vinegar_row = vec![GFElement(0); num_vinegar_vars];
+    let mut oil_row = vec![GFElement(0); num_oil_vars];
 
     for i in 0..params.m {
         let p1_i = &p1_matrices[i];
@@ -55,30 +60,32 @@ fn compute_p_star_s(
 
         // Dimension checks for each matrix P_i^k
         if p1_i.num_rows() != num_vinegar_vars || p1_i.num_cols() != num_vinegar_vars {
-            return Err("P1 matrix dimension mismatch");
+            return Err(MayoError::DimensionMismatch("P1 matrix dimension mismatch"));
         }
         if p2_i.num_rows() != num_vinegar_vars || p2_i.num_cols() != num_oil_vars {
-            return Err("P2 matrix dimension mismatch");
+            return Err(MayoError::DimensionMismatch("P2 matrix dimension mismatch"));
         }
         if p3_i.num_rows() != num_oil_vars || p3_i.num_cols() != num_oil_vars {
-            return Err("P3 matrix dimension mismatch");
+            return Err(MayoError::DimensionMismatch("P3 matrix dimension mismatch"));
         }
 
-        // Symmetrize P1_i and P3_i (M + M^T, diagonal becomes 0)
-        let p1_i_sym = matrix_symmetrize(p1_i)?;
-        let p3_i_sym = matrix_symmetrize(p3_i)?;
+        // P_i = [[P1_i, P2_i], [0, P3_i]] is upper triangular and P*(s)_i = s^T P_i s.
+        // P1_i and P3_i are used as stored: symmetrizing them would turn the quadratic
+        // terms into a symmetric bilinear form, which is identically zero in characteristic 2.
 
-        // Term 1: s_V^T * P1_i_sym * s_V
-        let sv_p1_intermediate = matrix_vec_mul_transpose_gfvector(&s_v_gfvec, &p1_i_sym)?;
-        let term1 = vector_dot_product(&sv_p1_intermediate, &s_v_gfvec)?;
+        // Term 1: s_V^T * P1_i * s_V
+        matrix_vec_mul_transpose_into(&s_v_gfvec, p1_i, &mut vinegar_row)?;
+        let term1 = vector_dot_product(&vinegar_row, &s_v_gfvec)?;
 
         // Term 2: s_V^T * P2_i * s_O
-        let sv_p2_intermediate = matrix_vec_mul_transpose_gfvector(&s_v_gfvec, p2_i)?;
-        let term2 = vector_dot_product(&sv_p2_intermediate, &s_o_gfvec)?;
+        // P2_i is the rectangular (n-o) x o off-diagonal block of P_i, so there is nothing to
+        // symmetrize: the lower-left block of P_i is zero and this is the only cross term.
+        matrix_vec_mul_transpose_into(&s_v_gfvec, p2_i, &mut oil_row)?;
+        let term2 = vector_dot_product(&oil_row, &s_o_gfvec)?;
 
-        // Term 3: s_O^T * P3_i_sym * s_O
-        let so_p3_intermediate = matrix_vec_mul_transpose_gfvector(&s_o_gfvec, &p3_i_sym)?;
-        let term3 = vector_dot_product(&so_p3_intermediate, &s_o_gfvec)?;
+        // Term 3: s_O^T * P3_i * s_O
+        matrix_vec_mul_transpose_into(&s_o_gfvec, p3_i, &mut oil_row)?;
+        let term3 = vector_dot_product(&oil_row, &s_o_gfvec)?;
         
         let y_i = gf16_add(gf16_add(term1, term2), term3);
         y_elements.push(y_i);
@@ -86,17 +93,181 @@ fn compute_p_star_s(
     Ok(y_elements)
 }
 
-/// Implements MAYO.Verify (Algorithm 9 from the MAYO specification).
-/// Verifies a signature against a message and an expanded public key.
-pub fn verify_signature(epk: &ExpandedPublicKey, message: &Message, signature: &Signature, params_enum: &MayoParams) -> Result<bool, &'static str> {
-    let params = params_enum.variant();
+/// Computes the whipped public map P*(s) that MAYO.Verify compares with the target t.
+///
+/// `s_vector` is the signature's s = s_0 || ... || s_{k-1}, k * n elements. With P'(a, b) =
+/// a^T P b + b^T P a the polar form of P, pair number l of `whipping_pairs` contributes
+/// u_l = P(s_i) for i = j and u_l = P'(s_i, s_j) otherwise, and
+/// P*(s) = sum_l E^l u_l, where E multiplies by z modulo f(z) (see `emulsify_rows`).
+pub fn compute_whipped_p_star(
+    s_vector: &GFVector,
+    p1_matrices: &[GFMatrix],
+    p2_matrices: &[GFMatrix],
+    p3_matrices: &[GFMatrix],
+    params: &MayoVariantParams
+) -> Result<GFVector, MayoError> {
+    compute_whipped_p_star_with_scratch(s_vector, p1_matrices, p2_matrices, p3_matrices, &mut PStarScratch::new(params), params)
+}
 
-    // 1. Decode epk into P1, P2, P3 matrices
+/// Intermediate buffers of `compute_whipped_p_star`, reused across calls by
+/// `compute_whipped_p_star_with_scratch`.
+#[derive(Debug, Default, Clone)]
+pub struct PStarScratch {
+    // u[l * m + a] is coordinate a of u_l
+    u: GFVector,
+    // rows[i] = s_i^T P_a
+    rows: Vec<GFVector>,
+    oil_row: GFVector,
+}
+
+impl PStarScratch {
+    /// Creates scratch buffers sized for the given variant.
+    pub fn new(params: &MayoVariantParams) -> Self {
+        let mut scratch = PStarScratch::default();
+        scratch.resize(params);
+        scratch
+    }
+
+    // The scratch may be default-constructed or sized for another variant. `u` is cleared,
+    // since coordinates outside a requested prefix are never written.
+    fn resize(&mut self, params: &MayoVariantParams) {
+        let num_pairs = params.k * (params.k + 1) / 2;
+        self.u.clear();
+        self.u.resize(num_pairs * params.m, GFElement(0));
+        self.rows.resize(params.k, GFVector::new());
+        for row in &mut self.rows {
+            row.resize(params.n, GFElement(0));
+        }
+        self.oil_row.resize(params.oil_vars(), GFElement(0));
+    }
+}
+
+/// Same as `compute_whipped_p_star`, but reuses `scratch` for the intermediate vectors,
+/// so a batch of signatures under one key allocates them once. The result is identical.
+pub fn compute_whipped_p_star_with_scratch(
+    s_vector: &GFVector,
+    p1_matrices: &[GFMatrix],
+    p2_matrices: &[GFMatrix],
+    p3_matrices: &[GFMatrix],
+    scratch: &mut PStarScratch,
+    params: &MayoVariantParams
+) -> Result<GFVector, MayoError> {
+    compute_whipped_p_star_prefix(s_vector, p1_matrices, p2_matrices, p3_matrices, params.m, scratch, params)
+}
+
+/// For each pair number l, which coordinates of u_l can reach the first `num_coords`
+/// coordinates of P*(s) = sum_l E^l u_l.
+///
+/// E moves coordinate b to b + 1 and folds the top coordinate m - 1 onto the nonzero
+/// coefficients of f_tail, so walking Horner's scheme backwards shifts the needed set
+/// down by one per power of E, and adds m - 1 whenever it meets f_tail.
+fn whipped_prefix_dependencies(num_coords: usize, num_pairs: usize, m: usize, f_tail: &[u8]) -> Vec<Vec<bool>> {
+    let mut needed = Vec::with_capacity(num_pairs);
+    let mut current: Vec<bool> = (0..m).map(|b| b < num_coords).collect();
+    for _ in 0..num_pairs {
+        let mut previous = vec![false; m];
+        previous[..m - 1].copy_from_slice(&current[1..]);
+        previous[m - 1] = f_tail.iter().zip(&current).any(|(&f_a, &needed_a)| f_a != 0 && needed_a);
+        needed.push(current);
+        current = previous;
+    }
+    needed
+}
+
+/// The first `num_coords` coordinates of `compute_whipped_p_star`. Only the P_a whose
+/// u_l coordinates can reach that prefix through the emulsifier are evaluated, see
+/// `whipped_prefix_dependencies`; `num_coords` = m is the full map.
+fn compute_whipped_p_star_prefix(
+    s_vector: &GFVector,
+    p1_matrices: &[GFMatrix],
+    p2_matrices: &[GFMatrix],
+    p3_matrices: &[GFMatrix],
+    num_coords: usize,
+    scratch: &mut PStarScratch,
+    params: &MayoVariantParams
+) -> Result<GFVector, MayoError> {
+    let (n, k, m) = (params.n, params.k, params.m);
+    if s_vector.len() != k * n {
+        return Err(MayoError::InvalidSignatureLength("Signature vector s has incorrect length"));
+    }
+    if p1_matrices.len() != m || p2_matrices.len() != m || p3_matrices.len() != m {
+        return Err(MayoError::DimensionMismatch("Incorrect number of P matrices"));
+    }
+    let f_tail = params.f_tail()?;
+    let num_vinegar_vars = params.vinegar_vars();
+    let num_oil_vars = params.oil_vars();
+    let dot = |a: &[GFElement], b: &[GFElement]| {
+        a.iter().zip(b).fold(GFElement(0), |sum, (&x, &y)| gf16_add(sum, gf16_mul(x, y)))
+    };
+    let s_i = |i: usize| &s_vector[i * n..(i + 1) * n];
+    let pairs = whipping_pairs(k);
+    let needed = whipped_prefix_dependencies(num_coords.min(m), pairs.len(), m, f_tail);
+
+    // u[l * m + a] is coordinate a of u_l, so each u_l is a contiguous m-element column.
+    // rows[i] = s_i^T P_a is reused across the m coordinates.
+    scratch.resize(params);
+    let PStarScratch { u, rows, oil_row } = scratch;
+    for a in 0..m {
+        let (p1_a, p2_a, p3_a) = (&p1_matrices[a], &p2_matrices[a], &p3_matrices[a]);
+        if p1_a.num_rows() != num_vinegar_vars || p1_a.num_cols() != num_vinegar_vars {
+            return Err(MayoError::DimensionMismatch("P1 matrix dimension mismatch"));
+        }
+        if p2_a.num_rows() != num_vinegar_vars || p2_a.num_cols() != num_oil_vars {
+            return Err(MayoError::DimensionMismatch("P2 matrix dimension mismatch"));
+        }
+        if p3_a.num_rows() != num_oil_vars || p3_a.num_cols() != num_oil_vars {
+            return Err(MayoError::DimensionMismatch("P3 matrix dimension mismatch"));
+        }
+        // u_l stays zero where it cannot reach the requested prefix
+        if !needed.iter().any(|needed_l| needed_l[a]) {
+            continue;
+        }
+
+        // P_a = [[P1_a, P2_a], [0, P3_a]], so s_i^T P_a = (s_V^T P1_a, s_V^T P2_a + s_O^T P3_a).
+        for (i, row) in rows.iter_mut().enumerate() {
+            let (s_v, s_o) = s_i(i).split_at(num_vinegar_vars);
+            let (row_v, row_o) = row.split_at_mut(num_vinegar_vars);
+            matrix_vec_mul_transpose_into(s_v, p1_a, row_v)?;
+            matrix_vec_mul_transpose_into(s_v, p2_a, row_o)?;
+            matrix_vec_mul_transpose_into(s_o, p3_a, oil_row)?;
+            for (r, &x) in row_o.iter_mut().zip(oil_row.iter()) {
+                *r = gf16_add(*r, x);
+            }
+        }
+
+        for (l, &(i, j)) in pairs.iter().enumerate() {
+            let mut u_l = dot(&rows[i], s_i(j));
+            if i != j {
+                u_l = gf16_add(u_l, dot(&rows[j], s_i(i)));
+            }
+            u[l * m + a] = u_l;
+        }
+    }
+
+    // Horner: y = u_0 + E(u_1 + E(u_2 + ...))
+    let mut y_elements = vec![GFElement(0); m];
+    for u_l in u.chunks(m).rev() {
+        emulsify_rows(&mut y_elements, 1, f_tail);
+        for (y, &u_a) in y_elements.iter_mut().zip(u_l) {
+            *y = gf16_add(*y, u_a);
+        }
+    }
+    y_elements.truncate(num_coords);
+    Ok(y_elements)
+}
+
+/// The decoded P1, P2 and P3 matrix sets of an expanded public key.
+pub type PublicMatrices = (Vec<GFMatrix>, Vec<GFMatrix>, Vec<GFMatrix>);
+
+/// Decodes an expanded public key into its P1, P2 and P3 matrix sets.
+/// Callers verifying many signatures under one key can decode once and use `verify_with_matrices`.
+pub fn decode_epk_matrices(epk: &ExpandedPublicKey, params: &MayoVariantParams) -> Result<PublicMatrices, MayoError> {
+    params.validate()?;
     let p1_bytes_end = params.p1_bytes;
     let p2_bytes_end = params.p1_bytes + params.p2_bytes;
 
     if epk.0.len() != params.p1_bytes + params.p2_bytes + params.p3_bytes {
-        return Err("Expanded public key has incorrect length");
+        return Err(MayoError::InvalidKeyLength("Expanded public key has incorrect length"));
     }
 
     let p1_all_bytes = &epk.0[0..p1_bytes_end];
@@ -106,35 +277,287 @@ pub fn verify_signature(epk: &ExpandedPublicKey, message: &Message, signature: &
     let p1_matrices = decode_p1_matrices(p1_all_bytes, params)?;
     let p2_matrices = decode_p2_matrices(p2_all_bytes, params)?;
     let p3_matrices = decode_p3_matrices(p3_all_bytes, params)?;
+    Ok((p1_matrices, p2_matrices, p3_matrices))
+}
+
+/// Decodes the signature into s and derives the target vector t for the message.
+/// `pk_fingerprint` is absorbed into t for key-bound signatures.
+fn decode_signature_and_target(message: &Message, signature: &Signature, pk_fingerprint: Option<&[u8]>, params_enum: &MayoParams) -> Result<(GFVector, GFVector), MayoError> {
+    // Reject inconsistent parameters before hashing anything
+    params_enum.variant().validate()?;
+
+    // Hash message M to M_digest
+    let m_digest = message_digest(message, params_enum);
+    decode_signature_and_target_from_digest(&m_digest, signature, pk_fingerprint, params_enum)
+}
+
+/// Same as `decode_signature_and_target`, for a message that has already been hashed.
+fn decode_signature_and_target_from_digest(m_digest: &MessageDigest, signature: &Signature, pk_fingerprint: Option<&[u8]>, params_enum: &MayoParams) -> Result<(GFVector, GFVector), MayoError> {
+    let params = params_enum.variant();
+    params.validate()?;
+
+    // Decode signature into salt and s_vector
+    let s_vector = decode_signature_s(signature, params)?;
+    let salt = Salt(signature.0[params.s_bytes()..].to_vec());
+
+    // Derive target vector t
+    let t_bytes = shake256_derive_target_t_with_binding(m_digest, &salt, pk_fingerprint, params_enum);
+    let t_vector = decode_target_vector(&t_bytes, params.m)?;
+
+    Ok((s_vector, t_vector))
+}
 
-    // 2. Decode signature into salt and s_vector
-    let s_bytes_len = MayoParams::bytes_for_gf16_elements(params.n);
+/// Checks the signature length and decodes its solution vector s, ignoring the salt.
+fn decode_signature_s(signature: &Signature, params: &MayoVariantParams) -> Result<GFVector, MayoError> {
+    let s_bytes_len = params.s_bytes();
     if signature.0.len() != s_bytes_len + params.salt_bytes {
-        return Err("Signature has incorrect length");
+        return Err(MayoError::InvalidSignatureLength("Signature has incorrect length"));
     }
-    let s_bytes = &signature.0[0..s_bytes_len];
-    let salt_bytes_slice = &signature.0[s_bytes_len..];
-    
-    let s_vector = decode_s_vector(s_bytes, params)?;
-    let salt = Salt(salt_bytes_slice.to_vec());
+    decode_s_vector(&signature.0[..s_bytes_len], params)
+}
 
-    // 3. Hash message M to M_digest
-    let m_digest = shake256_digest(&message.0, params_enum);
+/// P*(0) = 0 for every public key, so s = 0 would "verify" against a zero target without any
+/// knowledge of the secret key. Such a target occurs with negligible probability for hashed
+/// messages, but `verify_with_target` accepts caller-supplied targets, so s = 0 is always rejected.
+fn is_zero_solution(s_vector: &GFVector) -> bool {
+    s_vector.iter().all(|e| e.0 == 0)
+}
 
-    // 4. Derive target vector t
-    let t_bytes = shake256_derive_target_t(&m_digest, &salt, params_enum);
-    let t_vector = decode_gf_elements(&t_bytes, params.m)?;
+/// Verifies a signature against a target vector t supplied by the caller, for protocols
+/// that transmit t alongside the signature. No message is hashed and the signature's salt
+/// is ignored, so the result says nothing about which message t belongs to.
+pub fn verify_with_target(epk: &ExpandedPublicKey, t_vector: &GFVector, signature: &Signature, params_enum: &MayoParams) -> Result<bool, MayoError> {
+    let params = params_enum.variant();
+    let (p1_matrices, p2_matrices, p3_matrices) = decode_epk_matrices(epk, params)?;
+    if t_vector.len() != params.m {
+        return Err(MayoError::DimensionMismatch("Target vector t has incorrect length"));
+    }
+    let s_vector = decode_signature_s(signature, params)?;
+    solution_matches_target(&s_vector, t_vector, &p1_matrices, &p2_matrices, &p3_matrices, params)
+}
 
-    // 5. Compute y = P*(s)
-    let y_computed_vector = compute_p_star_s(&s_vector, &p1_matrices, &p2_matrices, &p3_matrices, params)?;
-    
-    if y_computed_vector.len() != params.m {
-        // This check should be redundant if compute_p_star_s is correct
-        return Err("Computed y vector has incorrect length");
+/// Evaluates the whipped public map on the signature's solution vector: returns y = P*(s), with m
+/// elements, without deriving or comparing against a target. The salt is ignored. For callers
+/// that compare y with a target themselves; unlike `verify_signature`, s = 0 is not rejected.
+pub fn evaluate_public_map(epk: &ExpandedPublicKey, signature: &Signature, params_enum: &MayoParams) -> Result<GFVector, MayoError> {
+    let params = params_enum.variant();
+    let (p1_matrices, p2_matrices, p3_matrices) = decode_epk_matrices(epk, params)?;
+    let s_vector = decode_signature_s(signature, params)?;
+    compute_whipped_p_star(&s_vector, &p1_matrices, &p2_matrices, &p3_matrices, params)
+}
+
+/// Verifies an `ExpandedSignature`: recomputes P*(s) and accepts only if it equals both the
+/// signer's claimed `y` and the target t for `message`. A signature that verifies on its own
+/// is still rejected when its claimed `y` is wrong.
+pub fn verify_expanded(epk: &ExpandedPublicKey, message: &Message, expanded: &ExpandedSignature, params_enum: &MayoParams) -> Result<bool, MayoError> {
+    let params = params_enum.variant();
+    let (p1_matrices, p2_matrices, p3_matrices) = decode_epk_matrices(epk, params)?;
+    if expanded.y.len() != params.m {
+        return Err(MayoError::InvalidInput("Claimed public map output y has incorrect length"));
+    }
+    let (s_vector, t_vector) = decode_signature_and_target(message, &expanded.signature, None, params_enum)?;
+    if is_zero_solution(&s_vector) {
+        return Ok(false);
     }
+    let y_computed_vector = compute_whipped_p_star(&s_vector, &p1_matrices, &p2_matrices, &p3_matrices, params)?;
+    Ok(y_computed_vector == expanded.y && expanded.y == t_vector)
+}
 
-    // 6. Compare computed y with target t
-    Ok(y_computed_vector == t_vector)
+/// Steps 5-6 of MAYO.Verify: computes y = P*(s) and compares it with the target t.
+/// The all-zero s is rejected up front, see `is_zero_solution`.
+fn solution_matches_target(
+    s_vector: &GFVector,
+    t_vector: &GFVector,
+    p1_matrices: &[GFMatrix],
+    p2_matrices: &[GFMatrix],
+    p3_matrices: &[GFMatrix],
+    params: &MayoVariantParams
+) -> Result<bool, MayoError> {
+    if is_zero_solution(s_vector) {
+        return Ok(false);
+    }
+    let y_computed_vector = compute_whipped_p_star(s_vector, p1_matrices, p2_matrices, p3_matrices, params)?;
+    Ok(y_computed_vector == *t_vector)
+}
+
+/// Implements MAYO.Verify (Algorithm 9 from the MAYO specification).
+/// Verifies a signature against a message and an expanded public key.
+pub fn verify_signature(epk: &ExpandedPublicKey, message: &Message, signature: &Signature, params_enum: &MayoParams) -> Result<bool, MayoError> {
+    // 1. Decode epk into P1, P2, P3 matrices
+    let (p1_matrices, p2_matrices, p3_matrices) = decode_epk_matrices(epk, params_enum.variant())?;
+    verify_with_matrices(&p1_matrices, &p2_matrices, &p3_matrices, message, signature, params_enum)
+}
+
+/// Verifies a signature against already-decoded P1, P2 and P3 matrix sets.
+/// Equivalent to `verify_signature` without the epk decoding step.
+pub fn verify_with_matrices(
+    p1_matrices: &[GFMatrix],
+    p2_matrices: &[GFMatrix],
+    p3_matrices: &[GFMatrix],
+    message: &Message,
+    signature: &Signature,
+    params_enum: &MayoParams
+) -> Result<bool, MayoError> {
+    verify_core(p1_matrices, p2_matrices, p3_matrices, message, signature, None, params_enum)
+}
+
+/// Same as `verify_signature`, with `options` applied to the target derivation.
+/// The options must match those the signer passed to `sign_message_with_options`.
+pub fn verify_signature_with_options(epk: &ExpandedPublicKey, message: &Message, signature: &Signature, options: &SignatureOptions, params_enum: &MayoParams) -> Result<bool, MayoError> {
+    let (p1_matrices, p2_matrices, p3_matrices) = decode_epk_matrices(epk, params_enum.variant())?;
+    let pk_fingerprint = options.bind_public_key.then(|| public_key_fingerprint(epk, params_enum));
+    verify_core(&p1_matrices, &p2_matrices, &p3_matrices, message, signature, pk_fingerprint.as_deref(), params_enum)
+}
+
+fn verify_core(
+    p1_matrices: &[GFMatrix],
+    p2_matrices: &[GFMatrix],
+    p3_matrices: &[GFMatrix],
+    message: &Message,
+    signature: &Signature,
+    pk_fingerprint: Option<&[u8]>,
+    params_enum: &MayoParams
+) -> Result<bool, MayoError> {
+    let params = params_enum.variant();
+
+    // 2-4. Decode the signature and derive the target vector t
+    let (s_vector, t_vector) = decode_signature_and_target(message, signature, pk_fingerprint, params_enum)?;
+
+    // 5-6. Compute y = P*(s) and compare it with the target t
+    solution_matches_target(&s_vector, &t_vector, p1_matrices, p2_matrices, p3_matrices, params)
+}
+
+/// Verifies many (message, signature) pairs under one expanded public key, decoding the key once.
+///
+/// `result[i]` is the verdict for `items[i]`, exactly as `verify_signature` would return it.
+/// With the `parallel` feature the items are verified on the rayon thread pool; collecting
+/// an indexed parallel iterator keeps the input order, so the mapping is the same either way.
+pub fn verify_batch(epk: &ExpandedPublicKey, items: &[(Message, Signature)], params_enum: &MayoParams) -> Result<Vec<Result<bool, MayoError>>, MayoError> {
+    let (p1_matrices, p2_matrices, p3_matrices) = decode_epk_matrices(epk, params_enum.variant())?;
+    let verify_item = |(message, signature): &(Message, Signature)| {
+        verify_with_matrices(&p1_matrices, &p2_matrices, &p3_matrices, message, signature, params_enum)
+    };
+
+    #[cfg(feature = "parallel")]
+    let results = {
+        use rayon::prelude::*;
+        items.par_iter().map(verify_item).collect()
+    };
+    #[cfg(not(feature = "parallel"))]
+    let results = items.iter().map(verify_item).collect();
+
+    Ok(results)
+}
+
+/// Verifies a signature over a message read from `reader`, e.g. a large file.
+/// The message is streamed through SHAKE256 in chunks instead of being loaded into memory;
+/// the result is the same as `verify_signature` on the full message.
+pub fn verify_from_reader<R: std::io::Read>(epk: &ExpandedPublicKey, reader: &mut R, signature: &Signature, params_enum: &MayoParams) -> Result<bool, MayoError> {
+    let (p1_matrices, p2_matrices, p3_matrices) = decode_epk_matrices(epk, params_enum.variant())?;
+    let m_digest = message_digest_from_reader(reader, params_enum).map_err(|_| MayoError::InvalidInput("Failed to read message"))?;
+    let (s_vector, t_vector) = decode_signature_and_target_from_digest(&m_digest, signature, None, params_enum)?;
+    solution_matches_target(&s_vector, &t_vector, &p1_matrices, &p2_matrices, &p3_matrices, params_enum.variant())
+}
+
+/// Verifies a signature received before its message, absorbing the message as it arrives
+/// instead of buffering it. The result is the same as `verify_signature` on the
+/// concatenation of all chunks passed to `update`.
+pub struct StreamingVerifier {
+    matrices: PublicMatrices,
+    s_vector: GFVector,
+    salt: Salt,
+    digester: MessageDigester,
+    params_enum: MayoParams,
+}
+
+impl StreamingVerifier {
+    /// Decodes the public key matrices and the signature up front, so malformed input is
+    /// reported before any message bytes are read.
+    pub fn new(epk: &ExpandedPublicKey, signature: &Signature, params_enum: &MayoParams) -> Result<Self, MayoError> {
+        let params = params_enum.variant();
+        let matrices = decode_epk_matrices(epk, params)?;
+        let s_vector = decode_signature_s(signature, params)?;
+        let salt = Salt(signature.0[params.s_bytes()..].to_vec());
+        Ok(StreamingVerifier { matrices, s_vector, salt, digester: MessageDigester::new(params_enum), params_enum: *params_enum })
+    }
+
+    /// Feeds the next chunk of the message.
+    pub fn update(&mut self, chunk: &[u8]) {
+        self.digester.update(chunk);
+    }
+
+    /// Derives the target from the streamed message and the signature's salt and checks P*(s) = t.
+    pub fn finalize(self) -> Result<bool, MayoError> {
+        let params = self.params_enum.variant();
+        let m_digest = self.digester.finalize();
+        let t_bytes = shake256_derive_target_t_with_binding(&m_digest, &self.salt, None, &self.params_enum);
+        let t_vector = decode_target_vector(&t_bytes, params.m)?;
+        let (p1_matrices, p2_matrices, p3_matrices) = &self.matrices;
+        solution_matches_target(&self.s_vector, &t_vector, p1_matrices, p2_matrices, p3_matrices, params)
+    }
+}
+
+/// Checks only the first `k_coords` coordinates of P*(s) against the target t.
+///
+/// This is NOT a cryptographic verdict: a forged signature passes with probability
+/// about 16^-k_coords. A `true` result must always be followed by full verification.
+/// Only the P_a that reach these coordinates through the emulsifier are evaluated, so the
+/// saving grows with m relative to the k(k+1)/2 powers of E the whipping applies.
+pub fn verify_prefilter(epk: &ExpandedPublicKey, message: &Message, signature: &Signature, k_coords: usize, params_enum: &MayoParams) -> Result<bool, MayoError> {
+    let params = params_enum.variant();
+    if k_coords == 0 || k_coords > params.m {
+        return Err(MayoError::InvalidInput("k_coords must be between 1 and m"));
+    }
+    let (p1_matrices, p2_matrices, p3_matrices) = decode_epk_matrices(epk, params)?;
+    let (s_vector, t_vector) = decode_signature_and_target(message, signature, None, params_enum)?;
+    // Same up-front rejection as `solution_matches_target`
+    if is_zero_solution(&s_vector) {
+        return Ok(false);
+    }
+    let y_prefix = compute_whipped_p_star_prefix(&s_vector, &p1_matrices, &p2_matrices, &p3_matrices, k_coords, &mut PStarScratch::new(params), params)?;
+    Ok(y_prefix[..] == t_vector[..k_coords])
+}
+
+/// Cheap structural pre-routing filter: returns whether `signature` is shaped like a signature
+/// for `cpk`'s variant, without expanding the key or hashing any message.
+///
+/// This is NOT a cryptographic check and says nothing about which key produced the signature.
+/// It only confirms the signature is exactly `sig_bytes` long (so the salt region is the
+/// variant's `salt_bytes`) and that s decodes to a nonzero vector, which verification rejects
+/// up front. Use it to route signatures to candidate keys before a full `open`.
+/// Returns Err only if the parameters or the length of `cpk` are invalid.
+pub fn quick_key_match(cpk: &CompactPublicKey, signature: &Signature, params_enum: &MayoParams) -> Result<bool, MayoError> {
+    let params = params_enum.variant();
+    params.validate()?;
+    if cpk.0.len() != params.cpk_bytes() {
+        return Err(MayoError::InvalidKeyLength("Compact public key has incorrect length"));
+    }
+    match decode_signature_s(signature, params) {
+        Ok(s_vector) => Ok(!is_zero_solution(&s_vector)),
+        Err(_) => Ok(false),
+    }
+}
+
+/// Returns how many of the m coordinates of P*(s) equal the target t.
+///
+/// A valid signature returns m and a random one about m/16. Intended for diagnosing
+/// near-misses and fault analysis; use `verify_signature` for the actual verdict.
+pub fn verify_match_count(epk: &ExpandedPublicKey, message: &Message, signature: &Signature, params_enum: &MayoParams) -> Result<usize, MayoError> {
+    let (p1_matrices, p2_matrices, p3_matrices) = decode_epk_matrices(epk, params_enum.variant())?;
+    match_count_with_matrices(&p1_matrices, &p2_matrices, &p3_matrices, message, signature, params_enum)
+}
+
+fn match_count_with_matrices(
+    p1_matrices: &[GFMatrix],
+    p2_matrices: &[GFMatrix],
+    p3_matrices: &[GFMatrix],
+    message: &Message,
+    signature: &Signature,
+    params_enum: &MayoParams
+) -> Result<usize, MayoError> {
+    let (s_vector, t_vector) = decode_signature_and_target(message, signature, None, params_enum)?;
+    let y_computed_vector = compute_whipped_p_star(&s_vector, p1_matrices, p2_matrices, p3_matrices, params_enum.variant())?;
+    Ok(y_computed_vector.iter().zip(t_vector.iter()).filter(|(y, t)| y == t).count())
 }
 
 
@@ -142,12 +565,14 @@ pub fn verify_signature(epk: &ExpandedPublicKey, message: &Message, signature: &
 mod tests {
     use super::*;
     use crate::params::MayoParams;
+    use crate::matrix::matrix_vec_mul_transpose_gfvector;
     // GFElement removed from here as it's only used in create_dummy_signature for GFElement(0) which can be Self(0) or just 0 if type inference works.
     // However, GFVector is Vec<GFElement>, so GFElement itself might still be needed if GFVector is constructed with GFElement explicitly.
     // Let's check if the compiler complains after removing GFElement from the main import. It's used in dummy_s_vector.
     use crate::types::{ExpandedPublicKey as EpkTypeForTest, Signature as SigTypeForTest, Message as MsgTypeForTest, GFElement}; // Re-added GFElement for test
     use crate::keygen::{compact_key_gen, expand_pk}; 
     use crate::codec::encode_s_vector; 
+    use crate::matrix::matrix_symmetrize;
 
     fn create_dummy_epk(params_enum: &MayoParams) -> EpkTypeForTest {
         let (_csk, cpk) = compact_key_gen(params_enum).unwrap();
@@ -156,8 +581,8 @@ mod tests {
 
     fn create_dummy_signature(params_enum: &MayoParams) -> SigTypeForTest {
         let params = params_enum.variant();
-        let s_len = params.n;
-        let s_bytes_len = MayoParams::bytes_for_gf16_elements(s_len);
+        let s_len = params.k * params.n;
+        let s_bytes_len = params.s_bytes();
         let salt_len = params.salt_bytes;
 
         let dummy_s_vector: GFVector = vec![GFElement(0); s_len];
@@ -181,7 +606,7 @@ mod tests {
 
         let verify_result = verify_signature(&epk, &message, &signature, &params_enum);
         
-        // With compute_p_star_s implemented, we expect Ok(false) for a dummy signature
+        // With compute_unwhipped_p_s implemented, we expect Ok(false) for a dummy signature
         // as it's highly unlikely to match the recomputed t_vector.
         match verify_result {
             Ok(false) => { /* Expected for a dummy signature not matching a real message hash */ }
@@ -216,27 +641,630 @@ mod tests {
         wrong_epk_bytes.pop();
         let wrong_epk = EpkTypeForTest(wrong_epk_bytes);
         assert_eq!(verify_signature(&wrong_epk, &message, &valid_signature, &params_enum), 
-                   Err("Expanded public key has incorrect length"));
+                   Err(MayoError::InvalidKeyLength("Expanded public key has incorrect length")));
 
         let mut wrong_sig_bytes = valid_signature.0.clone();
         wrong_sig_bytes.pop();
         let wrong_sig = SigTypeForTest(wrong_sig_bytes);
         assert_eq!(verify_signature(&epk, &message, &wrong_sig, &params_enum),
-                   Err("Signature has incorrect length"));
+                   Err(MayoError::InvalidSignatureLength("Signature has incorrect length")));
     }
     
-    // TODO: More detailed structural tests once compute_p_star_s is implemented.
+    #[test]
+    fn test_malformed_inputs_return_specific_variants() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let message = MsgTypeForTest(b"malformed".to_vec());
+        let signature = create_dummy_signature(&params_enum);
+
+        let truncated_epk = EpkTypeForTest(vec![0u8; params.epk_bytes() - 1]);
+        assert!(matches!(verify_signature(&truncated_epk, &message, &signature, &params_enum), Err(MayoError::InvalidKeyLength(_))));
+        let truncated_cpk = CompactPublicKey(vec![0u8; params.cpk_bytes() - 1]);
+        assert!(matches!(expand_pk(&truncated_cpk, &params_enum), Err(MayoError::InvalidKeyLength(_))));
+
+        let epk = EpkTypeForTest(vec![0u8; params.epk_bytes()]);
+        let truncated_sig = SigTypeForTest(signature.0[..params.s_bytes()].to_vec());
+        assert!(matches!(verify_signature(&epk, &message, &truncated_sig, &params_enum), Err(MayoError::InvalidSignatureLength(_))));
+        assert!(matches!(decode_s_vector(&signature.0[..params.s_bytes() - 1], params), Err(MayoError::DecodeError(_))));
+        assert!(matches!(SigTypeForTest::from_tagged_bytes(&[0xFF]), Err(MayoError::DecodeError(_))));
+        assert!(matches!(verify_prefilter(&epk, &message, &signature, 0, &params_enum), Err(MayoError::InvalidInput(_))));
+    }
+
+    #[test]
+    fn test_verify_rejects_degenerate_params() {
+        let mut no_vinegar = *MayoParams::mayo1().variant();
+        no_vinegar.o = no_vinegar.n;
+        let params_enum = MayoParams::MAYO1(no_vinegar);
+        let epk = EpkTypeForTest(vec![0u8; no_vinegar.epk_bytes()]);
+        let signature = SigTypeForTest(vec![0u8; no_vinegar.sig_bytes()]);
+        let message = MsgTypeForTest(b"test".to_vec());
+        assert_eq!(verify_signature(&epk, &message, &signature, &params_enum),
+                   Err(MayoError::InvalidParameters("Invalid parameters: o must be less than n")));
+    }
+
+    #[test]
+    fn test_verify_with_matrices_matches_verify_signature() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let epk = create_dummy_epk(&params_enum);
+        let (p1, p2, p3) = decode_epk_matrices(&epk, params).unwrap();
+        assert_eq!(p1.len(), params.m);
+
+        let message = MsgTypeForTest(b"pre-decoded matrices".to_vec());
+        let mut sig_bytes = vec![0u8; params.sig_bytes()];
+        getrandom::getrandom(&mut sig_bytes).unwrap();
+        for signature in [create_dummy_signature(&params_enum), SigTypeForTest(sig_bytes)] {
+            assert_eq!(verify_with_matrices(&p1, &p2, &p3, &message, &signature, &params_enum),
+                       verify_signature(&epk, &message, &signature, &params_enum));
+        }
+
+        // The public map can also be evaluated directly on the decoded matrices.
+        let s_vector = vec![GFElement(0); params.n];
+        assert_eq!(compute_unwhipped_p_s(&s_vector, &p1, &p2, &p3, params).unwrap(), vec![GFElement(0); params.m]);
+    }
+
+    #[test]
+    fn test_whipped_map_matches_definition() {
+        let params_enum = MayoParams::mayo1();
+        let base = params_enum.variant();
+        let mut params = *base;
+        let epk = create_dummy_epk(&params_enum);
+        let (p1, p2, p3) = decode_epk_matrices(&epk, base).unwrap();
+        let random_s = |len: usize| {
+            let mut bytes = vec![0u8; len];
+            getrandom::getrandom(&mut bytes).unwrap();
+            bytes.iter().map(|b| GFElement(b & 0x0F)).collect::<GFVector>()
+        };
+        let p = |s: &GFVector| compute_unwhipped_p_s(s, &p1, &p2, &p3, base).unwrap();
+        let add = |a: &GFVector, b: &GFVector| a.iter().zip(b).map(|(&x, &y)| gf16_add(x, y)).collect::<GFVector>();
+
+        // k = 2: pairs (0,1), (0,0), (1,1) give P*(s) = P'(s_0, s_1) + E P(s_0) + E^2 P(s_1).
+        params.k = 2;
+        let (s_0, s_1) = (random_s(params.n), random_s(params.n));
+        let polar = add(&add(&p(&add(&s_0, &s_1)), &p(&s_0)), &p(&s_1));
+        let mut e_p_s_0 = p(&s_0);
+        emulsify_rows(&mut e_p_s_0, 1, params.f_tail().unwrap());
+        let mut e2_p_s_1 = p(&s_1);
+        emulsify_rows(&mut e2_p_s_1, 1, params.f_tail().unwrap());
+        emulsify_rows(&mut e2_p_s_1, 1, params.f_tail().unwrap());
+        let s_vector: GFVector = s_0.iter().chain(&s_1).copied().collect();
+        assert_eq!(compute_whipped_p_star(&s_vector, &p1, &p2, &p3, &params).unwrap(), add(&add(&polar, &e_p_s_0), &e2_p_s_1));
+
+        // k = 1 is the plain public map.
+        params.k = 1;
+        assert_eq!(compute_whipped_p_star(&s_0, &p1, &p2, &p3, &params).unwrap(), p(&s_0));
+        assert_eq!(compute_whipped_p_star(&s_vector, &p1, &p2, &p3, &params), Err(MayoError::InvalidSignatureLength("Signature vector s has incorrect length")));
+    }
+
+    #[test]
+    fn test_whipped_p_star_scratch_matches_allocating() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let epk = create_dummy_epk(&params_enum);
+        let (p1, p2, p3) = decode_epk_matrices(&epk, params).unwrap();
+
+        // One scratch is reused across several signatures, as a batch verifier would.
+        // A prefix evaluation in between leaves unwritten coordinates of u behind.
+        let mut scratch = PStarScratch::default();
+        for _ in 0..3 {
+            let mut s_bytes = vec![0u8; params.k * params.n];
+            getrandom::getrandom(&mut s_bytes).unwrap();
+            let s_vector: GFVector = s_bytes.iter().map(|b| GFElement(b & 0x0F)).collect();
+            assert_eq!(compute_whipped_p_star_with_scratch(&s_vector, &p1, &p2, &p3, &mut scratch, params),
+                       compute_whipped_p_star(&s_vector, &p1, &p2, &p3, params));
+            compute_whipped_p_star_prefix(&s_vector, &p1, &p2, &p3, 4, &mut scratch, params).unwrap();
+        }
+
+        let short_s = vec![GFElement(1); params.k * params.n - 1];
+        assert_eq!(compute_whipped_p_star_with_scratch(&short_s, &p1, &p2, &p3, &mut scratch, params),
+                   Err(MayoError::InvalidSignatureLength("Signature vector s has incorrect length")));
+    }
+
+    #[test]
+    fn test_whipped_prefix_matches_full_map() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let epk = create_dummy_epk(&params_enum);
+        let (p1, p2, p3) = decode_epk_matrices(&epk, params).unwrap();
+        let mut s_bytes = vec![0u8; params.k * params.n];
+        getrandom::getrandom(&mut s_bytes).unwrap();
+        let s_vector: GFVector = s_bytes.iter().map(|b| GFElement(b & 0x0F)).collect();
+
+        let full = compute_whipped_p_star(&s_vector, &p1, &p2, &p3, params).unwrap();
+        for num_coords in [1, 4, 8, params.m / 2, params.m] {
+            assert_eq!(compute_whipped_p_star_prefix(&s_vector, &p1, &p2, &p3, num_coords, &mut PStarScratch::new(params), params).unwrap(), full[..num_coords]);
+        }
+
+        // A short prefix does not need every coordinate of every u_l.
+        let needed = whipped_prefix_dependencies(8, whipping_pairs(params.k).len(), params.m, params.f_tail().unwrap());
+        assert!((0..params.m).any(|a| !needed.iter().any(|needed_l| needed_l[a])));
+    }
+
+    #[test]
+    fn test_verify_prefilter() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let epk = create_dummy_epk(&params_enum);
+        let message = MsgTypeForTest(b"prefilter message".to_vec());
+
+        // A random signature matches 8 coordinates with probability 16^-8.
+        let mut random_sig_bytes = vec![0u8; params.sig_bytes()];
+        getrandom::getrandom(&mut random_sig_bytes).unwrap();
+        let random_sig = SigTypeForTest(random_sig_bytes);
+        assert_eq!(verify_prefilter(&epk, &message, &random_sig, 8, &params_enum), Ok(false));
+
+        // Checking all m coordinates gives the same verdict as full verification.
+        let signature = create_dummy_signature(&params_enum);
+        assert_eq!(verify_prefilter(&epk, &message, &signature, params.m, &params_enum),
+                   verify_signature(&epk, &message, &signature, &params_enum));
+
+        assert!(verify_prefilter(&epk, &message, &signature, 0, &params_enum).is_err());
+        assert!(verify_prefilter(&epk, &message, &signature, params.m + 1, &params_enum).is_err());
+
+        // A valid signature passes on a strict prefix, and on the full map.
+        let (valid_epk, valid_sig) = crafted_valid_key_and_signature(&message, &params_enum);
+        assert_eq!(verify_signature(&valid_epk, &message, &valid_sig, &params_enum), Ok(true));
+        for k_coords in [1, 8, params.m - 1, params.m] {
+            assert_eq!(verify_prefilter(&valid_epk, &message, &valid_sig, k_coords, &params_enum), Ok(true));
+        }
+    }
+    
+    #[test]
+    fn test_verify_from_reader_matches_in_memory() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let epk = create_dummy_epk(&params_enum);
+        // Larger than several reader chunks, and not a multiple of the chunk size
+        let large_message: Vec<u8> = (0..300_001u32).map(|i| (i % 253) as u8).collect();
+        let message = MsgTypeForTest(large_message.clone());
+
+        let streamed = message_digest_from_reader(&mut std::io::Cursor::new(&large_message), &params_enum).unwrap();
+        assert_eq!(streamed, message_digest(&message, &params_enum));
+
+        let mut random_sig_bytes = vec![0u8; params.sig_bytes()];
+        getrandom::getrandom(&mut random_sig_bytes).unwrap();
+        for signature in [create_dummy_signature(&params_enum), SigTypeForTest(random_sig_bytes)] {
+            let mut reader = std::io::Cursor::new(&large_message);
+            assert_eq!(verify_from_reader(&epk, &mut reader, &signature, &params_enum),
+                       verify_signature(&epk, &message, &signature, &params_enum));
+        }
+    }
+
+    #[test]
+    fn test_quick_key_match() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let (_, cpk) = compact_key_gen(&params_enum).unwrap();
+        let (_, signature) = crafted_valid_key_and_signature(&MsgTypeForTest(b"routing".to_vec()), &params_enum);
+        assert_eq!(quick_key_match(&cpk, &signature, &params_enum), Ok(true));
+
+        let mut short = signature.clone();
+        short.0.pop();
+        assert_eq!(quick_key_match(&cpk, &short, &params_enum), Ok(false));
+        let mut long = signature.clone();
+        long.0.push(0);
+        assert_eq!(quick_key_match(&cpk, &long, &params_enum), Ok(false));
+        assert_eq!(quick_key_match(&cpk, &SigTypeForTest(vec![0u8; params.sig_bytes()]), &params_enum), Ok(false));
+
+        let truncated_cpk = CompactPublicKey(cpk.0[1..].to_vec());
+        assert_eq!(quick_key_match(&truncated_cpk, &signature, &params_enum), Err(MayoError::InvalidKeyLength("Compact public key has incorrect length")));
+    }
+
+    #[test]
+    fn test_verify_match_count() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let (v, o) = (params.vinegar_vars(), params.oil_vars());
+        let message = MsgTypeForTest(b"match count".to_vec());
+
+        // See `crafted_s_vector`: P*(s)_i = P2_i[0][0] when P1 and P3 are zero.
+        let mut sig_bytes = encode_s_vector(&crafted_s_vector(params), params);
+        sig_bytes.extend_from_slice(&vec![0x5Au8; params.salt_bytes]);
+        let signature = SigTypeForTest(sig_bytes);
+        let (_, t_vector) = decode_signature_and_target(&message, &signature, None, &params_enum).unwrap();
+
+        let p1 = vec![GFMatrix::zero(v, v); params.m];
+        let p3 = vec![GFMatrix::zero(o, o); params.m];
+        let mut p2 = vec![GFMatrix::zero(v, o); params.m];
+        for i in 0..params.m {
+            p2[i].set_val(0, 0, t_vector[i]);
+        }
+        assert_eq!(match_count_with_matrices(&p1, &p2, &p3, &message, &signature, &params_enum), Ok(params.m));
+
+        // A one-coordinate fault shows up as m - 1
+        p2[3].set_val(0, 0, gf16_add(t_vector[3], GFElement(1)));
+        assert_eq!(match_count_with_matrices(&p1, &p2, &p3, &message, &signature, &params_enum), Ok(params.m - 1));
+
+        // A random signature under a real key matches far fewer coordinates
+        let epk = create_dummy_epk(&params_enum);
+        let mut random_sig_bytes = vec![0u8; params.sig_bytes()];
+        getrandom::getrandom(&mut random_sig_bytes).unwrap();
+        let count = verify_match_count(&epk, &message, &SigTypeForTest(random_sig_bytes), &params_enum).unwrap();
+        assert!(count < params.m);
+    }
+
+    #[test]
+    fn test_p2_orientation_matches_hand_calculation() {
+        // Small shape: n = 5, o = 2, m = 1, so each P2_i is 3 x 2.
+        let mut params = *MayoParams::mayo1().variant();
+        params.n = 5;
+        params.o = 2;
+        params.m = 1;
+        params.p2_bytes = MayoParams::bytes_for_gf16_elements(3 * 2);
+
+        // Elements 1..6 are decoded row by row (high nibble first):
+        // P2 = [[1,2],[3,4],[5,6]]. Column-major decoding would give [[1,4],[2,5],[3,6]].
+        let p2 = decode_p2_matrices(&[0x12, 0x34, 0x56], &params).unwrap();
+        assert_eq!(p2[0], GFMatrix::new_with_data(3, 2, (1..=6).map(GFElement).collect()));
+        let p1 = vec![GFMatrix::zero(3, 3)];
+        let p3 = vec![GFMatrix::zero(2, 2)];
+        let term2 = |s_v: [u8; 3], s_o: [u8; 2]| {
+            let s_vector: GFVector = s_v.iter().chain(s_o.iter()).map(|&e| GFElement(e)).collect();
+            compute_unwhipped_p_s(&s_vector, &p1, &p2, &p3, &params).unwrap()[0]
+        };
+
+        // s_V = e_0, s_O = e_1 picks out P2[0][1] = 2 (it would be 4 if P2 were transposed).
+        assert_eq!(term2([1, 0, 0], [0, 1]), GFElement(2));
+        // s_V = (1,2,3), s_O = (1,1): 1*(1+2) + 2*(3+4) + 3*(5+6) = 3 + 0xE + 5 = 8
+        // (the transposed reading gives 4).
+        assert_eq!(term2([1, 2, 3], [1, 1]), GFElement(8));
+    }
+
+    #[test]
+    fn test_p2_is_not_symmetrized() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let (v, o, n) = (params.vinegar_vars(), params.oil_vars(), params.n);
+        let epk = create_dummy_epk(&params_enum);
+        let (p1, p2, p3) = decode_epk_matrices(&epk, params).unwrap();
+        let mut s_bytes = vec![0u8; n];
+        getrandom::getrandom(&mut s_bytes).unwrap();
+        let s_vector: GFVector = s_bytes.iter().map(|b| GFElement(b & 0x0F)).collect();
+        let y_vector = compute_unwhipped_p_s(&s_vector, &p1, &p2, &p3, params).unwrap();
+
+        // P2_i is rectangular, so it has no symmetrization of its own.
+        assert!(matrix_symmetrize(&p2[0]).is_err());
+
+        let mut y_symmetrized = Vec::new();
+        for i in 0..params.m {
+            // Assemble the full n x n P_i = [[P1_i, P2_i], [0, P3_i]].
+            let mut full = GFMatrix::zero(n, n);
+            for r in 0..v {
+                for c in 0..v { full.set_val(r, c, p1[i].get_unsafe(r, c)); }
+                for c in 0..o { full.set_val(r, v + c, p2[i].get_unsafe(r, c)); }
+            }
+            for r in 0..o {
+                for c in 0..o { full.set_val(v + r, v + c, p3[i].get_unsafe(r, c)); }
+            }
+            // P*(s)_i is s^T P_i s, with P2_i used once, as stored.
+            let s_t_p = matrix_vec_mul_transpose_gfvector(&s_vector, &full).unwrap();
+            assert_eq!(vector_dot_product(&s_t_p, &s_vector).unwrap(), y_vector[i]);
+
+            let s_t_sym = matrix_vec_mul_transpose_gfvector(&s_vector, &matrix_symmetrize(&full).unwrap()).unwrap();
+            y_symmetrized.push(vector_dot_product(&s_t_sym, &s_vector).unwrap());
+        }
+        // Symmetrizing P_i (and with it, mirroring P2_i into the lower-left block) changes the
+        // result: s^T (P_i + P_i^T) s is identically zero in characteristic 2.
+        assert_eq!(y_symmetrized, vec![GFElement(0); params.m]);
+        assert_ne!(y_vector, y_symmetrized);
+    }
+
+    #[test]
+    fn test_bit_flipped_public_key_rejected() {
+        use crate::keygen::{compact_key_gen_from_seed, expand_sk};
+        use crate::sign::sign_message_deterministic;
+
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let message = MsgTypeForTest(b"bit-flipped key".to_vec());
+
+        // A seeded key and a deterministic signature keep the test reproducible.
+        let seed: Vec<u8> = (0..params.sk_seed_bytes as u8).map(|b| b.wrapping_mul(29)).collect();
+        let (csk, cpk) = compact_key_gen_from_seed(&seed, &params_enum).unwrap();
+        let esk = expand_sk(&csk, &params_enum).unwrap();
+        let signature = sign_message_deterministic(&esk, &message, &params_enum).unwrap();
+        let epk = expand_pk(&cpk, &params_enum).unwrap();
+        assert_eq!(verify_signature(&epk, &message, &signature, &params_enum), Ok(true));
+
+        let regions = [
+            ("P1", 0, params.p1_bytes),
+            ("P2", params.p1_bytes, params.p2_bytes),
+            ("P3", params.p1_bytes + params.p2_bytes, params.p3_bytes),
+        ];
+        for (region, start, len) in regions {
+            // High nibbles always hold a coefficient; only a trailing low nibble can be padding.
+            let mut flipped = epk.0.clone();
+            flipped[start + len / 2] ^= 0x10;
+            assert_eq!(verify_signature(&EpkTypeForTest(flipped), &message, &signature, &params_enum), Ok(false),
+                       "Signature accepted after a bit flip in the {} region", region);
+        }
+    }
+
+    #[test]
+    fn test_verify_with_target() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let epk = create_dummy_epk(&params_enum);
+        let (p1, p2, p3) = decode_epk_matrices(&epk, params).unwrap();
+
+        let mut sig_bytes = vec![0u8; params.sig_bytes()];
+        getrandom::getrandom(&mut sig_bytes).unwrap();
+        let signature = SigTypeForTest(sig_bytes);
+        let s_vector = decode_s_vector(&signature.0[..params.s_bytes()], params).unwrap();
+        let mut t_vector = compute_whipped_p_star(&s_vector, &p1, &p2, &p3, params).unwrap();
+        assert_eq!(verify_with_target(&epk, &t_vector, &signature, &params_enum), Ok(true));
+
+        t_vector[0] = gf16_add(t_vector[0], GFElement(1));
+        assert_eq!(verify_with_target(&epk, &t_vector, &signature, &params_enum), Ok(false));
+
+        t_vector.pop();
+        assert_eq!(verify_with_target(&epk, &t_vector, &signature, &params_enum), Err(MayoError::DimensionMismatch("Target vector t has incorrect length")));
+    }
+
+    #[test]
+    fn test_target_decoding_uses_codec_nibble_convention() {
+        for params_enum in MayoParams::all_variants() {
+            let params = params_enum.variant();
+            let m_digest = crate::hash::message_digest(&Message(b"target".to_vec()), &params_enum);
+            let signature = SigTypeForTest(vec![0x5A; params.sig_bytes()]);
+            let (_, t_vector) = decode_signature_and_target_from_digest(&m_digest, &signature, None, &params_enum).unwrap();
+
+            let salt = Salt(signature.0[params.s_bytes()..].to_vec());
+            let t_bytes = shake256_derive_target_t_with_binding(&m_digest, &salt, None, &params_enum);
+            let nibbles = crate::codec::unpack_nibbles(&t_bytes, params.m).unwrap();
+            assert_eq!(t_vector, nibbles.into_iter().map(GFElement).collect::<GFVector>());
+        }
+    }
+
+    #[test]
+    fn test_all_zero_signature_is_rejected() {
+        for params_enum in MayoParams::all_variants() {
+            let params = params_enum.variant();
+            let epk = create_dummy_epk(&params_enum);
+            let zero_signature = SigTypeForTest(vec![0u8; params.sig_bytes()]);
+            for message in [Message(b"nonempty message".to_vec()), Message(Vec::new())] {
+                assert_eq!(verify_signature(&epk, &message, &zero_signature, &params_enum), Ok(false));
+            }
+
+            // P*(0) = 0, so only the explicit check stops s = 0 from matching a zero target.
+            let zero_target = vec![GFElement(0); params.m];
+            assert_eq!(verify_with_target(&epk, &zero_target, &zero_signature, &params_enum), Ok(false));
+        }
+    }
+
+    /// Returns a copy of `sig` with one bit of the s region flipped (the low bit of s[0]),
+    /// so it has the right length but, for a valid `sig`, no longer verifies.
+    fn corrupt_signature(sig: &SigTypeForTest) -> SigTypeForTest {
+        let mut corrupted = sig.clone();
+        corrupted.0[0] ^= 0x10;
+        corrupted
+    }
+
+    /// s with s_0 = e_0 (first vinegar entry), s_{k-1} = e_{n-o} (first oil entry) and the
+    /// other vectors zero. With P1 = P3 = 0 the only nonzero term is pair (0, k-1), which the
+    /// whipping order scales by E^0, so P*(s)_i = s_0^T P_i s_{k-1} = P2_i[0][0].
+    fn crafted_s_vector(params: &MayoVariantParams) -> GFVector {
+        let mut s_vector = vec![GFElement(0); params.k * params.n];
+        s_vector[0] = GFElement(1);
+        s_vector[(params.k - 1) * params.n + params.vinegar_vars()] = GFElement(1);
+        s_vector
+    }
+
+    /// Builds an epk under which a fixed signature on `message` verifies.
+    /// s is `crafted_s_vector` and P1 = P3 = 0, so P*(s)_i = P2_i[0][0]; setting P2_i[0][0] = t_i
+    /// (the high nibble of each P2_i's first byte) makes the pair valid.
+    fn crafted_valid_key_and_signature(message: &MsgTypeForTest, params_enum: &MayoParams) -> (EpkTypeForTest, SigTypeForTest) {
+        let params = params_enum.variant();
+        let mut sig_bytes = encode_s_vector(&crafted_s_vector(params), params);
+        sig_bytes.extend_from_slice(&vec![0x3Cu8; params.salt_bytes]);
+        let signature = SigTypeForTest(sig_bytes);
+        let (_, t_vector) = decode_signature_and_target(message, &signature, None, params_enum).unwrap();
+        let mut epk_bytes = vec![0u8; params.epk_bytes()];
+        let p2_mat_bytes = params.p2_bytes / params.m;
+        for (i, t_i) in t_vector.iter().enumerate() {
+            epk_bytes[params.p1_bytes + i * p2_mat_bytes] = t_i.0 << 4;
+        }
+        (EpkTypeForTest(epk_bytes), signature)
+    }
+
+    #[test]
+    fn test_corrupt_signature_fails_verification() {
+        for params_enum in MayoParams::all_variants() {
+            let message = MsgTypeForTest(b"negative test".to_vec());
+            let (epk, signature) = crafted_valid_key_and_signature(&message, &params_enum);
+            let corrupted = corrupt_signature(&signature);
+            assert_eq!(corrupted.0.len(), signature.0.len());
+            assert_eq!(verify_signature(&epk, &message, &signature, &params_enum), Ok(true));
+            assert_eq!(verify_signature(&epk, &message, &corrupted, &params_enum), Ok(false));
+        }
+    }
+
+    #[test]
+    fn test_tagged_signature_identifies_variant() {
+        let params_enum = MayoParams::mayo2();
+        let message = MsgTypeForTest(b"variant from the signature".to_vec());
+        let (epk, signature) = crafted_valid_key_and_signature(&message, &params_enum);
+        let tagged = signature.to_tagged_bytes(&params_enum);
+
+        let detected = crate::api::detect_variant_from_tagged_signature(&tagged).unwrap();
+        assert_eq!(detected, params_enum);
+        let (decoded, decoded_params) = SigTypeForTest::from_tagged_bytes(&tagged).unwrap();
+        assert_eq!(decoded, signature);
+        assert_eq!(verify_signature(&epk, &message, &decoded, &decoded_params), Ok(true));
+
+        assert_eq!(crate::api::detect_variant_from_tagged_signature(&tagged[..tagged.len() - 1]), Err(MayoError::InvalidSignatureLength("Signature has incorrect length")));
+        assert_eq!(SigTypeForTest::from_tagged_bytes(&[]), Err(MayoError::DecodeError("Tagged signature is empty")));
+        let mut retagged = tagged.clone();
+        retagged[0] = MayoParams::mayo1().variant_id();
+        assert!(SigTypeForTest::from_tagged_bytes(&retagged).is_err(), "A MAYO2 signature must not parse as MAYO1");
+    }
+
+    #[test]
+    fn test_streaming_verifier_matches_one_shot() {
+        let params_enum = MayoParams::mayo1();
+        let message = MsgTypeForTest((0..1000u32).map(|i| (i % 251) as u8).collect());
+        let (epk, signature) = crafted_valid_key_and_signature(&message, &params_enum);
+        assert_eq!(verify_signature(&epk, &message, &signature, &params_enum), Ok(true));
+
+        for chunk_len in [1, 7, 64, 1000] {
+            let mut verifier = StreamingVerifier::new(&epk, &signature, &params_enum).unwrap();
+            for chunk in message.0.chunks(chunk_len) {
+                verifier.update(chunk);
+            }
+            assert_eq!(verifier.finalize(), Ok(true), "chunk length {}", chunk_len);
+        }
+
+        let corrupted = corrupt_signature(&signature);
+        let mut verifier = StreamingVerifier::new(&epk, &corrupted, &params_enum).unwrap();
+        verifier.update(&message.0);
+        assert_eq!(verifier.finalize(), verify_signature(&epk, &message, &corrupted, &params_enum));
+
+        let mut truncated = StreamingVerifier::new(&epk, &signature, &params_enum).unwrap();
+        truncated.update(&message.0[..999]);
+        assert_eq!(truncated.finalize(), Ok(false));
+        assert!(StreamingVerifier::new(&epk, &SigTypeForTest(vec![0u8; 3]), &params_enum).is_err());
+    }
+
+    #[test]
+    fn test_zero_digest_bytes_rejected_before_hashing() {
+        let mut variant = *MayoParams::mayo1().variant();
+        variant.digest_bytes = 0;
+        let params_enum = MayoParams::MAYO1(variant);
+        let err = Err(MayoError::InvalidParameters("Invalid parameters: digest_bytes must be greater than zero"));
+
+        // Malformed key and signature: validation must fire before they are decoded or hashed.
+        let message = MsgTypeForTest(b"misconfigured".to_vec());
+        let signature = SigTypeForTest(vec![0u8; 3]);
+        assert_eq!(verify_signature(&EpkTypeForTest(vec![]), &message, &signature, &params_enum), err);
+        assert_eq!(verify_with_matrices(&[], &[], &[], &message, &signature, &params_enum), err);
+    }
+
+    #[test]
+    fn test_evaluate_public_map_reproduces_verdict() {
+        let params_enum = MayoParams::mayo1();
+        let message = MsgTypeForTest(b"external verifier".to_vec());
+        let (epk, signature) = crafted_valid_key_and_signature(&message, &params_enum);
+
+        for candidate in [signature.clone(), corrupt_signature(&signature)] {
+            let y = evaluate_public_map(&epk, &candidate, &params_enum).unwrap();
+            assert_eq!(y.len(), params_enum.m());
+            let (_, t_vector) = decode_signature_and_target(&message, &candidate, None, &params_enum).unwrap();
+            assert_eq!(Ok(y == t_vector), verify_signature(&epk, &message, &candidate, &params_enum));
+        }
+        assert!(verify_signature(&epk, &message, &signature, &params_enum).unwrap());
+
+        assert_eq!(evaluate_public_map(&epk, &SigTypeForTest(vec![0u8; 3]), &params_enum), Err(MayoError::InvalidSignatureLength("Signature has incorrect length")));
+    }
+
+    #[test]
+    fn test_verify_expanded_cross_checks_claimed_y() {
+        use crate::sign::sign_message_expanded;
+
+        let params_enum = MayoParams::mayo1();
+        let (csk, cpk) = compact_key_gen(&params_enum).unwrap();
+        let esk = crate::keygen::expand_sk(&csk, &params_enum).unwrap();
+        let epk = expand_pk(&cpk, &params_enum).unwrap();
+        let message = MsgTypeForTest(b"audited signature".to_vec());
+
+        let expanded = sign_message_expanded(&esk, &message, &SignatureOptions::default(), &params_enum).unwrap();
+        assert_eq!(expanded.y, evaluate_public_map(&epk, &expanded.signature, &params_enum).unwrap());
+        assert_eq!(verify_expanded(&epk, &message, &expanded, &params_enum), Ok(true));
+        assert_eq!(verify_expanded(&epk, &MsgTypeForTest(b"other message".to_vec()), &expanded, &params_enum), Ok(false));
+
+        // The signature itself still verifies, but the claimed evaluation no longer matches.
+        let mut tampered = expanded.clone();
+        tampered.y[0] = gf16_add(tampered.y[0], GFElement(1));
+        assert_eq!(verify_signature(&epk, &message, &tampered.signature, &params_enum), Ok(true));
+        assert_eq!(verify_expanded(&epk, &message, &tampered, &params_enum), Ok(false));
+
+        tampered.y.pop();
+        assert_eq!(verify_expanded(&epk, &message, &tampered, &params_enum), Err(MayoError::InvalidInput("Claimed public map output y has incorrect length")));
+    }
+
+    #[test]
+    fn test_verify_batch_preserves_order() {
+        let params_enum = MayoParams::mayo1();
+        let message = MsgTypeForTest(b"batch member".to_vec());
+        let (epk, signature) = crafted_valid_key_and_signature(&message, &params_enum);
+
+        let valid_indices = [0, 7, 8, 31, 100, 127];
+        let items: Vec<(MsgTypeForTest, SigTypeForTest)> = (0..128).map(|i| {
+            if valid_indices.contains(&i) {
+                (message.clone(), signature.clone())
+            } else if i % 2 == 0 {
+                (message.clone(), corrupt_signature(&signature))
+            } else {
+                (MsgTypeForTest(format!("other message {}", i).into_bytes()), signature.clone())
+            }
+        }).collect();
+
+        let results = verify_batch(&epk, &items, &params_enum).unwrap();
+        assert_eq!(results.len(), items.len());
+        for (i, result) in results.iter().enumerate() {
+            assert_eq!(*result, Ok(valid_indices.contains(&i)), "Wrong verdict at index {}", i);
+        }
+
+        // A malformed item only affects its own slot.
+        let mut with_bad_item = items.clone();
+        with_bad_item[5].1 = SigTypeForTest(vec![0u8; 3]);
+        let results = verify_batch(&epk, &with_bad_item, &params_enum).unwrap();
+        assert_eq!(results[5], Err(MayoError::InvalidSignatureLength("Signature has incorrect length")));
+        assert_eq!(results[7], Ok(true));
+
+        assert_eq!(verify_batch(&EpkTypeForTest(vec![0u8; 5]), &items, &params_enum), Err(MayoError::InvalidKeyLength("Expanded public key has incorrect length")));
+    }
+
+    #[test]
+    fn test_key_bound_signature_rejected_under_other_key() {
+        let params_enum = MayoParams::mayo1();
+        let params = params_enum.variant();
+        let (v, o) = (params.vinegar_vars(), params.oil_vars());
+        let message = MsgTypeForTest(b"key binding".to_vec());
+
+        // See `crafted_s_vector`: with P1 = P3 = 0, P*(s)_i = P2_i[0][0].
+        let mut sig_bytes = encode_s_vector(&crafted_s_vector(params), params);
+        sig_bytes.extend_from_slice(&vec![0x3Cu8; params.salt_bytes]);
+        let signature = SigTypeForTest(sig_bytes);
+
+        // Signed under key A: the target is derived with A's fingerprint.
+        let fingerprint_a = public_key_fingerprint(&EpkTypeForTest(vec![0xA5; params.epk_bytes()]), &params_enum);
+        let (_, t_bound) = decode_signature_and_target(&message, &signature, Some(&fingerprint_a), &params_enum).unwrap();
+        let (_, t_unbound) = decode_signature_and_target(&message, &signature, None, &params_enum).unwrap();
+        assert_ne!(t_bound, t_unbound);
+
+        // Key B's matrices satisfy P*(s) = t for A's bound target.
+        let p1 = vec![GFMatrix::zero(v, v); params.m];
+        let p3 = vec![GFMatrix::zero(o, o); params.m];
+        let mut p2 = vec![GFMatrix::zero(v, o); params.m];
+        for i in 0..params.m {
+            p2[i].set_val(0, 0, t_bound[i]);
+        }
+        assert_eq!(verify_core(&p1, &p2, &p3, &message, &signature, Some(&fingerprint_a), &params_enum), Ok(true));
+
+        // Verifying under key B binds B's fingerprint instead, so the signature is rejected.
+        let mut epk_b_bytes = vec![0u8; params.p1_bytes];
+        for p2_i in &p2 {
+            epk_b_bytes.extend_from_slice(&crate::codec::encode_gf_elements(&p2_i.data));
+        }
+        epk_b_bytes.extend_from_slice(&vec![0u8; params.p3_bytes]);
+        let epk_b = EpkTypeForTest(epk_b_bytes);
+        assert_eq!(decode_epk_matrices(&epk_b, params), Ok((p1, p2, p3)));
+        let bound = SignatureOptions { bind_public_key: true };
+        assert_eq!(verify_signature_with_options(&epk_b, &message, &signature, &bound, &params_enum), Ok(false));
+    }
+
+    // TODO: More detailed structural tests once compute_unwhipped_p_s is implemented.
     // These tests would involve:
-    // 1. Mocking or providing a test implementation for compute_p_star_s.
+    // 1. Mocking or providing a test implementation for compute_unwhipped_p_s.
     // 2. Scenario 1: Test verification success:
-    //    - Have compute_p_star_s return a y_computed_vector that matches the t_vector derived in the test.
+    //    - Have compute_unwhipped_p_s return a y_computed_vector that matches the t_vector derived in the test.
     //    - Assert that verify_signature returns Ok(true).
     // 3. Scenario 2: Test verification failure (y_computed mismatch):
-    //    - Have compute_p_star_s return a y_computed_vector that *does not* match the t_vector.
+    //    - Have compute_unwhipped_p_s return a y_computed_vector that *does not* match the t_vector.
     //    - Assert that verify_signature returns Ok(false).
     // These tests verify the comparison logic in verify_signature.
 
     // TODO: Implement Known Answer Tests (KATs) for verify_signature 
-    // once compute_p_star_s is fully implemented.
+    // once compute_unwhipped_p_s is fully implemented.
     // These tests will use official MAYO test vectors.
 }
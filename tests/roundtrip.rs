@@ -0,0 +1,57 @@
+//! End-to-end sign/verify round trips for every shipped variant:
+//! compact_key_gen -> expand_sk -> sign_message, then expand_pk -> verify_signature.
+//! Guards against sign and verify drifting apart on the public map.
+
+use identity::keygen::{compact_key_gen, expand_pk, expand_sk};
+use identity::params::MayoParams;
+use identity::sign::sign_message;
+use identity::types::{ExpandedPublicKey, Message, Signature};
+use identity::verify::verify_signature;
+
+/// Generates a key pair for `params_enum` and signs `message` with it.
+fn signed_message(message: &Message, params_enum: &MayoParams) -> (ExpandedPublicKey, Signature) {
+    let (csk, cpk) = compact_key_gen(params_enum).unwrap();
+    let esk = expand_sk(&csk, params_enum).unwrap();
+    let signature = sign_message(&esk, message, params_enum).unwrap();
+    let epk = expand_pk(&cpk, params_enum).unwrap();
+    (epk, signature)
+}
+
+#[test]
+fn test_roundtrip_accepts_signed_message() {
+    for params_enum in [MayoParams::mayo1(), MayoParams::mayo2()] {
+        let message = Message(b"round trip".to_vec());
+        let (epk, signature) = signed_message(&message, &params_enum);
+        assert_eq!(signature.0.len(), params_enum.variant().sig_bytes());
+        assert_eq!(verify_signature(&epk, &message, &signature, &params_enum), Ok(true));
+    }
+}
+
+#[test]
+fn test_roundtrip_rejects_flipped_message_byte() {
+    for params_enum in [MayoParams::mayo1(), MayoParams::mayo2()] {
+        let message = Message(b"round trip".to_vec());
+        let (epk, signature) = signed_message(&message, &params_enum);
+
+        let mut flipped = message.clone();
+        flipped.0[3] ^= 0x01;
+        assert_eq!(verify_signature(&epk, &flipped, &signature, &params_enum), Ok(false));
+    }
+}
+
+#[test]
+fn test_roundtrip_rejects_flipped_signature_bytes() {
+    for (name, params_enum) in [("MAYO1", MayoParams::mayo1()), ("MAYO2", MayoParams::mayo2())] {
+        let params = params_enum.variant();
+        let message = Message(b"round trip".to_vec());
+        let (epk, signature) = signed_message(&message, &params_enum);
+
+        // One byte in the middle of s, and the last byte of the salt.
+        for (region, index) in [("s", params.s_bytes() / 2), ("salt", params.sig_bytes() - 1)] {
+            let mut corrupted = signature.clone();
+            corrupted.0[index] ^= 0x01;
+            assert_eq!(verify_signature(&epk, &message, &corrupted, &params_enum), Ok(false),
+                       "{}: signature accepted after a byte flip in its {} region", name, region);
+        }
+    }
+}
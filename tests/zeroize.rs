@@ -0,0 +1,85 @@
+//! Checks that secret key material is zeroed when it is dropped.
+//! Runs as its own test binary because it installs a `#[global_allocator]`, which would
+//! otherwise sit under every unit test in the crate.
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+use identity::keygen::{compact_key_gen, expand_sk};
+use identity::params::MayoParams;
+use identity::sign::SigningMatrices;
+use identity::types::{ExpandedSecretKey, Salt, SeedSK};
+
+// Observes heap buffers as they are freed, which is the only sound point to check that
+// a destructor zeroed them: once deallocated, the allocator may reuse or overwrite them.
+struct WipeCheckingAlloc;
+
+thread_local! {
+    // (address being watched, whether it was all zeros when freed)
+    static WATCHED: Cell<(usize, Option<bool>)> = const { Cell::new((0, None)) };
+}
+
+unsafe impl GlobalAlloc for WipeCheckingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let _ = WATCHED.try_with(|watched| {
+            if watched.get().0 == ptr as usize {
+                // SAFETY: `ptr` is a live allocation of `layout.size()` bytes until it is freed below.
+                let bytes = std::slice::from_raw_parts(ptr, layout.size());
+                watched.set((0, Some(bytes.iter().all(|&b| b == 0))));
+            }
+        });
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: WipeCheckingAlloc = WipeCheckingAlloc;
+
+/// Drops `value`, whose heap buffer starts at `buffer`, and reports whether the buffer
+/// was all zeros when it was freed.
+fn freed_as_zeros<T>(value: T, buffer: *const u8) -> bool {
+    WATCHED.with(|watched| watched.set((buffer as usize, None)));
+    drop(value);
+    WATCHED.with(|watched| watched.get().1).expect("watched buffer was not freed")
+}
+
+#[test]
+fn test_secret_material_is_zeroed_on_drop() {
+    // Sanity check: an ordinary buffer is freed with its contents intact.
+    let plain = vec![0xA5u8; 32];
+    let ptr = plain.as_ptr();
+    assert!(!freed_as_zeros(plain, ptr));
+
+    let params_enum = MayoParams::mayo1();
+    let (csk, _) = compact_key_gen(&params_enum).unwrap();
+    let esk = expand_sk(&csk, &params_enum).unwrap();
+
+    // The decoded L_i of a signing key are secret too.
+    let params = params_enum.variant();
+    let p1_start = params.sk_seed_bytes + params.o_bytes;
+    let p1_end = p1_start + params.p1_bytes;
+    let matrices = SigningMatrices::decode(&esk.0[p1_start..p1_end], &esk.0[p1_end..], params).unwrap();
+    let ptr = matrices.l[0].data.as_ptr() as *const u8;
+    assert!(freed_as_zeros(matrices, ptr), "SigningMatrices::l was not zeroed on drop");
+
+    let ptr = esk.0.as_ptr();
+    assert!(freed_as_zeros(esk, ptr), "ExpandedSecretKey was not zeroed on drop");
+    let ptr = csk.0.as_ptr();
+    assert!(freed_as_zeros(csk, ptr), "CompactSecretKey was not zeroed on drop");
+
+    let seedsk = SeedSK(vec![0x5Au8; 24]);
+    let ptr = seedsk.0.as_ptr();
+    assert!(freed_as_zeros(seedsk, ptr), "SeedSK was not zeroed on drop");
+    let salt = Salt(vec![0x3Cu8; 24]);
+    let ptr = salt.0.as_ptr();
+    assert!(freed_as_zeros(salt, ptr), "Salt was not zeroed on drop");
+
+    // The explicit wipe leaves the key usable as an all-zero buffer of the same length.
+    let mut esk = ExpandedSecretKey(vec![0xFFu8; 8]);
+    esk.wipe();
+    assert_eq!(esk.0, vec![0u8; 8]);
+}